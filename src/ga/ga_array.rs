@@ -0,0 +1,711 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! 2D and 3D Array Genomes
+//!
+//! GALib's GA2DArrayGenome and GA3DArrayGenome: fixed size grids of user
+//! defined alleles (see `GAArrayAllele`), stored row by row (and layer by
+//! layer).
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Array Allele
+///
+/// Value of a cell of an array genome. Cells are compared with `==` to
+/// measure the distance between genomes (see `GAIndividual::compare`).
+pub trait GAArrayAllele: Clone + PartialEq
+{
+    /// Random allele, for initialization.
+    fn random(rng_ctx: &mut GARandomCtx) -> Self;
+
+    /// Change the allele, for mutation. Replaces it with a random allele by
+    /// default.
+    fn mutate(&mut self, rng_ctx: &mut GARandomCtx)
+    {
+        *self = Self::random(rng_ctx);
+    }
+}
+
+/// Array Crossover
+///
+/// Crossover operator used by `GAIndividual::crossover` on array genomes.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAArrayCrossover
+{
+    /// The cells of a random block (sub-rectangle or sub-cube) come from
+    /// the second parent, the rest from the first one.
+    #[default]
+    Block,
+    /// Each cell comes from either parent with equal probability.
+    Uniform,
+}
+
+/// Objective function of a 2D Array Genome. Its result is the raw score
+/// (and the fitness) of the genome.
+pub type GA2DArrayObjective<A> = Arc<dyn Fn(&GA2DArrayGenome<A>) -> f32 + Send + Sync>;
+
+/// Objective function of a 3D Array Genome. Its result is the raw score
+/// (and the fitness) of the genome.
+pub type GA3DArrayObjective<A> = Arc<dyn Fn(&GA3DArrayGenome<A>) -> f32 + Send + Sync>;
+
+/// 2D Array Genome
+///
+/// Mutation changes each cell with probability `p_mutation` (see
+/// `GAArrayAllele::mutate`).
+#[derive(Clone)]
+pub struct GA2DArrayGenome<A: GAArrayAllele>
+{
+    cells: Vec<A>,
+    width: usize,
+    height: usize,
+    crossover: GAArrayCrossover,
+    objective: GA2DArrayObjective<A>,
+    raw: f32,
+    fitness: f32,
+}
+impl<A: GAArrayAllele> GA2DArrayGenome<A>
+{
+    /// `width` x `height` genome, with `cells` given row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<A>, objective: GA2DArrayObjective<A>) -> GA2DArrayGenome<A>
+    {
+        assert_eq!(cells.len(), width * height);
+        GA2DArrayGenome
+        {
+            cells,
+            width,
+            height,
+            crossover: GAArrayCrossover::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// `width` x `height` genome with random alleles.
+    pub fn random(width: usize, height: usize, objective: GA2DArrayObjective<A>, rng_ctx: &mut GARandomCtx) -> GA2DArrayGenome<A>
+    {
+        let cells = (0..width * height).map(|_| A::random(rng_ctx)).collect();
+        GA2DArrayGenome::new(width, height, cells, objective)
+    }
+
+    pub fn width(&self) -> usize
+    {
+        self.width
+    }
+
+    pub fn height(&self) -> usize
+    {
+        self.height
+    }
+
+    /// Cells, row by row.
+    pub fn cells(&self) -> &[A]
+    {
+        &self.cells
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &A
+    {
+        assert!(x < self.width && y < self.height);
+        &self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, allele: A)
+    {
+        assert!(x < self.width && y < self.height);
+        self.cells[y * self.width + x] = allele;
+    }
+
+    pub fn crossover_operator(&self) -> GAArrayCrossover
+    {
+        self.crossover
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GAArrayCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    // Copy of self, without scores, with 'cells'.
+    fn child(&self, cells: Vec<A>) -> GA2DArrayGenome<A>
+    {
+        GA2DArrayGenome
+        {
+            cells,
+            width: self.width,
+            height: self.height,
+            crossover: self.crossover,
+            objective: self.objective.clone(),
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+}
+impl<A: GAArrayAllele> GAIndividual for GA2DArrayGenome<A>
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GA2DArrayGenome<A>, rng_ctx: &mut GARandomCtx) -> Box<GA2DArrayGenome<A>>
+    {
+        assert!(self.width == other.width && self.height == other.height);
+        let cells = array_crossover(self.crossover, &self.cells, &other.cells, &[self.width, self.height], rng_ctx);
+        Box::new(self.child(cells))
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        array_mutate(&mut self.cells, p_mutation, rng_ctx)
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        let score = (self.objective)(self);
+        self.raw = score;
+        self.fitness = score;
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Fraction of cells holding different alleles. Arrays of different
+    /// dimensions can't be compared.
+    fn compare(&self, other: &GA2DArrayGenome<A>) -> f32
+    {
+        if self.width != other.width || self.height != other.height
+        {
+            return -1.0;
+        }
+        array_compare(&self.cells, &other.cells)
+    }
+}
+impl<A: GAArrayAllele + Send> GAParallelIndividual for GA2DArrayGenome<A>
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        self.evaluate(&mut ());
+    }
+}
+impl<A: GAArrayAllele> PartialEq for GA2DArrayGenome<A>
+{
+    fn eq(&self, other: &GA2DArrayGenome<A>) -> bool
+    {
+        self.width == other.width && self.height == other.height && self.cells == other.cells
+    }
+}
+impl<A: GAArrayAllele + fmt::Debug> fmt::Debug for GA2DArrayGenome<A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GA2DArrayGenome({}x{} {:?}) raw: {} fitness: {}",
+               self.width, self.height, self.cells, self.raw, self.fitness)
+    }
+}
+
+/// 3D Array Genome
+///
+/// Mutation changes each cell with probability `p_mutation` (see
+/// `GAArrayAllele::mutate`).
+#[derive(Clone)]
+pub struct GA3DArrayGenome<A: GAArrayAllele>
+{
+    cells: Vec<A>,
+    width: usize,
+    height: usize,
+    depth: usize,
+    crossover: GAArrayCrossover,
+    objective: GA3DArrayObjective<A>,
+    raw: f32,
+    fitness: f32,
+}
+impl<A: GAArrayAllele> GA3DArrayGenome<A>
+{
+    /// `width` x `height` x `depth` genome, with `cells` given layer by
+    /// layer, each layer row by row.
+    pub fn new(width: usize, height: usize, depth: usize, cells: Vec<A>, objective: GA3DArrayObjective<A>) -> GA3DArrayGenome<A>
+    {
+        assert_eq!(cells.len(), width * height * depth);
+        GA3DArrayGenome
+        {
+            cells,
+            width,
+            height,
+            depth,
+            crossover: GAArrayCrossover::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// `width` x `height` x `depth` genome with random alleles.
+    pub fn random(width: usize, height: usize, depth: usize, objective: GA3DArrayObjective<A>, rng_ctx: &mut GARandomCtx) -> GA3DArrayGenome<A>
+    {
+        let cells = (0..width * height * depth).map(|_| A::random(rng_ctx)).collect();
+        GA3DArrayGenome::new(width, height, depth, cells, objective)
+    }
+
+    pub fn width(&self) -> usize
+    {
+        self.width
+    }
+
+    pub fn height(&self) -> usize
+    {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize
+    {
+        self.depth
+    }
+
+    /// Cells, layer by layer, each layer row by row.
+    pub fn cells(&self) -> &[A]
+    {
+        &self.cells
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> &A
+    {
+        assert!(x < self.width && y < self.height && z < self.depth);
+        &self.cells[(z * self.height + y) * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, allele: A)
+    {
+        assert!(x < self.width && y < self.height && z < self.depth);
+        self.cells[(z * self.height + y) * self.width + x] = allele;
+    }
+
+    pub fn crossover_operator(&self) -> GAArrayCrossover
+    {
+        self.crossover
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GAArrayCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    // Copy of self, without scores, with 'cells'.
+    fn child(&self, cells: Vec<A>) -> GA3DArrayGenome<A>
+    {
+        GA3DArrayGenome
+        {
+            cells,
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            crossover: self.crossover,
+            objective: self.objective.clone(),
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+}
+impl<A: GAArrayAllele> GAIndividual for GA3DArrayGenome<A>
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GA3DArrayGenome<A>, rng_ctx: &mut GARandomCtx) -> Box<GA3DArrayGenome<A>>
+    {
+        assert!(self.width == other.width && self.height == other.height && self.depth == other.depth);
+        let cells = array_crossover(self.crossover, &self.cells, &other.cells,
+                                    &[self.width, self.height, self.depth], rng_ctx);
+        Box::new(self.child(cells))
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        array_mutate(&mut self.cells, p_mutation, rng_ctx)
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        let score = (self.objective)(self);
+        self.raw = score;
+        self.fitness = score;
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Fraction of cells holding different alleles. Arrays of different
+    /// dimensions can't be compared.
+    fn compare(&self, other: &GA3DArrayGenome<A>) -> f32
+    {
+        if self.width != other.width || self.height != other.height || self.depth != other.depth
+        {
+            return -1.0;
+        }
+        array_compare(&self.cells, &other.cells)
+    }
+}
+impl<A: GAArrayAllele + Send> GAParallelIndividual for GA3DArrayGenome<A>
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        self.evaluate(&mut ());
+    }
+}
+impl<A: GAArrayAllele> PartialEq for GA3DArrayGenome<A>
+{
+    fn eq(&self, other: &GA3DArrayGenome<A>) -> bool
+    {
+        self.width == other.width && self.height == other.height && self.depth == other.depth &&
+        self.cells == other.cells
+    }
+}
+impl<A: GAArrayAllele + fmt::Debug> fmt::Debug for GA3DArrayGenome<A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GA3DArrayGenome({}x{}x{} {:?}) raw: {} fitness: {}",
+               self.width, self.height, self.depth, self.cells, self.raw, self.fitness)
+    }
+}
+
+/// 2D Array Genome Factory
+///
+/// Creates genomes with random alleles.
+pub struct GA2DArrayFactory<A: GAArrayAllele>
+{
+    width: usize,
+    height: usize,
+    objective: GA2DArrayObjective<A>,
+    crossover: GAArrayCrossover,
+}
+impl<A: GAArrayAllele> GA2DArrayFactory<A>
+{
+    pub fn new<F>(width: usize, height: usize, objective: F) -> GA2DArrayFactory<A>
+        where F: Fn(&GA2DArrayGenome<A>) -> f32 + Send + Sync + 'static
+    {
+        GA2DArrayFactory { width, height, objective: Arc::new(objective), crossover: GAArrayCrossover::default() }
+    }
+
+    /// Crossover operator of the genomes created from now on.
+    pub fn set_crossover_operator(&mut self, crossover: GAArrayCrossover)
+    {
+        self.crossover = crossover;
+    }
+}
+impl<A: GAArrayAllele> GAFactory<GA2DArrayGenome<A>> for GA2DArrayFactory<A>
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GA2DArrayGenome<A>>
+    {
+        let mut genomes = vec![];
+        for _ in 0..n
+        {
+            let mut genome = GA2DArrayGenome::random(self.width, self.height, self.objective.clone(), rng_ctx);
+            genome.set_crossover_operator(self.crossover);
+            genomes.push(genome);
+        }
+
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+/// 3D Array Genome Factory
+///
+/// Creates genomes with random alleles.
+pub struct GA3DArrayFactory<A: GAArrayAllele>
+{
+    width: usize,
+    height: usize,
+    depth: usize,
+    objective: GA3DArrayObjective<A>,
+    crossover: GAArrayCrossover,
+}
+impl<A: GAArrayAllele> GA3DArrayFactory<A>
+{
+    pub fn new<F>(width: usize, height: usize, depth: usize, objective: F) -> GA3DArrayFactory<A>
+        where F: Fn(&GA3DArrayGenome<A>) -> f32 + Send + Sync + 'static
+    {
+        GA3DArrayFactory { width, height, depth, objective: Arc::new(objective), crossover: GAArrayCrossover::default() }
+    }
+
+    /// Crossover operator of the genomes created from now on.
+    pub fn set_crossover_operator(&mut self, crossover: GAArrayCrossover)
+    {
+        self.crossover = crossover;
+    }
+}
+impl<A: GAArrayAllele> GAFactory<GA3DArrayGenome<A>> for GA3DArrayFactory<A>
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GA3DArrayGenome<A>>
+    {
+        let mut genomes = vec![];
+        for _ in 0..n
+        {
+            let mut genome = GA3DArrayGenome::random(self.width, self.height, self.depth, self.objective.clone(), rng_ctx);
+            genome.set_crossover_operator(self.crossover);
+            genomes.push(genome);
+        }
+
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+// Cells of a child of 'cells' and 'other', arrays with dimensions 'dims'
+// (fastest changing first).
+fn array_crossover<A: Clone>(crossover: GAArrayCrossover, cells: &[A], other: &[A],
+                             dims: &[usize], rng_ctx: &mut GARandomCtx) -> Vec<A>
+{
+    match crossover
+    {
+        GAArrayCrossover::Block =>
+        {
+            // [from, to) along each dimension.
+            let block : Vec<(usize, usize)> = dims.iter().map(|&d| {
+                let a = rng_ctx.gen_range(0, d + 1);
+                let b = rng_ctx.gen_range(0, d + 1);
+                (a.min(b), a.max(b))
+            }).collect();
+
+            (0..cells.len()).map(|i| {
+                let mut rest = i;
+                let mut inside = true;
+                for (&d, &(from, to)) in dims.iter().zip(&block)
+                {
+                    let c = rest % d;
+                    rest /= d;
+                    inside &= c >= from && c < to;
+                }
+                if inside { other[i].clone() } else { cells[i].clone() }
+            }).collect()
+        },
+        GAArrayCrossover::Uniform =>
+        {
+            cells.iter().zip(other)
+                 .map(|(c, o)| if rng_ctx.test_value(0.5f32) { o.clone() } else { c.clone() })
+                 .collect()
+        },
+    }
+}
+
+// Mutate each cell with probability 'p_mutation'.
+fn array_mutate<A: GAArrayAllele>(cells: &mut [A], p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+{
+    if p_mutation <= 0.0
+    {
+        return 0;
+    }
+
+    let mut mutations = 0;
+    for c in cells
+    {
+        if rng_ctx.test_value(p_mutation)
+        {
+            c.mutate(rng_ctx);
+            mutations += 1;
+        }
+    }
+    mutations
+}
+
+// Fraction of cells holding different alleles, for arrays of the same
+// dimensions.
+fn array_compare<A: GAArrayAllele>(cells: &[A], other_cells: &[A]) -> f32
+{
+    if cells.is_empty()
+    {
+        return 0.0;
+    }
+
+    let different = cells.iter().zip(other_cells.iter()).filter(|&(a, b)| a != b).count();
+    different as f32 / cells.len() as f32
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    impl GAArrayAllele for bool
+    {
+        fn random(rng_ctx: &mut GARandomCtx) -> bool
+        {
+            rng_ctx.test_value(0.5f32)
+        }
+
+        fn mutate(&mut self, _: &mut GARandomCtx)
+        {
+            *self = !*self;
+        }
+    }
+
+    fn zero_2d() -> GA2DArrayObjective<bool>
+    {
+        Arc::new(|_: &GA2DArrayGenome<bool>| 0.0)
+    }
+
+    fn zero_3d() -> GA3DArrayObjective<bool>
+    {
+        Arc::new(|_: &GA3DArrayGenome<bool>| 0.0)
+    }
+
+    #[test]
+    fn indexing()
+    {
+        ga_test_setup("ga_array::indexing");
+        let mut g = GA2DArrayGenome::new(3, 2, vec![false; 6], zero_2d());
+        g.set(2, 1, true);
+        assert!(*g.get(2, 1));
+        assert_eq!(g.cells().iter().position(|&c| c), Some(5));
+
+        let mut g = GA3DArrayGenome::new(2, 3, 4, vec![false; 24], zero_3d());
+        g.set(1, 2, 3, true);
+        assert!(*g.get(1, 2, 3));
+        assert_eq!(g.cells().iter().position(|&c| c), Some(23));
+        g.set(1, 0, 1, true);
+        assert_eq!(g.cells().iter().position(|&c| c), Some(7));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn block_crossover()
+    {
+        ga_test_setup("ga_array::block_crossover");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+
+        let mom = GA2DArrayGenome::new(6, 5, vec![false; 30], zero_2d());
+        let dad = GA2DArrayGenome::new(6, 5, vec![true; 30], zero_2d());
+        for _ in 0..30
+        {
+            // Cells from dad form a rectangle.
+            let c = mom.crossover(&dad, &mut rng_ctx);
+            let cells : Vec<(usize, usize)> = (0..5).flat_map(|y| (0..6).map(move |x| (x, y)))
+                                                    .filter(|&(x, y)| *c.get(x, y))
+                                                    .collect();
+            if let (Some(&(x0, y0)), Some(&(x1, y1))) = (cells.first(), cells.last())
+            {
+                assert_eq!(cells.len(), (x1 - x0 + 1) * (y1 - y0 + 1));
+            }
+        }
+
+        let mom = GA3DArrayGenome::new(3, 4, 5, vec![false; 60], zero_3d());
+        let dad = GA3DArrayGenome::new(3, 4, 5, vec![true; 60], zero_3d());
+        for _ in 0..30
+        {
+            // Cells from dad form a box.
+            let c = mom.crossover(&dad, &mut rng_ctx);
+            let cells : Vec<(usize, usize, usize)> = (0..60).map(|i| (i % 3, (i / 3) % 4, i / 12))
+                                                            .filter(|&(x, y, z)| *c.get(x, y, z))
+                                                            .collect();
+            if !cells.is_empty()
+            {
+                let extent = |coord: &dyn Fn(&(usize, usize, usize)) -> usize|
+                {
+                    cells.iter().map(coord).max().unwrap() - cells.iter().map(coord).min().unwrap() + 1
+                };
+                assert_eq!(cells.len(), extent(&|p| p.0) * extent(&|p| p.1) * extent(&|p| p.2));
+            }
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn uniform_crossover_and_mutation()
+    {
+        ga_test_setup("ga_array::uniform_crossover_and_mutation");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mut mom = GA2DArrayGenome::new(10, 10, vec![false; 100], zero_2d());
+        mom.set_crossover_operator(GAArrayCrossover::Uniform);
+        let dad = GA2DArrayGenome::new(10, 10, vec![true; 100], zero_2d());
+        let c = mom.crossover(&dad, &mut rng_ctx);
+        let ones = c.cells().iter().filter(|&&b| b).count();
+        assert!(ones > 0 && ones < 100);
+
+        let mut g = GA3DArrayGenome::new(4, 4, 4, vec![false; 64], zero_3d());
+        g.mutate(0.0, &mut rng_ctx);
+        assert!(g.cells().iter().all(|&b| !b));
+        g.mutate(1.0, &mut rng_ctx);
+        assert!(g.cells().iter().all(|&b| b));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn simple_ga_with_factories()
+    {
+        ga_test_setup("ga_array::simple_ga_with_factories");
+        let cfg = SimpleGeneticAlgorithmCfg {
+            d_seed : [1; 4],
+            flags : DEBUG_FLAG,
+            max_generations: 40,
+            population_size: 30,
+            probability_crossover: 0.9,
+            probability_mutation: 0.02,
+            elitism: true,
+            ..Default::default()
+        };
+
+        // Checkerboard.
+        let mut factory = GA2DArrayFactory::new(6, 6, |g: &GA2DArrayGenome<bool>|
+        {
+            (0..6).flat_map(|y| (0..6).map(move |x| (x, y)))
+                  .filter(|&(x, y)| *g.get(x, y) == ((x + y) % 2 == 0))
+                  .count() as f32
+        });
+        let mut ga = SimpleGeneticAlgorithm::new(cfg, Some(&mut factory as &mut dyn GAFactory<GA2DArrayGenome<bool>>), None);
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        assert!(ga.population().best_by_raw_score().raw() > initial_best);
+
+        // All set.
+        let mut factory = GA3DArrayFactory::new(3, 3, 3, |g: &GA3DArrayGenome<bool>|
+        {
+            g.cells().iter().filter(|&&b| b).count() as f32
+        });
+        factory.set_crossover_operator(GAArrayCrossover::Uniform);
+        let mut ga = SimpleGeneticAlgorithm::new(cfg, Some(&mut factory as &mut dyn GAFactory<GA3DArrayGenome<bool>>), None);
+        ga.initialize().unwrap();
+        assert_eq!(ga.population().size(), 30);
+        assert_eq!(ga.population().individual(0, GAPopulationSortBasis::Raw).unwrap().crossover_operator(), GAArrayCrossover::Uniform);
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        assert!(ga.population().best_by_raw_score().raw() > initial_best);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_array::compare");
+        let a = GA2DArrayGenome::new(2, 2, vec![false; 4], zero_2d());
+        assert_eq!(a.compare(&a.clone()), 0.0);
+        assert_eq!(a.compare(&GA2DArrayGenome::new(2, 2, vec![true, false, false, false], zero_2d())), 0.25);
+        assert_eq!(a.compare(&GA2DArrayGenome::new(2, 2, vec![true; 4], zero_2d())), 1.0);
+        assert_eq!(a.compare(&GA2DArrayGenome::new(4, 1, vec![false; 4], zero_2d())), -1.0);
+
+        let b = GA3DArrayGenome::new(2, 2, 2, vec![false; 8], zero_3d());
+        let mut c = b.clone();
+        c.set(1, 1, 1, true);
+        c.set(0, 1, 0, true);
+        assert_eq!(b.compare(&c), 0.25);
+        assert_eq!(b.compare(&GA3DArrayGenome::new(2, 2, 1, vec![false; 4], zero_3d())), -1.0);
+        ga_test_teardown();
+    }
+}
@@ -0,0 +1,467 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Binary to Decimal Genome
+//!
+//! GALib's GABin2DecGenome: a binary string genome whose bits encode a
+//! vector of real valued parameters. How many bits each parameter takes and
+//! the range they map to is described by a phenotype, shared by all the
+//! genomes of a run.
+use ::ga::ga_binary_string::{GABinaryStringCrossover, GABinaryStringGenome, GABinaryStringObjective};
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Objective function of a Binary to Decimal Genome. Takes the decoded
+/// parameters and returns the raw score (and the fitness) of the genome.
+pub type GABin2DecObjective = Arc<dyn Fn(&[f64]) -> f32 + Send + Sync>;
+
+/// Binary to Decimal Parameter
+///
+/// `bits` bits, read as an unsigned integer (most significant bit first),
+/// mapped linearly to [`min`, `max`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GABin2DecParameter
+{
+    pub bits: usize,
+    pub min: f64,
+    pub max: f64,
+}
+impl GABin2DecParameter
+{
+    // Largest integer the parameter's bits can hold.
+    fn max_int(&self) -> u64
+    {
+        if self.bits == 64 { u64::MAX } else { (1 << self.bits) - 1 }
+    }
+}
+
+/// Binary to Decimal Phenotype
+///
+/// Describes how the bits of a genome map to parameters. Parameters are
+/// laid out one after the other, in the order they were added.
+///
+/// With Gray coding, consecutive values of a parameter differ in a single
+/// bit.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct GABin2DecPhenotype
+{
+    parameters: Vec<GABin2DecParameter>,
+    gray: bool,
+}
+impl GABin2DecPhenotype
+{
+    pub fn new() -> GABin2DecPhenotype
+    {
+        GABin2DecPhenotype::default()
+    }
+
+    /// Phenotype that encodes its parameters with Gray coding.
+    pub fn new_gray() -> GABin2DecPhenotype
+    {
+        GABin2DecPhenotype { parameters: vec![], gray: true }
+    }
+
+    /// Add a parameter of `bits` bits (1 to 64) ranging over [`min`, `max`].
+    pub fn add(&mut self, bits: usize, min: f64, max: f64) -> &mut GABin2DecPhenotype
+    {
+        assert!(bits > 0 && bits <= 64, "Bin2Dec Phenotype - parameters take 1 to 64 bits");
+        assert!(min <= max);
+        self.parameters.push(GABin2DecParameter { bits, min, max });
+        self
+    }
+
+    pub fn parameters(&self) -> &[GABin2DecParameter]
+    {
+        &self.parameters
+    }
+
+    pub fn is_gray(&self) -> bool
+    {
+        self.gray
+    }
+
+    /// Number of parameters.
+    pub fn size(&self) -> usize
+    {
+        self.parameters.len()
+    }
+
+    /// Number of bits of a genome with this phenotype.
+    pub fn length(&self) -> usize
+    {
+        self.parameters.iter().map(|p| p.bits).sum()
+    }
+
+    /// Parameter values encoded by `bits`.
+    pub fn decode(&self, bits: &[bool]) -> Vec<f64>
+    {
+        assert_eq!(bits.len(), self.length());
+        self.decode_with(|i| bits[i])
+    }
+
+    /// Bits that encode `values`, each rounded to the closest value its
+    /// parameter can take.
+    pub fn encode(&self, values: &[f64]) -> Vec<bool>
+    {
+        assert_eq!(values.len(), self.size());
+        let mut bits = vec![];
+        for (p, &v) in self.parameters.iter().zip(values)
+        {
+            let mut int = if p.max > p.min
+            {
+                let t = ((v - p.min) / (p.max - p.min)).clamp(0.0, 1.0);
+                (t * p.max_int() as f64).round() as u64
+            }
+            else
+            {
+                0
+            };
+
+            if self.gray
+            {
+                int ^= int >> 1;
+            }
+
+            for b in (0..p.bits).rev()
+            {
+                bits.push(int & (1 << b) != 0);
+            }
+        }
+        bits
+    }
+
+    // Decode the bits returned by 'bit' for indices 0..length().
+    fn decode_with<F: Fn(usize) -> bool>(&self, bit: F) -> Vec<f64>
+    {
+        let mut values = Vec::with_capacity(self.parameters.len());
+        let mut offset = 0;
+        for p in &self.parameters
+        {
+            let mut int : u64 = 0;
+            let mut prev = false;
+            for i in 0..p.bits
+            {
+                let mut b = bit(offset + i);
+                if self.gray
+                {
+                    // Binary bit i is the xor of Gray bits 0..i.
+                    b ^= prev;
+                    prev = b;
+                }
+                int = (int << 1) | b as u64;
+            }
+            offset += p.bits;
+
+            values.push(p.min + (p.max - p.min) * int as f64 / p.max_int() as f64);
+        }
+        values
+    }
+}
+
+/// Binary to Decimal Genome
+///
+/// Crossover and mutation are those of the underlying binary string genome.
+#[derive(Clone)]
+pub struct GABin2DecGenome
+{
+    genome: GABinaryStringGenome,
+    phenotype: Arc<GABin2DecPhenotype>,
+}
+impl GABin2DecGenome
+{
+    /// Genome with all its bits unset (every parameter at its minimum).
+    pub fn new(phenotype: Arc<GABin2DecPhenotype>, objective: GABin2DecObjective) -> GABin2DecGenome
+    {
+        let bit_objective = GABin2DecGenome::bit_objective(&phenotype, objective);
+        GABin2DecGenome
+        {
+            genome: GABinaryStringGenome::new_with_objective(phenotype.length(), bit_objective),
+            phenotype,
+        }
+    }
+
+    /// Genome with random bits.
+    pub fn random(phenotype: Arc<GABin2DecPhenotype>, objective: GABin2DecObjective, rng_ctx: &mut GARandomCtx) -> GABin2DecGenome
+    {
+        let bit_objective = GABin2DecGenome::bit_objective(&phenotype, objective);
+        GABin2DecGenome
+        {
+            genome: GABinaryStringGenome::random(phenotype.length(), bit_objective, rng_ctx),
+            phenotype,
+        }
+    }
+
+    pub fn phenotype(&self) -> &GABin2DecPhenotype
+    {
+        &self.phenotype
+    }
+
+    /// Underlying binary string genome.
+    pub fn binary_string(&self) -> &GABinaryStringGenome
+    {
+        &self.genome
+    }
+
+    /// Decoded parameter values.
+    pub fn values(&self) -> Vec<f64>
+    {
+        self.phenotype.decode_with(|i| self.genome.get(i))
+    }
+
+    /// Encode `values` (see `GABin2DecPhenotype::encode`).
+    pub fn set_values(&mut self, values: &[f64])
+    {
+        for (i, b) in self.phenotype.encode(values).into_iter().enumerate()
+        {
+            self.genome.set(i, b);
+        }
+    }
+
+    pub fn crossover_operator(&self) -> GABinaryStringCrossover
+    {
+        self.genome.crossover_operator()
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GABinaryStringCrossover)
+    {
+        self.genome.set_crossover_operator(crossover);
+    }
+
+    // Objective of the underlying binary string genome: decode, then
+    // evaluate the values.
+    fn bit_objective(phenotype: &Arc<GABin2DecPhenotype>, objective: GABin2DecObjective) -> GABinaryStringObjective
+    {
+        let phenotype = phenotype.clone();
+        Arc::new(move |g: &GABinaryStringGenome| objective(&phenotype.decode_with(|i| g.get(i))))
+    }
+}
+impl GAIndividual for GABin2DecGenome
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GABin2DecGenome, rng_ctx: &mut GARandomCtx) -> Box<GABin2DecGenome>
+    {
+        Box::new(GABin2DecGenome
+        {
+            genome: *self.genome.crossover(&other.genome, rng_ctx),
+            phenotype: self.phenotype.clone(),
+        })
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        self.genome.mutate(p_mutation, rng_ctx)
+    }
+
+    fn evaluate(&mut self, evaluation_ctx: &mut ())
+    {
+        self.genome.evaluate(evaluation_ctx);
+    }
+
+    fn fitness(&self) -> f32 { self.genome.fitness() }
+    fn set_fitness(&mut self, fitness: f32) { self.genome.set_fitness(fitness); }
+    fn raw(&self) -> f32 { self.genome.raw() }
+    fn set_raw(&mut self, raw: f32) { self.genome.set_raw(raw); }
+
+    fn compare(&self, other: &GABin2DecGenome) -> f32
+    {
+        self.genome.compare(&other.genome)
+    }
+}
+impl GAParallelIndividual for GABin2DecGenome
+{
+    fn evaluate_shared(&mut self, evaluation_ctx: &())
+    {
+        self.genome.evaluate_shared(evaluation_ctx);
+    }
+}
+impl PartialEq for GABin2DecGenome
+{
+    fn eq(&self, other: &GABin2DecGenome) -> bool
+    {
+        self.genome == other.genome && self.phenotype == other.phenotype
+    }
+}
+impl fmt::Debug for GABin2DecGenome
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GABin2DecGenome({:?}) raw: {} fitness: {}", self.values(), self.raw(), self.fitness())
+    }
+}
+
+/// Binary to Decimal Genome Factory
+///
+/// Creates genomes with random bits.
+pub struct GABin2DecFactory
+{
+    phenotype: Arc<GABin2DecPhenotype>,
+    objective: GABin2DecObjective,
+    crossover: GABinaryStringCrossover,
+}
+impl GABin2DecFactory
+{
+    pub fn new<F>(phenotype: GABin2DecPhenotype, objective: F) -> GABin2DecFactory
+        where F: Fn(&[f64]) -> f32 + Send + Sync + 'static
+    {
+        GABin2DecFactory
+        {
+            phenotype: Arc::new(phenotype),
+            objective: Arc::new(objective),
+            crossover: GABinaryStringCrossover::default(),
+        }
+    }
+
+    /// Crossover operator of the genomes created from now on.
+    pub fn set_crossover_operator(&mut self, crossover: GABinaryStringCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    pub fn random_genome(&mut self, rng_ctx: &mut GARandomCtx) -> GABin2DecGenome
+    {
+        let mut genome = GABin2DecGenome::random(self.phenotype.clone(), self.objective.clone(), rng_ctx);
+        genome.set_crossover_operator(self.crossover);
+        genome
+    }
+}
+impl GAFactory<GABin2DecGenome> for GABin2DecFactory
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GABin2DecGenome>
+    {
+        let genomes = (0..n).map(|_| self.random_genome(rng_ctx)).collect();
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    fn phenotype(mut p: GABin2DecPhenotype) -> GABin2DecPhenotype
+    {
+        p.add(8, 0.0, 255.0).add(4, 0.0, 15.0).add(16, -5.0, 5.0);
+        p
+    }
+
+    #[test]
+    fn decode()
+    {
+        ga_test_setup("ga_bin2dec::decode");
+        let p = phenotype(GABin2DecPhenotype::new());
+        assert_eq!(p.size(), 3);
+        assert_eq!(p.length(), 28);
+
+        let mut bits = vec![false; 28];
+        assert_eq!(p.decode(&bits), vec![0.0, 0.0, -5.0]);
+
+        // 00000011 1111 1111111111111111
+        for b in &mut bits[6..]
+        {
+            *b = true;
+        }
+        assert_eq!(p.decode(&bits), vec![3.0, 15.0, 5.0]);
+
+        let values = vec![200.0, 7.0, 1.25];
+        assert_eq!(p.decode(&p.encode(&values))[..2], values[..2]);
+        assert!((p.decode(&p.encode(&values))[2] - 1.25).abs() < 1e-3);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn gray_coding()
+    {
+        ga_test_setup("ga_bin2dec::gray_coding");
+        let p = phenotype(GABin2DecPhenotype::new_gray());
+        assert!(p.is_gray());
+
+        let values = vec![200.0, 7.0, 1.25];
+        let bits = p.encode(&values);
+        assert_ne!(bits, phenotype(GABin2DecPhenotype::new()).encode(&values));
+        assert_eq!(p.decode(&bits)[..2], values[..2]);
+
+        // Consecutive values differ in exactly one bit.
+        let mut p = GABin2DecPhenotype::new_gray();
+        p.add(6, 0.0, 63.0);
+        for v in 0..63
+        {
+            let a = p.encode(&[v as f64]);
+            let b = p.encode(&[(v + 1) as f64]);
+            assert_eq!(a.iter().zip(&b).filter(|&(x, y)| x != y).count(), 1);
+            assert_eq!(p.decode(&b), vec![(v + 1) as f64]);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn genome_values()
+    {
+        ga_test_setup("ga_bin2dec::genome_values");
+        let objective : GABin2DecObjective = Arc::new(|v: &[f64]| v.iter().sum::<f64>() as f32);
+        let mut g = GABin2DecGenome::new(Arc::new(phenotype(GABin2DecPhenotype::new_gray())), objective);
+        assert_eq!(g.binary_string().len(), 28);
+        assert_eq!(g.values(), vec![0.0, 0.0, -5.0]);
+
+        g.set_values(&[10.0, 1.0, 5.0]);
+        assert_eq!(g.values(), vec![10.0, 1.0, 5.0]);
+        g.evaluate(&mut ());
+        assert_eq!(g.raw(), 16.0);
+        assert_eq!(g.fitness(), 16.0);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn simple_ga_with_factory()
+    {
+        ga_test_setup("ga_bin2dec::simple_ga_with_factory");
+        let mut p = GABin2DecPhenotype::new_gray();
+        p.add(16, -5.0, 5.0).add(16, -5.0, 5.0);
+        // Peak at (1, -2).
+        let mut factory = GABin2DecFactory::new(p, |v: &[f64]|
+        {
+            (1.0 / (1.0 + (v[0] - 1.0).powi(2) + (v[1] + 2.0).powi(2))) as f32
+        });
+        factory.set_crossover_operator(GABinaryStringCrossover::Uniform);
+
+        let mut rng_ctx = GARandomCtx::from_seed([3; 4], String::from(""));
+        let pop = factory.random_population(10, GAPopulationSortOrder::HighIsBest, &mut rng_ctx);
+        assert_eq!(pop.size(), 10);
+        assert_eq!(pop.individuals()[0].crossover_operator(), GABinaryStringCrossover::Uniform);
+
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 100,
+                                                   population_size: 40,
+                                                   probability_crossover: 0.9,
+                                                   probability_mutation: 0.01,
+                                                   elitism: true,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GABin2DecGenome>),
+                                                 None);
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        let best = ga.population().best_by_raw_score();
+        assert!(best.raw() > initial_best);
+        assert!(best.raw() > 0.5, "{:?}", best);
+        ga_test_teardown();
+    }
+}
@@ -0,0 +1,453 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Binary String Genome
+//!
+//! GALib's GA1DBinaryStringGenome: a fixed length string of bits, packed
+//! 64 to a word. The objective function is supplied by the user and is
+//! shared by all the copies of a genome.
+use ::ga::ga_core::{GAIndividual, GAParallelIndividual};
+use ::ga::ga_random::GARandomCtx;
+
+use std::fmt;
+use std::sync::Arc;
+
+const WORD_BITS: usize = 64;
+
+/// Objective function of a Binary String Genome. Its result is the raw
+/// score (and the fitness) of the genome.
+pub type GABinaryStringObjective = Arc<dyn Fn(&GABinaryStringGenome) -> f32 + Send + Sync>;
+
+/// Binary String Crossover
+///
+/// Crossover operator used by `GAIndividual::crossover`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GABinaryStringCrossover
+{
+    /// Bits before a random cut point come from the first parent, the rest
+    /// from the second one.
+    #[default]
+    OnePoint,
+    /// Bits between two random cut points come from the second parent, the
+    /// rest from the first one.
+    TwoPoint,
+    /// Each bit comes from either parent with equal probability.
+    Uniform,
+}
+
+/// Binary String Genome
+#[derive(Clone)]
+pub struct GABinaryStringGenome
+{
+    words: Vec<u64>,
+    length: usize,
+    crossover: GABinaryStringCrossover,
+    objective: GABinaryStringObjective,
+    raw: f32,
+    fitness: f32,
+}
+impl GABinaryStringGenome
+{
+    /// Genome of `length` bits, all of them unset.
+    pub fn new<F>(length: usize, objective: F) -> GABinaryStringGenome
+        where F: Fn(&GABinaryStringGenome) -> f32 + Send + Sync + 'static
+    {
+        GABinaryStringGenome::new_with_objective(length, Arc::new(objective))
+    }
+
+    /// Genome of `length` bits, all of them unset, sharing `objective` with
+    /// other genomes.
+    pub fn new_with_objective(length: usize, objective: GABinaryStringObjective) -> GABinaryStringGenome
+    {
+        GABinaryStringGenome
+        {
+            words: vec![0; length.div_ceil(WORD_BITS)],
+            length,
+            crossover: GABinaryStringCrossover::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// Genome with the given bits.
+    pub fn from_bits(bits: &[bool], objective: GABinaryStringObjective) -> GABinaryStringGenome
+    {
+        let mut genome = GABinaryStringGenome::new_with_objective(bits.len(), objective);
+        for (i, &b) in bits.iter().enumerate()
+        {
+            genome.set(i, b);
+        }
+        genome
+    }
+
+    /// Genome of `length` random bits.
+    pub fn random(length: usize, objective: GABinaryStringObjective, rng_ctx: &mut GARandomCtx) -> GABinaryStringGenome
+    {
+        let mut genome = GABinaryStringGenome::new_with_objective(length, objective);
+        for w in &mut genome.words
+        {
+            *w = rng_ctx.next_u64();
+        }
+        genome.clear_padding();
+        genome
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.length == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool
+    {
+        assert!(i < self.length);
+        self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    pub fn set(&mut self, i: usize, b: bool)
+    {
+        assert!(i < self.length);
+        if b
+        {
+            self.words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+        }
+        else
+        {
+            self.words[i / WORD_BITS] &= !(1 << (i % WORD_BITS));
+        }
+    }
+
+    pub fn flip(&mut self, i: usize)
+    {
+        assert!(i < self.length);
+        self.words[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize
+    {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn bits(&self) -> Vec<bool>
+    {
+        (0..self.length).map(|i| self.get(i)).collect()
+    }
+
+    pub fn crossover_operator(&self) -> GABinaryStringCrossover
+    {
+        self.crossover
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GABinaryStringCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    /// Child with the bits of `self` before a random cut point and those of
+    /// `other` from the cut point on.
+    pub fn one_point_crossover(&self, other: &GABinaryStringGenome, rng_ctx: &mut GARandomCtx) -> GABinaryStringGenome
+    {
+        assert_eq!(self.length, other.length);
+        let cut = rng_ctx.gen_range(0, self.length + 1);
+
+        let mut child = self.child();
+        child.copy_range(other, cut, self.length);
+        child
+    }
+
+    /// Child with the bits of `other` between two random cut points and
+    /// those of `self` elsewhere.
+    pub fn two_point_crossover(&self, other: &GABinaryStringGenome, rng_ctx: &mut GARandomCtx) -> GABinaryStringGenome
+    {
+        assert_eq!(self.length, other.length);
+        let a = rng_ctx.gen_range(0, self.length + 1);
+        let b = rng_ctx.gen_range(0, self.length + 1);
+
+        let mut child = self.child();
+        child.copy_range(other, a.min(b), a.max(b));
+        child
+    }
+
+    /// Child with each bit taken from either parent with equal probability.
+    pub fn uniform_crossover(&self, other: &GABinaryStringGenome, rng_ctx: &mut GARandomCtx) -> GABinaryStringGenome
+    {
+        assert_eq!(self.length, other.length);
+
+        let mut child = self.child();
+        for (w, o) in child.words.iter_mut().zip(&other.words)
+        {
+            let mask = rng_ctx.next_u64();
+            *w = (*w & !mask) | (o & mask);
+        }
+        child.clear_padding();
+        child
+    }
+
+    // Copy of self, without scores.
+    fn child(&self) -> GABinaryStringGenome
+    {
+        GABinaryStringGenome { raw: 0.0, fitness: 0.0, .. self.clone() }
+    }
+
+    // Overwrite bits [from, to) with those of 'other'.
+    fn copy_range(&mut self, other: &GABinaryStringGenome, from: usize, to: usize)
+    {
+        for i in from..to
+        {
+            let b = other.get(i);
+            self.set(i, b);
+        }
+    }
+
+    // Bits past 'length' in the last word are kept unset, so words can be
+    // compared and counted directly.
+    fn clear_padding(&mut self)
+    {
+        let used = self.length % WORD_BITS;
+        if used != 0
+        {
+            if let Some(last) = self.words.last_mut()
+            {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
+impl GAIndividual for GABinaryStringGenome
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GABinaryStringGenome, rng_ctx: &mut GARandomCtx) -> Box<GABinaryStringGenome>
+    {
+        let child = match self.crossover
+        {
+            GABinaryStringCrossover::OnePoint => self.one_point_crossover(other, rng_ctx),
+            GABinaryStringCrossover::TwoPoint => self.two_point_crossover(other, rng_ctx),
+            GABinaryStringCrossover::Uniform => self.uniform_crossover(other, rng_ctx),
+        };
+        Box::new(child)
+    }
+
+    /// Flip mutation: each bit is flipped with probability `p_mutation`.
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        if p_mutation <= 0.0
+        {
+            return 0;
+        }
+
+        let mut mutations = 0;
+        for i in 0..self.length
+        {
+            if rng_ctx.test_value(p_mutation)
+            {
+                self.flip(i);
+                mutations += 1;
+            }
+        }
+        mutations
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        self.evaluate_shared(&());
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Hamming distance, over the length of the strings. Strings of
+    /// different lengths can't be compared.
+    fn compare(&self, other: &GABinaryStringGenome) -> f32
+    {
+        if self.length != other.length
+        {
+            return -1.0;
+        }
+        if self.length == 0
+        {
+            return 0.0;
+        }
+
+        let different = self.words.iter().zip(other.words.iter())
+                                         .map(|(a, b)| (a ^ b).count_ones() as usize)
+                                         .sum::<usize>();
+        different as f32 / self.length as f32
+    }
+}
+impl GAParallelIndividual for GABinaryStringGenome
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        let score = (self.objective)(self);
+        self.raw = score;
+        self.fitness = score;
+    }
+}
+impl PartialEq for GABinaryStringGenome
+{
+    fn eq(&self, other: &GABinaryStringGenome) -> bool
+    {
+        self.length == other.length && self.words == other.words
+    }
+}
+impl fmt::Debug for GABinaryStringGenome
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let bits : String = (0..self.length).map(|i| if self.get(i) { '1' } else { '0' }).collect();
+        write!(f, "GABinaryStringGenome({}) raw: {} fitness: {}", bits, self.raw, self.fitness)
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    fn one_max() -> GABinaryStringObjective
+    {
+        Arc::new(|g: &GABinaryStringGenome| g.count_ones() as f32)
+    }
+
+    #[test]
+    fn packed_bits()
+    {
+        ga_test_setup("ga_binary_string::packed_bits");
+        let mut g = GABinaryStringGenome::new(130, |_: &GABinaryStringGenome| 0.0);
+        assert_eq!(g.len(), 130);
+        assert_eq!(g.count_ones(), 0);
+
+        g.set(0, true);
+        g.set(64, true);
+        g.flip(129);
+        assert!(g.get(0) && g.get(64) && g.get(129));
+        assert!(!g.get(1) && !g.get(128));
+        assert_eq!(g.count_ones(), 3);
+
+        g.set(64, false);
+        g.flip(129);
+        assert_eq!(g.count_ones(), 1);
+
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let r = GABinaryStringGenome::random(70, one_max(), &mut rng_ctx);
+        assert_eq!(r.count_ones(), r.bits().iter().filter(|&&b| b).count());
+        assert_eq!(GABinaryStringGenome::from_bits(&r.bits(), one_max()), r);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_binary_string::compare");
+        let a = GABinaryStringGenome::new_with_objective(100, one_max());
+        let mut b = a.clone();
+        assert_eq!(a.compare(&b), 0.0);
+
+        b.flip(3);
+        b.flip(70);
+        assert_eq!(a.compare(&b), 0.02);
+        assert_eq!(b.compare(&a), 0.02);
+
+        assert_eq!(a.compare(&GABinaryStringGenome::new_with_objective(10, one_max())), -1.0);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn flip_mutation()
+    {
+        ga_test_setup("ga_binary_string::flip_mutation");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mut g = GABinaryStringGenome::new_with_objective(100, one_max());
+
+        assert_eq!(g.mutate(0.0, &mut rng_ctx), 0);
+        assert_eq!(g.count_ones(), 0);
+
+        assert_eq!(g.mutate(1.0, &mut rng_ctx), 100);
+        assert_eq!(g.count_ones(), 100);
+
+        // Every flip is counted.
+        let mutations = g.mutate(0.5, &mut rng_ctx);
+        assert!(g.count_ones() > 0 && g.count_ones() < 100);
+        assert_eq!(mutations, 100 - g.count_ones());
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn crossover_operators()
+    {
+        ga_test_setup("ga_binary_string::crossover_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let zeros = GABinaryStringGenome::new_with_objective(100, one_max());
+        let ones = GABinaryStringGenome::from_bits(&[true; 100], one_max());
+
+        for _ in 0..20
+        {
+            // 0...01...1
+            let c = zeros.one_point_crossover(&ones, &mut rng_ctx).bits();
+            let cut = c.iter().position(|&b| b).unwrap_or(100);
+            assert!(c[cut..].iter().all(|&b| b));
+
+            // 0...01...10...0
+            let c = zeros.two_point_crossover(&ones, &mut rng_ctx).bits();
+            let from = c.iter().position(|&b| b).unwrap_or(100);
+            let to = c.iter().rposition(|&b| b).map_or(100, |i| i + 1);
+            assert!(c[from..to].iter().all(|&b| b));
+        }
+
+        let c = zeros.uniform_crossover(&ones, &mut rng_ctx);
+        assert!(c.count_ones() > 0 && c.count_ones() < 100);
+
+        let mut parent = zeros.clone();
+        parent.set_crossover_operator(GABinaryStringCrossover::Uniform);
+        assert_eq!(parent.crossover(&ones, &mut rng_ctx).crossover_operator(), GABinaryStringCrossover::Uniform);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn one_max_simple_ga()
+    {
+        ga_test_setup("ga_binary_string::one_max_simple_ga");
+        let mut rng_ctx = GARandomCtx::from_seed([2; 4], String::from(""));
+        let objective = one_max();
+        let individuals = (0..30).map(|_| GABinaryStringGenome::random(40, objective.clone(), &mut rng_ctx)).collect();
+
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 60,
+                                                   probability_crossover: 0.9,
+                                                   probability_mutation: 0.01,
+                                                   elitism: true,
+                                                   ..Default::default()
+                                                 },
+                                                 None,
+                                                 Some(GAPopulation::new(individuals, GAPopulationSortOrder::HighIsBest)));
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        let best = ga.population().best_by_raw_score();
+        assert_eq!(best.raw(), best.count_ones() as f32);
+        assert!(best.raw() > initial_best);
+        ga_test_teardown();
+    }
+}
@@ -9,7 +9,11 @@
 use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
 use ::ga::ga_random::GARandomCtx;
 
-use std::any::Any;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+
+use std::error::Error;
+use std::fmt;
 
 /// Bit Flags for Genetic Algorithm Configuration 
 /// 
@@ -26,19 +30,150 @@ impl Default for GAFlags
     fn default() -> GAFlags { GAFlags {bits : 0} }
 }
 
+// Serialized as their bits. Unknown bits are dropped.
+#[cfg(feature = "serde")]
+impl Serialize for GAFlags
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        self.bits().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GAFlags
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GAFlags, D::Error>
+    {
+        u32::deserialize(deserializer).map(GAFlags::from_bits_truncate)
+    }
+}
+
+/// Genetic Algorithm Error
+///
+/// Returned by selection, ranked population access and algorithm
+/// initialization and steps when they can't go on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GAError
+{
+    /// The operation needs at least 1 individual.
+    EmptyPopulation,
+    /// Ranked access past the last individual.
+    IndexOutOfBounds { index: usize, size: usize },
+    /// Ranked access to a population whose individuals were added or
+    /// removed since it was last sorted.
+    NotSorted,
+    /// Score proportional selection is undefined when scores have
+    /// different signs.
+    MixedSignScores { best: f32, worst: f32 },
+    /// The selector was last updated with a population of a different size.
+    SelectorNotUpdated { expected: usize, size: usize },
+    /// A configured parameter is out of its range.
+    InvalidParameter { name: &'static str, value: f32 },
+}
+
+impl fmt::Display for GAError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            GAError::EmptyPopulation
+            => write!(f, "the population has no individuals"),
+            GAError::IndexOutOfBounds { index, size }
+            => write!(f, "individual {} requested from a population of {}", index, size),
+            GAError::NotSorted
+            => write!(f, "the population changed since it was last sorted"),
+            GAError::MixedSignScores { best, worst }
+            => write!(f, "scores have mixed signs (best {}, worst {})", best, worst),
+            GAError::SelectorNotUpdated { expected, size }
+            => write!(f, "selector updated for {} individuals used on a population of {}", expected, size),
+            GAError::InvalidParameter { name, value }
+            => write!(f, "invalid {}: {}", name, value),
+        }
+    }
+}
+
+impl Error for GAError {}
+
 /// Genetic Algorithm Individual
 pub trait GAIndividual
 {
+    /// Context individuals are evaluated against (problem data, caches...).
+    /// `()` when evaluation doesn't need one.
+    type EvaluationCtx;
+
     // Instance
-    fn crossover(&self, other: &Self, &mut Any) -> Box<Self>;
-    fn mutate(&mut self, pMutation: f32, &mut Any);
-    fn evaluate(&mut self, evaluation_ctx: &mut Any);
+    fn crossover(&self, other: &Self, rng_ctx: &mut GARandomCtx) -> Box<Self>;
+    /// Mutate with probability `p_mutation` (per gene or per genome,
+    /// depending on the genome). Returns the number of mutations made,
+    /// which statistics count. GALib's GAGenome::mutate.
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize;
+    fn evaluate(&mut self, evaluation_ctx: &mut Self::EvaluationCtx);
     // Fitness score
     fn fitness(&self) -> f32;
     fn set_fitness(&mut self, f: f32);
     // Raw score
     fn raw(&self) -> f32;
     fn set_raw(&mut self, r: f32);
+
+    /// Distance to `other`, from 0.0 (identical) to 1.0 (as different as
+    /// two individuals can be). GALib's GAGenome::compare.
+    ///
+    /// -1.0 when individuals can't be compared, which is the default. Used
+    /// to measure population diversity (see `GAPopulation::compute_diversity`).
+    fn compare(&self, _other: &Self) -> f32
+    {
+        -1.0
+    }
+}
+
+/// Genetic Algorithm Individual that can be evaluated in parallel
+///
+/// Evaluation only reads the context, which is shared by all the threads
+/// evaluating a population. See `GAPopulation::evaluate_parallel`.
+pub trait GAParallelIndividual: GAIndividual + Send
+{
+    fn evaluate_shared(&mut self, evaluation_ctx: &Self::EvaluationCtx);
+}
+
+// Evaluation context of a genetic algorithm: its own (a default one, when
+// the user doesn't provide any) or borrowed from the user.
+pub(crate) enum GAEvaluationCtx<'a, C: 'a>
+{
+    Owned(C),
+    Borrowed(&'a mut C),
+}
+impl<'a, C> GAEvaluationCtx<'a, C>
+{
+    pub(crate) fn get_mut(&mut self) -> &mut C
+    {
+        match *self
+        {
+            GAEvaluationCtx::Owned(ref mut ctx) => ctx,
+            GAEvaluationCtx::Borrowed(ref mut ctx) => ctx,
+        }
+    }
+}
+
+// Evaluation context shared by the threads of a genetic algorithm: its own
+// (a default one, when the user doesn't provide any) or borrowed from the
+// user. See 'GAParallelIndividual'.
+pub(crate) enum GASharedEvaluationCtx<'a, C: 'a>
+{
+    Owned(C),
+    Borrowed(&'a C),
+}
+impl<'a, C> GASharedEvaluationCtx<'a, C>
+{
+    pub(crate) fn get(&self) -> &C
+    {
+        match *self
+        {
+            GASharedEvaluationCtx::Owned(ref ctx) => ctx,
+            GASharedEvaluationCtx::Borrowed(ctx) => ctx,
+        }
+    }
 }
 
 
@@ -66,13 +201,13 @@ pub trait GAFactory<T: GAIndividual>
 pub trait GeneticAlgorithm<T: GAIndividual>
 {
     // GENERIC GA METHODS - Should not be overriden frequently
-    fn initialize(&mut self)
+    fn initialize(&mut self) -> Result<(), GAError>
     {
         debug!("Genetic Algorithm - Initialized");
         self.initialize_internal()
     }
 
-    fn step(&mut self) -> i32
+    fn step(&mut self) -> Result<i32, GAError>
     { 
         debug!("Genetic Algorithm - Step");
         self.step_internal()
@@ -87,7 +222,7 @@ pub trait GeneticAlgorithm<T: GAIndividual>
     // IMPLEMENTATION SPECIFIC
     fn population(&mut self) -> &mut GAPopulation<T>;
 
-    fn initialize_internal(&mut self) {}
-    fn step_internal(&mut self) -> i32 { 0 }
+    fn initialize_internal(&mut self) -> Result<(), GAError> { Ok(()) }
+    fn step_internal(&mut self) -> Result<i32, GAError> { Ok(0) }
     fn done_internal(&mut self) -> bool { true }
 }
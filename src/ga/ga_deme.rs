@@ -0,0 +1,719 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Deme Genetic Algorithm
+//!
+//! Island model: several populations (demes) evolve independently and,
+//! every few generations, exchange their best individuals (migration).
+use ::ga::ga_core::{GAError, GAFactory, GeneticAlgorithm, GAIndividual, GAParallelIndividual, GASharedEvaluationCtx};
+use ::ga::ga_population::GAPopulation;
+use ::ga::ga_random::GARandomCtx;
+use ::ga::ga_replacement::{GAReplacement, GAReplaceWorst};
+use ::ga::ga_selectors::{GARouletteWheelSelector, GASelector};
+use ::ga::ga_simple::{SimpleGeneticAlgorithmCfg, simple_generation};
+use ::ga::ga_statistics::GAStatistics;
+use ::ga::ga_terminators::{GAGenerationTerminator, GATerminationCtx, GATerminator};
+
+use std::cmp;
+use std::panic;
+use std::thread;
+
+/// Migration Topology
+///
+/// Which demes receive the migrants of each deme.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAMigrationTopology
+{
+    /// Deme i sends to deme i+1; the last one sends to the first one.
+    #[default]
+    Ring,
+    /// Every deme sends to every other deme.
+    FullyConnected,
+    /// Every deme sends to another deme chosen at random each migration.
+    Random,
+}
+
+/// Deme Genetic Algorithm Config
+#[derive(Copy, Clone, Default)]
+pub struct DemeGeneticAlgorithmCfg
+{
+    // Every deme evolves like a Simple Genetic Algorithm with this config.
+    // 'population_size' is the size of each deme; 'd_seed' seeds the whole
+    // run and 'max_generations' bounds it. 'evaluation_workers' are the
+    // threads that evaluate each deme.
+    pub deme : SimpleGeneticAlgorithmCfg,
+    pub deme_count : usize,
+
+    // Threads that evolve the demes, each one evolving a contiguous chunk of
+    // them; as many as demes evolve one deme per thread. 0 or 1 evolve them
+    // in the calling thread.
+    pub deme_workers : usize,
+
+    pub migration_topology : GAMigrationTopology,
+    // Generations between migrations. 0 disables migration.
+    pub migration_interval : i32,
+    // Best individuals that each deme sends on every migration.
+    pub migration_count : usize,
+}
+
+// A deme and everything it needs to evolve on its own thread.
+struct GADeme<T: GAIndividual, Sel>
+{
+    population : GAPopulation<T>,
+    rng_ctx : GARandomCtx,
+    statistics : GAStatistics<T>,
+    selector : Sel,
+}
+impl<T: GAParallelIndividual + Clone, Sel: GASelector<T>> GADeme<T, Sel>
+{
+    fn initialize(&mut self, config: &SimpleGeneticAlgorithmCfg, eval_ctx: &T::EvaluationCtx)
+        where T::EvaluationCtx: Sync
+    {
+        self.population.evaluate_parallel(eval_ctx, config.evaluation_workers);
+        self.population.sort();
+        self.statistics.reset();
+        self.statistics.count_population_evaluation(self.population.size());
+        self.statistics.set_best(self.population.clone());
+    }
+
+    fn evolve(&mut self, config: &SimpleGeneticAlgorithmCfg, eval_ctx: &T::EvaluationCtx) -> Result<(), GAError>
+        where T::EvaluationCtx: Sync
+    {
+        let workers = config.evaluation_workers;
+        let mut evaluator = |pop: &mut GAPopulation<T>| pop.evaluate_parallel(eval_ctx, workers);
+        simple_generation(&mut self.population, config, &mut self.selector, &mut self.rng_ctx,
+                          &mut self.statistics, &mut evaluator)
+    }
+}
+
+// Run 'f' on every deme with 'workers' threads, each one running it on a
+// contiguous chunk of demes, and return the first error in deme order.
+//
+// Demes don't share any state, so results don't depend on the number of
+// workers. 0 or 1 workers run it in the calling thread.
+fn for_each_deme<T, Sel, F>(demes: &mut [GADeme<T, Sel>], workers: usize, f: F) -> Result<(), GAError>
+    where T: GAParallelIndividual, Sel: Send, F: Fn(&mut GADeme<T, Sel>) -> Result<(), GAError> + Sync
+{
+    let results : Vec<Result<(), GAError>> = if workers <= 1 || demes.len() <= 1
+    {
+        demes.iter_mut().map(&f).collect()
+    }
+    else
+    {
+        let chunk_size = demes.len().div_ceil(workers);
+        let f = &f;
+        thread::scope(|scope|
+        {
+            let handles : Vec<_> = demes.chunks_mut(chunk_size)
+                                        .map(|chunk| scope.spawn(move || chunk.iter_mut().map(f).collect::<Vec<_>>()))
+                                        .collect();
+            handles.into_iter()
+                   .flat_map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                   .collect()
+        })
+    };
+
+    results.into_iter().collect()
+}
+
+/// Deme Genetic Algorithm
+///
+/// GALib's GADemeGA.
+///
+/// Every deme has its own random number generator (seeded from the
+/// configured seed), its own `GAStatistics` and its own selector, so demes
+/// don't depend on each other between migrations and evolve in parallel
+/// (see `deme_workers`). Individuals are evaluated with
+/// `GAParallelIndividual::evaluate_shared`, all threads sharing the
+/// evaluation context; the run is the same regardless of the number of
+/// threads. On migration, copies of the best individuals of each deme (on
+/// the score configured in `deme.selection_basis`) are sent according to
+/// the topology, and the sending deme is left as it was. Like GALib's
+/// GADemeGA::migrate, the replacement scheme decides which individuals of
+/// the receiving deme they replace.
+///
+/// Parents are selected with a `GARouletteWheelSelector` unless another
+/// selector is given with `with_selector`. Migrants replace the worst
+/// individuals, if better (`GAReplaceWorst`), unless another replacement
+/// scheme is given with `with_replacement`.
+///
+/// `population()` is the combination of all demes, refreshed every
+/// generation. Changes made to it don't reach the demes.
+pub struct DemeGeneticAlgorithm<'a, T: GAParallelIndividual, Sel: GASelector<T> = GARouletteWheelSelector,
+                                Rep: GAReplacement<T> = GAReplaceWorst>
+{
+    current_generation : i32,
+    config : DemeGeneticAlgorithmCfg,
+    demes : Vec<GADeme<T, Sel>>,
+    population : GAPopulation<T>,
+    statistics : GAStatistics<T>,
+    rng_ctx : GARandomCtx,
+    eval_ctx: GASharedEvaluationCtx<'a, T::EvaluationCtx>,
+    terminator : Box<dyn GATerminator<T>>,
+    replacement : Rep,
+}
+impl<'a, T: GAParallelIndividual> DemeGeneticAlgorithm<'a, T>
+{
+    pub fn new(cfg: DemeGeneticAlgorithmCfg,
+               factory: Option<&mut dyn GAFactory<T>>,
+               demes: Option<Vec<GAPopulation<T>>>) -> DemeGeneticAlgorithm<'a, T>
+        where T::EvaluationCtx: Default
+    {
+        DemeGeneticAlgorithm::new_internal(cfg, factory, demes, GASharedEvaluationCtx::Owned(T::EvaluationCtx::default()))
+    }
+
+    /// Create a Deme Genetic Algorithm whose threads all share `eval_ctx`.
+    pub fn new_with_shared_eval_ctx(cfg: DemeGeneticAlgorithmCfg,
+                                    factory: Option<&mut dyn GAFactory<T>>,
+                                    demes: Option<Vec<GAPopulation<T>>>,
+                                    eval_ctx: &'a T::EvaluationCtx) -> DemeGeneticAlgorithm<'a, T>
+    {
+        DemeGeneticAlgorithm::new_internal(cfg, factory, demes, GASharedEvaluationCtx::Borrowed(eval_ctx))
+    }
+
+    fn new_internal(cfg: DemeGeneticAlgorithmCfg,
+                    factory: Option<&mut dyn GAFactory<T>>,
+                    demes: Option<Vec<GAPopulation<T>>>,
+                    eval_ctx: GASharedEvaluationCtx<'a, T::EvaluationCtx>) -> DemeGeneticAlgorithm<'a, T>
+    {
+        let mut rng = GARandomCtx::from_seed(cfg.deme.d_seed, String::from("deme_migration"));
+
+        let deme_count = match demes
+        {
+            Some(ref d) => d.len(),
+            None => cfg.deme_count
+        };
+
+        // Each deme's seed is drawn from the run's generator.
+        let mut deme_rng_ctxs = vec![];
+        for i in 0..deme_count
+        {
+            let seed = [rng.next_u32() | 1, rng.next_u32(), rng.next_u32(), rng.next_u32()];
+            deme_rng_ctxs.push(GARandomCtx::from_seed(seed, format!("deme_{}", i)));
+        }
+
+        let d : Vec<GAPopulation<T>> = match factory
+        {
+            Some(f) =>
+            {
+                deme_rng_ctxs.iter_mut()
+                             .map(|deme_rng| f.random_population(cfg.deme.population_size,
+                                                                 cfg.deme.population_sort_order,
+                                                                 deme_rng))
+                             .collect()
+            },
+            None =>
+            {
+                match demes
+                {
+                    Some(d_) => d_,
+                    None =>
+                    {
+                        panic!("Deme Genetic Algorithm - either factory or demes need to be provided");
+                    }
+                }
+            }
+        };
+
+        let demes = d.into_iter().zip(deme_rng_ctxs).map(|(population, rng_ctx)|
+        {
+            let selector = GARouletteWheelSelector::new(population.size());
+            GADeme { population, rng_ctx, statistics: GAStatistics::new(), selector }
+        }).collect();
+
+        DemeGeneticAlgorithm
+        {
+            current_generation: 0,
+            config: cfg,
+            demes,
+            population: GAPopulation::new(vec![], cfg.deme.population_sort_order),
+            statistics: GAStatistics::new(),
+            rng_ctx: rng,
+            eval_ctx,
+            terminator: Box::new(GAGenerationTerminator::new(cfg.deme.max_generations)),
+            replacement: GAReplaceWorst
+        }
+    }
+}
+impl<'a, T: GAParallelIndividual, Sel: GASelector<T>, Rep: GAReplacement<T>> DemeGeneticAlgorithm<'a, T, Sel, Rep>
+{
+    /// Select parents with copies of `selector` instead, one per deme.
+    ///
+    /// Each deme's selector is updated with the deme before every
+    /// generation, on the score configured in `deme.selection_basis`.
+    pub fn with_selector<U: GASelector<T> + Clone>(self, selector: U) -> DemeGeneticAlgorithm<'a, T, U, Rep>
+    {
+        let demes = self.demes.into_iter().map(|deme|
+        {
+            GADeme
+            {
+                population: deme.population,
+                rng_ctx: deme.rng_ctx,
+                statistics: deme.statistics,
+                selector: selector.clone()
+            }
+        }).collect();
+
+        DemeGeneticAlgorithm
+        {
+            current_generation: self.current_generation,
+            config: self.config,
+            demes,
+            population: self.population,
+            statistics: self.statistics,
+            rng_ctx: self.rng_ctx,
+            eval_ctx: self.eval_ctx,
+            terminator: self.terminator,
+            replacement: self.replacement
+        }
+    }
+
+    /// Insert migrants with `replacement` instead.
+    pub fn with_replacement<U: GAReplacement<T>>(self, replacement: U) -> DemeGeneticAlgorithm<'a, T, Sel, U>
+    {
+        DemeGeneticAlgorithm
+        {
+            current_generation: self.current_generation,
+            config: self.config,
+            demes: self.demes,
+            population: self.population,
+            statistics: self.statistics,
+            rng_ctx: self.rng_ctx,
+            eval_ctx: self.eval_ctx,
+            terminator: self.terminator,
+            replacement
+        }
+    }
+
+    pub fn deme_count(&self) -> usize
+    {
+        self.demes.len()
+    }
+
+    pub fn deme(&self, i: usize) -> &GAPopulation<T>
+    {
+        &self.demes[i].population
+    }
+
+    /// Selector of the ith deme.
+    pub fn deme_selector(&self, i: usize) -> &Sel
+    {
+        &self.demes[i].selector
+    }
+
+    /// Statistics of the ith deme.
+    pub fn deme_statistics(&self, i: usize) -> &GAStatistics<T>
+    {
+        &self.demes[i].statistics
+    }
+
+    pub fn deme_statistics_mut(&mut self, i: usize) -> &mut GAStatistics<T>
+    {
+        &mut self.demes[i].statistics
+    }
+
+    /// Statistics of all demes combined. Its counters are the totals of
+    /// those of the demes.
+    pub fn statistics(&self) -> &GAStatistics<T>
+    {
+        &self.statistics
+    }
+
+    pub fn statistics_mut(&mut self) -> &mut GAStatistics<T>
+    {
+        &mut self.statistics
+    }
+
+    /// Decide when the algorithm is done with `terminator`, instead of after
+    /// `deme.max_generations` generations. Terminators see all demes
+    /// combined.
+    pub fn set_terminator<U: GATerminator<T> + 'static>(&mut self, terminator: U)
+    {
+        self.terminator = Box::new(terminator);
+    }
+}
+impl<'a, T: GAParallelIndividual + Clone, Sel: GASelector<T>, Rep: GAReplacement<T>> DemeGeneticAlgorithm<'a, T, Sel, Rep>
+{
+    // Rebuild the combined population from the demes.
+    fn combine(&mut self)
+    {
+        let individuals : Vec<T> = self.demes.iter()
+                                             .flat_map(|d| d.population.individuals().iter().cloned())
+                                             .collect();
+        self.population = GAPopulation::new(individuals, self.config.deme.population_sort_order);
+        self.population.sort();
+    }
+
+    fn total_counts(&mut self)
+    {
+        let deme_statistics : Vec<&GAStatistics<T>> = self.demes.iter().map(|d| &d.statistics).collect();
+        self.statistics.total_counts(&deme_statistics);
+    }
+
+    fn migrate(&mut self) -> Result<(), GAError>
+    {
+        let deme_count = self.demes.len();
+        let smallest = self.demes.iter().map(|d| d.population.size()).min().unwrap_or(0);
+        let k = cmp::min(self.config.migration_count, smallest);
+
+        if deme_count < 2 || k == 0
+        {
+            return Ok(());
+        }
+
+        // Copies of the best individuals of every deme are taken before any
+        // deme receives migrants.
+        let basis = self.config.deme.selection_basis;
+        let mut migrants : Vec<Vec<T>> = vec![];
+        for deme in &mut self.demes
+        {
+            deme.population.sort();
+            let best = (0..k).map(|i| deme.population.best(i, basis).cloned())
+                             .collect::<Result<Vec<T>, GAError>>()?;
+            migrants.push(best);
+        }
+
+        let mut incoming : Vec<Vec<T>> = (0..deme_count).map(|_| vec![]).collect();
+        for (i, deme_migrants) in migrants.into_iter().enumerate()
+        {
+            match self.config.migration_topology
+            {
+                GAMigrationTopology::Ring =>
+                {
+                    incoming[(i+1) % deme_count].extend(deme_migrants);
+                },
+                GAMigrationTopology::FullyConnected =>
+                {
+                    for (j, deme_incoming) in incoming.iter_mut().enumerate()
+                    {
+                        if j != i
+                        {
+                            deme_incoming.extend(deme_migrants.iter().cloned());
+                        }
+                    }
+                },
+                GAMigrationTopology::Random =>
+                {
+                    // Any deme but i.
+                    let mut j = self.rng_ctx.gen_range(0, deme_count-1);
+                    if j >= i
+                    {
+                        j += 1;
+                    }
+                    incoming[j].extend(deme_migrants);
+                }
+            }
+        }
+
+        for (deme, deme_incoming) in self.demes.iter_mut().zip(incoming)
+        {
+            for migrant in deme_incoming
+            {
+                if deme.population.swap_individual_with(migrant, &[], &mut self.replacement, &mut self.rng_ctx).is_some()
+                {
+                    deme.statistics.count_replacements(1);
+                }
+            }
+            // Migrants were scaled in their own deme.
+            deme.population.scale();
+            deme.population.sort();
+        }
+        Ok(())
+    }
+}
+impl<'a, T, Sel, Rep> GeneticAlgorithm<T> for DemeGeneticAlgorithm<'a, T, Sel, Rep>
+    where T: GAParallelIndividual + Clone, T::EvaluationCtx: Sync, Sel: GASelector<T> + Send, Rep: GAReplacement<T>
+{
+    fn population(&mut self) -> &mut GAPopulation<T>
+    {
+        &mut self.population
+    }
+
+    fn initialize_internal(&mut self) -> Result<(), GAError>
+    {
+        if self.demes.is_empty() || self.demes.iter().any(|deme| deme.population.size() == 0)
+        {
+            return Err(GAError::EmptyPopulation);
+        }
+        for deme in &mut self.demes
+        {
+            if let Some(scaling) = self.config.deme.scaling.build()?
+            {
+                deme.population.set_scaling(scaling);
+            }
+        }
+
+        let config = &self.config.deme;
+        let eval_ctx = self.eval_ctx.get();
+        for_each_deme(&mut self.demes, self.config.deme_workers, |deme|
+        {
+            deme.initialize(config, eval_ctx);
+            Ok(())
+        })?;
+
+        self.combine();
+        self.statistics.reset();
+        self.statistics.set_best(self.population.clone());
+        self.total_counts();
+        self.terminator.reset();
+        Ok(())
+    }
+
+    fn step_internal(&mut self) -> Result<i32, GAError>
+    {
+        let config = &self.config.deme;
+        let eval_ctx = self.eval_ctx.get();
+        for_each_deme(&mut self.demes, self.config.deme_workers, |deme| deme.evolve(config, eval_ctx))?;
+
+        self.current_generation += 1;
+
+        if self.config.migration_interval > 0
+           && self.current_generation % self.config.migration_interval == 0
+        {
+            self.migrate()?;
+        }
+
+        for deme in &mut self.demes
+        {
+            deme.statistics.update(&mut deme.population);
+        }
+
+        self.combine();
+        self.statistics.update(&mut self.population);
+        self.total_counts();
+
+        Ok(self.current_generation)
+    }
+
+    fn done_internal(&mut self) -> bool
+    {
+        let evaluations = self.statistics.num_evaluations();
+        let done = self.terminator.done(&GATerminationCtx::new(self.current_generation, evaluations, &mut self.population));
+        if done
+        {
+            self.statistics.flush_scores_logged();
+            for deme in &mut self.demes
+            {
+                deme.statistics.flush_scores_logged();
+            }
+        }
+        done
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_selectors::GAKTournamentSelector;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    fn test_cfg(topology: GAMigrationTopology) -> DemeGeneticAlgorithmCfg
+    {
+        DemeGeneticAlgorithmCfg {
+            deme: SimpleGeneticAlgorithmCfg {
+                      d_seed : [1; 4],
+                      flags : DEBUG_FLAG,
+                      max_generations: 10,
+                      population_size: 5,
+                      probability_crossover: 0.9,
+                      probability_mutation: 0.1,
+                      ..Default::default()
+                  },
+            deme_count: 3,
+            deme_workers: 3,
+            migration_topology: topology,
+            migration_interval: 2,
+            migration_count: 2,
+        }
+    }
+
+    fn test_demes() -> Vec<GAPopulation<GATestIndividual>>
+    {
+        vec![GAPopulation::new(vec![GATestIndividual::new(1.0), GATestIndividual::new(2.0), GATestIndividual::new(3.0)],
+                               GAPopulationSortOrder::HighIsBest),
+             GAPopulation::new(vec![GATestIndividual::new(30.0), GATestIndividual::new(10.0), GATestIndividual::new(20.0)],
+                               GAPopulationSortOrder::HighIsBest)]
+    }
+
+    fn sorted_raw_scores(pop: &GAPopulation<GATestIndividual>) -> Vec<f32>
+    {
+        let mut scores : Vec<f32> = pop.individuals().iter().map(|ind| ind.raw()).collect();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        scores
+    }
+
+    #[test]
+    fn init_test_with_factory()
+    {
+        ga_test_setup("ga_deme::init_test_with_factory");
+        for topology in vec![GAMigrationTopology::Ring, GAMigrationTopology::FullyConnected, GAMigrationTopology::Random]
+        {
+            let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+            let mut ga = DemeGeneticAlgorithm::new(test_cfg(topology),
+                                                   Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                   None);
+            ga.initialize().unwrap();
+            assert_eq!(ga.deme_count(), 3);
+            assert_eq!(ga.population().size(), 15);
+
+            while !ga.done()
+            {
+                ga.step().unwrap();
+                for i in 0..ga.deme_count()
+                {
+                    assert_eq!(ga.deme(i).size(), 5);
+                    assert_eq!(ga.deme_statistics(i).cur_generation, ga.current_generation as u32 + 1);
+                }
+                assert_eq!(ga.population().size(), 15);
+            }
+            assert_eq!(ga.statistics().cur_generation, 11);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn ring_migration()
+    {
+        ga_test_setup("ga_deme::ring_migration");
+        let mut ga = DemeGeneticAlgorithm::new(DemeGeneticAlgorithmCfg {
+                                                   migration_count: 1,
+                                                   ..test_cfg(GAMigrationTopology::Ring)
+                                               },
+                                               None,
+                                               Some(test_demes()));
+        ga.initialize().unwrap();
+        ga.migrate().unwrap();
+
+        // 30.0 replaced the worst of deme 0; 3.0 is worse than anything in deme 1.
+        assert_eq!(sorted_raw_scores(ga.deme(0)), vec![2.0, 3.0, 30.0]);
+        assert_eq!(sorted_raw_scores(ga.deme(1)), vec![10.0, 20.0, 30.0]);
+
+        // Sending migrants leaves a deme as it was.
+        let deme_1 : Vec<f32> = ga.deme(1).individuals().iter().map(|ind| ind.raw()).collect();
+        assert_eq!(deme_1, vec![30.0, 10.0, 20.0]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn migration_basis_and_replacement()
+    {
+        ga_test_setup("ga_deme::migration_basis_and_replacement");
+        let mut cfg = DemeGeneticAlgorithmCfg {
+                          migration_count: 1,
+                          ..test_cfg(GAMigrationTopology::Ring)
+                      };
+        cfg.deme.selection_basis = GAPopulationSortBasis::Fitness;
+        let mut ga = DemeGeneticAlgorithm::new(cfg, None, Some(test_demes()))
+                         .with_replacement(::ga::ga_replacement::GAReplaceBest);
+        ga.initialize().unwrap();
+        ga.migrate().unwrap();
+
+        // The test individuals' fitness is the inverse of their raw score, so
+        // each deme sends its lowest raw score, which replaces the highest.
+        assert_eq!(sorted_raw_scores(ga.deme(0)), vec![1.0, 2.0, 10.0]);
+        assert_eq!(sorted_raw_scores(ga.deme(1)), vec![1.0, 10.0, 20.0]);
+        assert_eq!(ga.deme_statistics(0).num_replacements(), 1);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn fully_connected_migration()
+    {
+        ga_test_setup("ga_deme::fully_connected_migration");
+        let mut ga = DemeGeneticAlgorithm::new(test_cfg(GAMigrationTopology::FullyConnected),
+                                               None,
+                                               Some(test_demes()));
+        ga.initialize().unwrap();
+        ga.migrate().unwrap();
+
+        assert_eq!(sorted_raw_scores(ga.deme(0)), vec![3.0, 20.0, 30.0]);
+        assert_eq!(sorted_raw_scores(ga.deme(1)), vec![10.0, 20.0, 30.0]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn same_seed_same_run()
+    {
+        ga_test_setup("ga_deme::same_seed_same_run");
+        let run = ||
+        {
+            let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+            let mut ga = DemeGeneticAlgorithm::new(test_cfg(GAMigrationTopology::Random),
+                                                   Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                   None);
+            ga.initialize().unwrap();
+            while !ga.done()
+            {
+                ga.step().unwrap();
+            }
+            sorted_raw_scores(ga.population())
+        };
+
+        assert_eq!(run(), run());
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn same_run_whatever_the_thread_count()
+    {
+        ga_test_setup("ga_deme::same_run_whatever_the_thread_count");
+        let run = |deme_workers: usize, evaluation_workers: usize|
+        {
+            let mut cfg = test_cfg(GAMigrationTopology::Random);
+            cfg.deme_workers = deme_workers;
+            cfg.deme.evaluation_workers = evaluation_workers;
+
+            let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+            let mut ga = DemeGeneticAlgorithm::new_with_shared_eval_ctx(cfg,
+                                                                        Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                                        None,
+                                                                        &());
+            ga.initialize().unwrap();
+            while !ga.done()
+            {
+                ga.step().unwrap();
+            }
+
+            let demes : Vec<Vec<f32>> = (0..ga.deme_count())
+                                            .map(|i| ga.deme(i).individuals().iter().map(|ind| ind.raw()).collect())
+                                            .collect();
+            let stats = ga.statistics();
+            (demes, stats.num_selections(), stats.num_replacements(), stats.num_evaluations(), stats.online_performance())
+        };
+
+        let sequential = run(1, 1);
+        for &(deme_workers, evaluation_workers) in &[(0, 0), (2, 1), (3, 2), (8, 4)]
+        {
+            assert_eq!(run(deme_workers, evaluation_workers), sequential);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn with_selector_test()
+    {
+        ga_test_setup("ga_deme::with_selector_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = DemeGeneticAlgorithm::new(test_cfg(GAMigrationTopology::Ring),
+                                               Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                               None)
+                         .with_selector(GAKTournamentSelector::new(3, 1.0));
+        assert_eq!(ga.deme_selector(2).size(), 3);
+
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        assert_eq!(ga.population().size(), 15);
+        assert!(ga.statistics().num_selections() > 0);
+        ga_test_teardown();
+    }
+}
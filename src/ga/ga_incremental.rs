@@ -0,0 +1,509 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Incremental Genetic Algorithm
+//!
+//! Genetic Algorithm with overlapping populations and a very small amount of
+//! overlap. Each step, a single mating produces 1 or 2 children that are
+//! inserted in the population right away.
+use ::ga::ga_core::{GAError, GAEvaluationCtx, GAFactory, GAFlags, GeneticAlgorithm, GAIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
+use ::ga::ga_random::{GARandomCtx, GASeed};
+use ::ga::ga_replacement::GAReplacement;
+use ::ga::ga_scaling::GAScalingScheme;
+use ::ga::ga_selectors::{GASelector, GAScoreSelection, GARawScoreSelection, GAFitnessScoreSelection};
+use ::ga::ga_statistics::GAStatistics;
+use ::ga::ga_terminators::{GAGenerationTerminator, GATerminationCtx, GATerminator};
+
+/// Incremental Genetic Algorithm Config
+#[derive(Copy, Clone, Default)]
+pub struct IncrementalGeneticAlgorithmCfg
+{
+    pub d_seed : GASeed,
+
+    pub max_generations         : i32,
+    pub population_size         : usize,
+
+    pub probability_crossover   : f32,
+    pub probability_mutation    : f32,
+
+    pub population_sort_order : GAPopulationSortOrder,
+
+    // Score that selection is based on. With a scaling scheme (see
+    // 'scaling'), selecting on fitness selects on the scaled scores.
+    pub selection_basis : GAPopulationSortBasis,
+
+    // Scaling scheme set on the population when the algorithm is initialized
+    // (initialization fails if its parameters are invalid).
+    // The population rescales fitness every time it is evaluated.
+    pub scaling : GAScalingScheme,
+
+    // Children produced each step: 1 or 2. 0 is taken as 1.
+    pub offspring_count         : usize,
+
+    pub flags                   : GAFlags,
+}
+
+/// Incremental Genetic Algorithm
+///
+/// GALib's GAIncrementalGA.
+///
+/// Each step (generation), 2 parents are chosen with the selector `S` and
+/// mated. Each child is evaluated and inserted immediately; the replacement
+/// scheme `R` decides which individual it displaces (if any).
+pub struct IncrementalGeneticAlgorithm<'a, T: GAIndividual, S: GASelector<T>, R: GAReplacement<T>>
+{
+    current_generation : i32,
+    config : IncrementalGeneticAlgorithmCfg,
+    population : GAPopulation<T>,
+    selector : S,
+    replacement : R,
+    rng_ctx : GARandomCtx,
+    eval_ctx: GAEvaluationCtx<'a, T::EvaluationCtx>,
+    terminator : Box<dyn GATerminator<T>>,
+    statistics : GAStatistics<T>,
+}
+impl<'a, T: GAIndividual, S: GASelector<T>, R: GAReplacement<T>> IncrementalGeneticAlgorithm<'a, T, S, R>
+{
+    pub fn new(cfg: IncrementalGeneticAlgorithmCfg,
+               factory: Option<&mut dyn GAFactory<T>>,
+               population: Option<GAPopulation<T>>,
+               selector: S,
+               replacement: R) -> IncrementalGeneticAlgorithm<'a, T, S, R>
+        where T::EvaluationCtx: Default
+    {
+        IncrementalGeneticAlgorithm::new_internal(cfg, factory, population, selector, replacement,
+                                      GAEvaluationCtx::Owned(T::EvaluationCtx::default()))
+    }
+
+    pub fn new_with_eval_ctx(cfg: IncrementalGeneticAlgorithmCfg,
+                             factory: Option<&mut dyn GAFactory<T>>,
+                             population: Option<GAPopulation<T>>,
+                             selector: S,
+                             replacement: R,
+                             eval_ctx: &'a mut T::EvaluationCtx) -> IncrementalGeneticAlgorithm<'a, T, S, R>
+    {
+        IncrementalGeneticAlgorithm::new_internal(cfg, factory, population, selector, replacement,
+                                      GAEvaluationCtx::Borrowed(eval_ctx))
+    }
+
+    fn new_internal(cfg: IncrementalGeneticAlgorithmCfg,
+                    factory: Option<&mut dyn GAFactory<T>>,
+                    population: Option<GAPopulation<T>>,
+                    selector: S,
+                    replacement: R,
+                    eval_ctx: GAEvaluationCtx<'a, T::EvaluationCtx>) -> IncrementalGeneticAlgorithm<'a, T, S, R>
+    {
+        let mut rng = GARandomCtx::from_seed(cfg.d_seed, String::from(""));
+        let p = match factory
+        {
+            Some(f) => f.random_population(cfg.population_size, cfg.population_sort_order, &mut rng),
+            None =>
+            {
+                match population
+                {
+                    Some(p_) => p_,
+                    None =>
+                    {
+                        panic!("Incremental Genetic Algorithm - either factory or population need to be provided");
+                    }
+                }
+            }
+        };
+
+        IncrementalGeneticAlgorithm { current_generation: 0, config: cfg, population: p,
+                                      selector, replacement, rng_ctx: rng, eval_ctx,
+                                      terminator: Box::new(GAGenerationTerminator::new(cfg.max_generations)),
+                                      statistics: GAStatistics::new() }
+    }
+
+    /// Decide when the algorithm is done with `terminator`, instead of after
+    /// `max_generations` generations.
+    pub fn set_terminator<U: GATerminator<T> + 'static>(&mut self, terminator: U)
+    {
+        self.terminator = Box::new(terminator);
+    }
+
+    /// Statistics of the run, updated on initialization and after every
+    /// generation.
+    pub fn statistics(&self) -> &GAStatistics<T>
+    {
+        &self.statistics
+    }
+
+    pub fn statistics_mut(&mut self) -> &mut GAStatistics<T>
+    {
+        &mut self.statistics
+    }
+
+    fn evaluate_individual(&mut self, ind: &mut T)
+    {
+        ind.evaluate(self.eval_ctx.get_mut());
+        self.statistics.count_evaluations(1);
+    }
+
+    fn evaluate_population(&mut self)
+    {
+        self.population.evaluate(self.eval_ctx.get_mut());
+        self.statistics.count_population_evaluation(self.population.size());
+    }
+
+    // Mate 2 parents, selected on the score 'B' picks, into 1 or 2 evaluated
+    // children. Parents are returned as positions in the individuals vector,
+    // for the replacement scheme.
+    fn mate<B: GAScoreSelection<T>>(&mut self) -> Result<(Vec<T>, Vec<usize>), GAError>
+        where T: Clone
+    {
+        let two_children = self.config.offspring_count >= 2;
+
+        self.selector.update::<B>(&mut self.population)?;
+
+        let (mut children, parents) =
+        {
+            let mom = self.selector.select::<B>(&self.population, &mut self.rng_ctx)?;
+            let dad = self.selector.select::<B>(&self.population, &mut self.rng_ctx)?;
+            let parents = vec![self.population.index_of(mom).unwrap(),
+                               self.population.index_of(dad).unwrap()];
+            self.statistics.count_selections(2);
+
+            let mut children = vec![];
+            if self.rng_ctx.test_value(self.config.probability_crossover)
+            {
+                children.push(*mom.crossover(dad, &mut self.rng_ctx));
+                if two_children
+                {
+                    children.push(*dad.crossover(mom, &mut self.rng_ctx));
+                }
+                self.statistics.count_crossovers(children.len());
+            }
+            else
+            {
+                children.push(mom.clone());
+                if two_children
+                {
+                    children.push(dad.clone());
+                }
+            }
+
+            (children, parents)
+        };
+
+        for child in &mut children
+        {
+            let mutations = child.mutate(self.config.probability_mutation, &mut self.rng_ctx);
+            self.statistics.count_mutations(mutations);
+            self.evaluate_individual(child);
+        }
+
+        Ok((children, parents))
+    }
+}
+impl<'a, T: GAIndividual + Clone, S: GASelector<T>, R: GAReplacement<T>> GeneticAlgorithm<T> for IncrementalGeneticAlgorithm<'a, T, S, R>
+{
+    fn population(&mut self) -> &mut GAPopulation<T>
+    {
+        &mut self.population
+    }
+
+    fn initialize_internal(&mut self) -> Result<(), GAError>
+    {
+        if self.population.size() == 0
+        {
+            return Err(GAError::EmptyPopulation);
+        }
+        if let Some(scaling) = self.config.scaling.build()?
+        {
+            self.population.set_scaling(scaling);
+        }
+        self.statistics.reset();
+        self.terminator.reset();
+        self.evaluate_population();
+        self.population.sort();
+        self.statistics.set_best(self.population.clone());
+        Ok(())
+    }
+
+    fn step_internal(&mut self) -> Result<i32, GAError>
+    {
+        let (children, parents) = match self.config.selection_basis
+        {
+            GAPopulationSortBasis::Raw => self.mate::<GARawScoreSelection>()?,
+            GAPopulationSortBasis::Fitness => self.mate::<GAFitnessScoreSelection>()?,
+        };
+
+        // Like GALib, the first child replaces mom and the second one dad; a
+        // single child may replace either. When mom and dad are the same
+        // individual, the second child doesn't get a parent, so that it
+        // doesn't displace its sibling.
+        let two_children = children.len() == 2;
+        for (i, child) in children.into_iter().enumerate()
+        {
+            let child_parents = if !two_children
+            {
+                &parents[..]
+            }
+            else if i == 1 && parents[1] == parents[0]
+            {
+                &parents[..0]
+            }
+            else
+            {
+                &parents[i..i+1]
+            };
+
+            if self.population.swap_individual_with(child, child_parents, &mut self.replacement, &mut self.rng_ctx).is_some()
+            {
+                self.statistics.count_replacements(1);
+            }
+        }
+        // Raw scores changed: derive fitness again with the population's
+        // scaling scheme, if any.
+        self.population.scale();
+        self.population.sort();
+        self.statistics.update(&mut self.population);
+
+        self.current_generation += 1;
+        Ok(self.current_generation)
+    }
+
+    fn done_internal(&mut self) -> bool
+    {
+        let evaluations = self.statistics.num_evaluations();
+        let done = self.terminator.done(&GATerminationCtx::new(self.current_generation, evaluations, &mut self.population));
+        if done
+        {
+            self.statistics.flush_scores_logged();
+        }
+        done
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_replacement::*;
+    use ::ga::ga_selectors::*;
+    use ::ga::ga_scaling::{GALinearRankScaling, GAScalingScheme};
+    use super::*;
+
+    use std::cell::Cell;
+
+    fn test_cfg() -> IncrementalGeneticAlgorithmCfg
+    {
+        IncrementalGeneticAlgorithmCfg {
+            d_seed : [1; 4],
+            flags : DEBUG_FLAG,
+            max_generations: 20,
+            population_size: 6,
+            probability_crossover: 0.9,
+            probability_mutation: 0.1,
+            offspring_count: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn init_test_with_factory()
+    {
+        ga_test_setup("ga_incremental::init_test_with_factory");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = IncrementalGeneticAlgorithm::new(test_cfg(),
+                                                      Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                      None,
+                                                      GAUniformSelector::new(),
+                                                      GAReplaceRandom);
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            assert_eq!(ga.population().size(), 6);
+        }
+        assert_eq!(ga.step(), Ok(21));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn custom_replacement_scheme()
+    {
+        ga_test_setup("ga_incremental::custom_replacement_scheme");
+        let initial_population = GAPopulation::new(vec![GATestIndividual::new(1.0),
+                                                        GATestIndividual::new(2.0),
+                                                        GATestIndividual::new(3.0)],
+                                                   GAPopulationSortOrder::HighIsBest);
+
+        // Children always replace the first individual; the others survive.
+        let replacement = GACustomReplacement::new(|_: &GAPopulation<GATestIndividual>, _: &GATestIndividual,
+                                                    _: &[usize], _: &mut GARandomCtx| Some(0));
+        let mut ga = IncrementalGeneticAlgorithm::new(IncrementalGeneticAlgorithmCfg {
+                                                          offspring_count: 1,
+                                                          ..test_cfg()
+                                                      },
+                                                      None,
+                                                      Some(initial_population),
+                                                      GARankSelector::new(),
+                                                      replacement);
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+
+        // The rank selector always picks the best (3.0) and the test
+        // individual's crossover clones the first parent.
+        let mut scores : Vec<f32> = ga.population().individuals().iter().map(|ind| ind.raw()).collect();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(scores, vec![2.0, 3.0, 3.0]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn children_are_scaled()
+    {
+        ga_test_setup("ga_incremental::children_are_scaled");
+        let mut population = GAPopulation::new(vec![GATestIndividual::new(1.0), GATestIndividual::new(2.0), GATestIndividual::new(3.0)],
+                                               GAPopulationSortOrder::HighIsBest);
+        population.set_scaling(Box::new(GALinearRankScaling::new(2.0)));
+        let mut ga = IncrementalGeneticAlgorithm::new(test_cfg(), None, Some(population),
+                                                      GARankSelector::new(),
+                                                      GAReplaceWorst);
+
+        // Fitness is the rank of the raw score, children included.
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+        let fitness : Vec<f32> = ga.population().fitness_score_iterator().unwrap().map(|ind| ind.fitness()).collect();
+        assert_eq!(fitness, vec![2.0, 1.0, 0.0]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn scaling_and_selection_basis_test()
+    {
+        ga_test_setup("ga_incremental::scaling_and_selection_basis_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = IncrementalGeneticAlgorithm::new(IncrementalGeneticAlgorithmCfg {
+                                                          population_size: 5,
+                                                          population_sort_order: GAPopulationSortOrder::LowIsBest,
+                                                          selection_basis: GAPopulationSortBasis::Fitness,
+                                                          scaling: GAScalingScheme::LinearRank { pressure: 2.0 },
+                                                          ..test_cfg()
+                                                      },
+                                                      Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                      None,
+                                                      GARouletteWheelSelector::new(5),
+                                                      GAReplaceWorst);
+
+        // LowIsBest fitness: the best individual ranks 0, the worst 2.
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            let fitness : Vec<f32> = ga.population().fitness_score_iterator().unwrap().map(|ind| ind.fitness()).collect();
+            assert_eq!(fitness, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+            assert_eq!(ga.population().best_by_fitness_score().raw(),
+                       ga.population().best_by_raw_score().raw());
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn replace_best_and_worst()
+    {
+        ga_test_setup("ga_incremental::replace_best_and_worst");
+        let inds = || vec![GATestIndividual::new(1.0), GATestIndividual::new(2.0), GATestIndividual::new(3.0)];
+
+        // Replacing the best with copies of the best leaves scores unchanged.
+        let mut ga = IncrementalGeneticAlgorithm::new(test_cfg(), None,
+                                                      Some(GAPopulation::new(inds(), GAPopulationSortOrder::HighIsBest)),
+                                                      GARankSelector::new(),
+                                                      GAReplaceBest);
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+        assert_eq!(ga.population().best_by_raw_score().raw(), 3.0);
+        assert_eq!(ga.population().worst_by_raw_score().raw(), 1.0);
+
+        // Replacing the worst with copies of the best takes over the population.
+        let mut ga = IncrementalGeneticAlgorithm::new(test_cfg(), None,
+                                                      Some(GAPopulation::new(inds(), GAPopulationSortOrder::HighIsBest)),
+                                                      GARankSelector::new(),
+                                                      GAReplaceWorst);
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+        assert_eq!(ga.population().worst_by_raw_score().raw(), 3.0);
+        ga_test_teardown();
+    }
+
+    // Selects the worst individual as mom and the best one as dad.
+    struct GATestWorstBestSelector
+    {
+        selections: Cell<usize>,
+    }
+
+    impl GASelector<GATestIndividual> for GATestWorstBestSelector
+    {
+        fn update<S: GAScoreSelection<GATestIndividual>>(&mut self, pop: &mut GAPopulation<GATestIndividual>) -> Result<(), GAError>
+        {
+            pop.sort();
+            Ok(())
+        }
+
+        fn select<'b, S: GAScoreSelection<GATestIndividual>>(&self, pop: &'b GAPopulation<GATestIndividual>,
+                                                              _: &mut GARandomCtx) -> Result<&'b GATestIndividual, GAError>
+        {
+            let selections = self.selections.get();
+            self.selections.set(selections + 1);
+            let rank = if selections % 2 == 0 { pop.size()-1 } else { 0 };
+            pop.individual(rank, S::population_sort_basis())
+        }
+    }
+
+    #[test]
+    fn replace_parent_keeps_both_children()
+    {
+        ga_test_setup("ga_incremental::replace_parent_keeps_both_children");
+        let population = GAPopulation::new([1.0, 2.0, 3.0, 4.0, 5.0].iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                           GAPopulationSortOrder::HighIsBest);
+        let mut ga = IncrementalGeneticAlgorithm::new(IncrementalGeneticAlgorithmCfg {
+                                                          probability_crossover: 1.0,
+                                                          offspring_count: 2,
+                                                          ..test_cfg()
+                                                      },
+                                                      None,
+                                                      Some(population),
+                                                      GATestWorstBestSelector { selections: Cell::new(0) },
+                                                      GAReplaceParent);
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+
+        // The test individual's crossover clones the first parent: the child
+        // of mom (1.0) replaced mom and the child of dad (5.0) replaced dad.
+        let mut scores : Vec<f32> = ga.population().individuals().iter().map(|ind| ind.raw()).collect();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(scores, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(ga.statistics().num_replacements(), 2);
+
+        // Mom and dad are the same individual: the second child replaces the
+        // worst individual instead of its sibling.
+        let population = GAPopulation::new([1.0, 2.0, 3.0].iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                           GAPopulationSortOrder::HighIsBest);
+        let mut ga = IncrementalGeneticAlgorithm::new(IncrementalGeneticAlgorithmCfg {
+                                                          probability_crossover: 1.0,
+                                                          offspring_count: 2,
+                                                          ..test_cfg()
+                                                      },
+                                                      None,
+                                                      Some(population),
+                                                      GARankSelector::new(),
+                                                      GAReplaceParent);
+        ga.initialize().unwrap();
+        ga.step().unwrap();
+        let mut scores : Vec<f32> = ga.population().individuals().iter().map(|ind| ind.raw()).collect();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(scores, vec![2.0, 3.0, 3.0]);
+        assert_eq!(ga.statistics().num_replacements(), 2);
+        ga_test_teardown();
+    }
+}
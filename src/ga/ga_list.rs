@@ -0,0 +1,522 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! List Genome
+//!
+//! GALib's GAListGenome: a variable length list of user defined genes (see
+//! `GAListGene`). Operators may change the length of the list; a repair
+//! step keeps it within the genome's limits.
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use std::cmp;
+use std::fmt;
+use std::sync::Arc;
+
+/// List Gene
+///
+/// Element of a list genome. Genes are compared with `==` to measure the
+/// distance between genomes (see `GAIndividual::compare`).
+pub trait GAListGene: Clone + PartialEq
+{
+    /// Random gene, for insertions and initialization.
+    fn random(rng_ctx: &mut GARandomCtx) -> Self;
+
+    /// Change the gene, for alter mutation. Replaces it with a random gene
+    /// by default.
+    fn alter(&mut self, rng_ctx: &mut GARandomCtx)
+    {
+        *self = Self::random(rng_ctx);
+    }
+}
+
+/// Objective function of a List Genome. Takes the genes and returns the
+/// raw score (and the fitness) of the genome.
+pub type GAListObjective<G> = Arc<dyn Fn(&[G]) -> f32 + Send + Sync>;
+
+/// List Mutation
+///
+/// Mutation operator used by `GAIndividual::mutate`. Each gene of the
+/// genome triggers, with probability `p_mutation`, one mutation at a random
+/// position. An empty genome mutates as if it had one gene.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAListMutation
+{
+    /// Insert a random gene.
+    Insert,
+    /// Remove a gene.
+    Delete,
+    /// Swap two genes.
+    Swap,
+    /// Alter a gene (see `GAListGene::alter`).
+    Alter,
+    /// Any of the above, chosen at random each time.
+    #[default]
+    Mixed,
+}
+
+/// List Genome
+///
+/// Its length is always within [`min_length`, `max_length`]. Lists that
+/// are too long lose their last genes; lists that are too short get random
+/// genes appended.
+#[derive(Clone)]
+pub struct GAListGenome<G: GAListGene>
+{
+    genes: Vec<G>,
+    min_length: usize,
+    max_length: usize,
+    mutation: GAListMutation,
+    objective: GAListObjective<G>,
+    raw: f32,
+    fitness: f32,
+}
+impl<G: GAListGene> GAListGenome<G>
+{
+    /// Genome with `genes`, whose length must be within [`min_length`,
+    /// `max_length`].
+    pub fn new(genes: Vec<G>, min_length: usize, max_length: usize, objective: GAListObjective<G>) -> GAListGenome<G>
+    {
+        assert!(min_length <= max_length);
+        assert!(genes.len() >= min_length && genes.len() <= max_length,
+                "List Genome - length out of [min_length, max_length]");
+        GAListGenome
+        {
+            genes,
+            min_length,
+            max_length,
+            mutation: GAListMutation::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// Genome of random length within [`min_length`, `max_length`], with
+    /// random genes.
+    pub fn random(min_length: usize, max_length: usize, objective: GAListObjective<G>, rng_ctx: &mut GARandomCtx) -> GAListGenome<G>
+    {
+        assert!(min_length <= max_length);
+        let length = rng_ctx.gen_range(min_length, max_length + 1);
+        let genes = (0..length).map(|_| G::random(rng_ctx)).collect();
+        GAListGenome::new(genes, min_length, max_length, objective)
+    }
+
+    pub fn genes(&self) -> &[G]
+    {
+        &self.genes
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.genes.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.genes.is_empty()
+    }
+
+    pub fn min_length(&self) -> usize
+    {
+        self.min_length
+    }
+
+    pub fn max_length(&self) -> usize
+    {
+        self.max_length
+    }
+
+    pub fn mutation_operator(&self) -> GAListMutation
+    {
+        self.mutation
+    }
+
+    pub fn set_mutation_operator(&mut self, mutation: GAListMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    /// Cut-and-splice crossover: the genes of `self` before a random cut
+    /// point followed by those of `other` after another random cut point.
+    pub fn cut_and_splice_crossover(&self, other: &GAListGenome<G>, rng_ctx: &mut GARandomCtx) -> GAListGenome<G>
+    {
+        let a = rng_ctx.gen_range(0, self.genes.len() + 1);
+        let b = rng_ctx.gen_range(0, other.genes.len() + 1);
+
+        let mut genes = Vec::with_capacity(a + other.genes.len() - b);
+        genes.extend_from_slice(&self.genes[..a]);
+        genes.extend_from_slice(&other.genes[b..]);
+
+        let mut child = GAListGenome
+        {
+            genes,
+            min_length: self.min_length,
+            max_length: self.max_length,
+            mutation: self.mutation,
+            objective: self.objective.clone(),
+            raw: 0.0,
+            fitness: 0.0,
+        };
+        child.repair(rng_ctx);
+        child
+    }
+
+    // Bring the length back within [min_length, max_length].
+    fn repair(&mut self, rng_ctx: &mut GARandomCtx)
+    {
+        self.genes.truncate(self.max_length);
+        while self.genes.len() < self.min_length
+        {
+            self.genes.push(G::random(rng_ctx));
+        }
+    }
+}
+impl<G: GAListGene> GAIndividual for GAListGenome<G>
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GAListGenome<G>, rng_ctx: &mut GARandomCtx) -> Box<GAListGenome<G>>
+    {
+        Box::new(self.cut_and_splice_crossover(other, rng_ctx))
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        if p_mutation <= 0.0
+        {
+            return 0;
+        }
+
+        let mut mutations = 0;
+        for _ in 0..self.genes.len().max(1)
+        {
+            if !rng_ctx.test_value(p_mutation)
+            {
+                continue;
+            }
+
+            let mutation = match self.mutation
+            {
+                GAListMutation::Mixed =>
+                {
+                    [GAListMutation::Insert, GAListMutation::Delete,
+                     GAListMutation::Swap, GAListMutation::Alter][rng_ctx.gen_range(0, 4)]
+                },
+                m => m,
+            };
+
+            let n = self.genes.len();
+            match mutation
+            {
+                GAListMutation::Insert =>
+                {
+                    let g = G::random(rng_ctx);
+                    self.genes.insert(rng_ctx.gen_range(0, n + 1), g);
+                    mutations += 1;
+                },
+                GAListMutation::Delete if n > 0 =>
+                {
+                    self.genes.remove(rng_ctx.gen_range(0, n));
+                    mutations += 1;
+                },
+                GAListMutation::Swap if n > 1 =>
+                {
+                    let i = rng_ctx.gen_range(0, n);
+                    let j = (i + rng_ctx.gen_range(1, n)) % n;
+                    self.genes.swap(i, j);
+                    mutations += 1;
+                },
+                GAListMutation::Alter if n > 0 =>
+                {
+                    let i = rng_ctx.gen_range(0, n);
+                    self.genes[i].alter(rng_ctx);
+                    mutations += 1;
+                },
+                _ => {},
+            }
+        }
+
+        self.repair(rng_ctx);
+        mutations
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        let score = (self.objective)(&self.genes);
+        self.raw = score;
+        self.fitness = score;
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Edit distance (insertions, deletions and substitutions of genes),
+    /// over the length of the longer list.
+    fn compare(&self, other: &GAListGenome<G>) -> f32
+    {
+        let longest = cmp::max(self.genes.len(), other.genes.len());
+        if longest == 0
+        {
+            return 0.0;
+        }
+
+        // Edit distances between the prefixes of self.genes and those of
+        // other.genes, a row at a time.
+        let mut previous : Vec<usize> = (0..other.genes.len()+1).collect();
+        for (i, a) in self.genes.iter().enumerate()
+        {
+            let mut current = vec![i+1; other.genes.len()+1];
+            for (j, b) in other.genes.iter().enumerate()
+            {
+                let substitution = previous[j] + if a == b { 0 } else { 1 };
+                current[j+1] = cmp::min(substitution, cmp::min(previous[j+1], current[j]) + 1);
+            }
+            previous = current;
+        }
+
+        previous[other.genes.len()] as f32 / longest as f32
+    }
+}
+impl<G: GAListGene + Send> GAParallelIndividual for GAListGenome<G>
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        self.evaluate(&mut ());
+    }
+}
+impl<G: GAListGene> PartialEq for GAListGenome<G>
+{
+    fn eq(&self, other: &GAListGenome<G>) -> bool
+    {
+        self.genes == other.genes
+    }
+}
+impl<G: GAListGene + fmt::Debug> fmt::Debug for GAListGenome<G>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GAListGenome({:?}) raw: {} fitness: {}", self.genes, self.raw, self.fitness)
+    }
+}
+
+/// List Genome Factory
+///
+/// Creates genomes of random length, with random genes.
+pub struct GAListFactory<G: GAListGene>
+{
+    min_length: usize,
+    max_length: usize,
+    objective: GAListObjective<G>,
+    mutation: GAListMutation,
+}
+impl<G: GAListGene> GAListFactory<G>
+{
+    pub fn new<F>(min_length: usize, max_length: usize, objective: F) -> GAListFactory<G>
+        where F: Fn(&[G]) -> f32 + Send + Sync + 'static
+    {
+        assert!(min_length <= max_length);
+        GAListFactory
+        {
+            min_length,
+            max_length,
+            objective: Arc::new(objective),
+            mutation: GAListMutation::default(),
+        }
+    }
+
+    /// Mutation operator of the genomes created from now on.
+    pub fn set_mutation_operator(&mut self, mutation: GAListMutation)
+    {
+        self.mutation = mutation;
+    }
+}
+impl<G: GAListGene> GAFactory<GAListGenome<G>> for GAListFactory<G>
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GAListGenome<G>>
+    {
+        let mut genomes = vec![];
+        for _ in 0..n
+        {
+            let mut genome = GAListGenome::random(self.min_length, self.max_length, self.objective.clone(), rng_ctx);
+            genome.set_mutation_operator(self.mutation);
+            genomes.push(genome);
+        }
+
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    struct Digit(u8);
+    impl GAListGene for Digit
+    {
+        fn random(rng_ctx: &mut GARandomCtx) -> Digit
+        {
+            Digit(rng_ctx.gen_range(0, 10))
+        }
+    }
+
+    fn zero() -> GAListObjective<Digit>
+    {
+        Arc::new(|_: &[Digit]| 0.0)
+    }
+
+    fn digits(ds: &[u8]) -> Vec<Digit>
+    {
+        ds.iter().map(|&d| Digit(d)).collect()
+    }
+
+    #[test]
+    fn cut_and_splice()
+    {
+        ga_test_setup("ga_list::cut_and_splice");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mom = GAListGenome::new(digits(&[1, 1, 1, 1, 1]), 2, 8, zero());
+        let dad = GAListGenome::new(digits(&[2, 2, 2, 2, 2, 2]), 2, 8, zero());
+
+        let mut lengths = vec![];
+        for _ in 0..50
+        {
+            let c = mom.crossover(&dad, &mut rng_ctx);
+            assert!(c.len() >= 2 && c.len() <= 8);
+            // Mom's genes, then dad's; unless it was too short and got
+            // random genes appended.
+            let ones = c.genes().iter().take_while(|&&d| d == Digit(1)).count();
+            let twos = c.genes()[ones..].iter().take_while(|&&d| d == Digit(2)).count();
+            assert!(ones <= 5 && twos <= 6);
+            assert!(ones + twos == c.len() || c.len() == 2);
+            lengths.push(c.len());
+        }
+        lengths.sort();
+        lengths.dedup();
+        assert!(lengths.len() > 3);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn repair()
+    {
+        ga_test_setup("ga_list::repair");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mut g = GAListGenome::new(digits(&[1, 2, 3]), 3, 3, zero());
+        g.genes.push(Digit(4));
+        g.repair(&mut rng_ctx);
+        assert_eq!(g.genes(), &digits(&[1, 2, 3])[..]);
+
+        g.genes.clear();
+        g.repair(&mut rng_ctx);
+        assert_eq!(g.len(), 3);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn mutation_operators()
+    {
+        ga_test_setup("ga_list::mutation_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let start = digits(&[0, 1, 2, 3, 4, 5]);
+
+        let mutated = |mutation, rng_ctx: &mut GARandomCtx|
+        {
+            let mut g = GAListGenome::new(start.clone(), 0, 10, zero());
+            g.set_mutation_operator(mutation);
+            g.mutate(0.0, rng_ctx);
+            assert_eq!(g.genes(), &start[..]);
+            g.mutate(1.0, rng_ctx);
+            assert!(g.len() <= 10);
+            g
+        };
+
+        assert_eq!(mutated(GAListMutation::Insert, &mut rng_ctx).len(), 10);
+        assert!(mutated(GAListMutation::Delete, &mut rng_ctx).is_empty());
+
+        let mut swapped = mutated(GAListMutation::Swap, &mut rng_ctx).genes().to_vec();
+        swapped.sort_by_key(|d| d.0);
+        assert_eq!(swapped, start);
+
+        assert_eq!(mutated(GAListMutation::Alter, &mut rng_ctx).len(), 6);
+
+        // Inserting into an empty list.
+        let mut g = GAListGenome::new(vec![], 0, 10, zero());
+        g.set_mutation_operator(GAListMutation::Insert);
+        g.mutate(1.0, &mut rng_ctx);
+        assert_eq!(g.len(), 1);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn simple_ga_with_factory()
+    {
+        ga_test_setup("ga_list::simple_ga_with_factory");
+        // Lists adding up to 42.
+        let mut factory = GAListFactory::new(1, 20, |ds: &[Digit]|
+        {
+            let sum : i32 = ds.iter().map(|d| d.0 as i32).sum();
+            1.0 / (1.0 + (sum - 42).abs() as f32)
+        });
+
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let pop = factory.random_population(20, GAPopulationSortOrder::HighIsBest, &mut rng_ctx);
+        assert!(pop.individuals().iter().all(|g| g.len() >= 1 && g.len() <= 20));
+
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 50,
+                                                   population_size: 30,
+                                                   probability_crossover: 0.9,
+                                                   probability_mutation: 0.05,
+                                                   elitism: true,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GAListGenome<Digit>>),
+                                                 None);
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        let best = ga.population().best_by_raw_score();
+        assert!(best.raw() >= initial_best);
+        assert_eq!(best.raw(), 1.0, "{:?}", best);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_list::compare");
+        let list = |digits: &[u8]| GAListGenome::new(digits.iter().map(|&d| Digit(d)).collect(), 0, 10, zero());
+
+        let a = list(&[1, 2, 3, 4]);
+        assert_eq!(a.compare(&a.clone()), 0.0);
+        // 1 substitution.
+        assert_eq!(a.compare(&list(&[1, 2, 5, 4])), 0.25);
+        // 1 insertion, over the length of the longer list.
+        assert_eq!(a.compare(&list(&[1, 2, 3, 4, 5])), 0.2);
+        // 1 deletion: no worse than a substitution.
+        assert_eq!(a.compare(&list(&[2, 3, 4])), 0.25);
+        assert_eq!(a.compare(&list(&[5, 6, 7, 8])), 1.0);
+        assert_eq!(a.compare(&list(&[])), 1.0);
+        assert_eq!(list(&[]).compare(&list(&[])), 0.0);
+        ga_test_teardown();
+    }
+}
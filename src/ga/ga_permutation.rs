@@ -0,0 +1,639 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Permutation Genome
+//!
+//! An ordering of the numbers 0..n, for routing and scheduling problems.
+//! Every crossover and mutation operator produces a valid permutation.
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Objective function of a Permutation Genome. Takes the permutation and
+/// returns the raw score (and the fitness) of the genome.
+pub type GAPermutationObjective = Arc<dyn Fn(&[usize]) -> f32 + Send + Sync>;
+
+/// Permutation Crossover
+///
+/// Crossover operator used by `GAIndividual::crossover`. In the
+/// descriptions, the first parent is the genome crossover is called on.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAPermutationCrossover
+{
+    /// Partially mapped crossover: a random segment of the first parent is
+    /// kept in place; the other positions are taken from the second parent,
+    /// following the mapping defined by the segment on conflicts.
+    #[default]
+    PartiallyMapped,
+    /// Order crossover: a random segment of the first parent is kept in
+    /// place; the other positions are filled with the missing values in
+    /// the order they appear in the second parent.
+    Order,
+    /// Cycle crossover: every value keeps the position it has in one of the
+    /// parents. Cycles of positions are taken alternately from each parent.
+    Cycle,
+    /// Edge recombination: the child is built from the adjacencies (edges)
+    /// of both parents, preferring values with the fewest edges left.
+    EdgeRecombination,
+}
+
+/// Permutation Mutation
+///
+/// Mutation operator used by `GAIndividual::mutate`. A genome mutates with
+/// probability `p_mutation`; a mutation is a single move.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAPermutationMutation
+{
+    /// Swap two random positions.
+    #[default]
+    Swap,
+    /// Move a random value to a random position.
+    Insert,
+    /// Reverse a random segment.
+    Inversion,
+    /// Shuffle a random segment.
+    Scramble,
+}
+
+/// Permutation Genome
+#[derive(Clone)]
+pub struct GAPermutationGenome
+{
+    order: Vec<usize>,
+    crossover: GAPermutationCrossover,
+    mutation: GAPermutationMutation,
+    objective: GAPermutationObjective,
+    raw: f32,
+    fitness: f32,
+}
+impl GAPermutationGenome
+{
+    /// Genome with the given ordering, which has to be a permutation of
+    /// 0..order.len().
+    pub fn new(order: Vec<usize>, objective: GAPermutationObjective) -> GAPermutationGenome
+    {
+        assert!(is_permutation(&order), "Permutation Genome - {:?} is not a permutation", order);
+        GAPermutationGenome
+        {
+            order,
+            crossover: GAPermutationCrossover::default(),
+            mutation: GAPermutationMutation::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// Genome with the ordering 0, 1, ..., n - 1.
+    pub fn identity(n: usize, objective: GAPermutationObjective) -> GAPermutationGenome
+    {
+        GAPermutationGenome::new((0..n).collect(), objective)
+    }
+
+    /// Genome with a random ordering of 0..n.
+    pub fn random(n: usize, objective: GAPermutationObjective, rng_ctx: &mut GARandomCtx) -> GAPermutationGenome
+    {
+        let mut order : Vec<usize> = (0..n).collect();
+        shuffle(&mut order, rng_ctx);
+        GAPermutationGenome::new(order, objective)
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.order.is_empty()
+    }
+
+    pub fn order(&self) -> &[usize]
+    {
+        &self.order
+    }
+
+    pub fn crossover_operator(&self) -> GAPermutationCrossover
+    {
+        self.crossover
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GAPermutationCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    pub fn mutation_operator(&self) -> GAPermutationMutation
+    {
+        self.mutation
+    }
+
+    pub fn set_mutation_operator(&mut self, mutation: GAPermutationMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    /// Partially mapped crossover (PMX).
+    pub fn partially_mapped_crossover(&self, other: &GAPermutationGenome, rng_ctx: &mut GARandomCtx) -> GAPermutationGenome
+    {
+        assert_eq!(self.len(), other.len());
+        let (a, b) = segment(self.len(), rng_ctx);
+
+        // Start from the second parent and move the segment's values into
+        // place; the values they displace go where the moved ones were.
+        let mut order = other.order.clone();
+        let mut position = positions(&order);
+        for i in a..b
+        {
+            let v = self.order[i];
+            let j = position[v];
+            order.swap(i, j);
+            position[order[j]] = j;
+            position[v] = i;
+        }
+        self.child(order)
+    }
+
+    /// Order crossover (OX).
+    pub fn order_crossover(&self, other: &GAPermutationGenome, rng_ctx: &mut GARandomCtx) -> GAPermutationGenome
+    {
+        assert_eq!(self.len(), other.len());
+        let n = self.len();
+        let (a, b) = segment(n, rng_ctx);
+
+        let mut used = vec![false; n];
+        for &v in &self.order[a..b]
+        {
+            used[v] = true;
+        }
+
+        // Fill from the end of the segment on, wrapping around, reading the
+        // second parent from the same point.
+        let mut order = self.order.clone();
+        let mut next = b;
+        for k in 0..n
+        {
+            let v = other.order[(b + k) % n];
+            if !used[v]
+            {
+                order[next % n] = v;
+                next += 1;
+            }
+        }
+        self.child(order)
+    }
+
+    /// Cycle crossover (CX).
+    pub fn cycle_crossover(&self, other: &GAPermutationGenome) -> GAPermutationGenome
+    {
+        assert_eq!(self.len(), other.len());
+        let n = self.len();
+        let position = positions(&self.order);
+
+        let mut order = vec![0; n];
+        let mut visited = vec![false; n];
+        let mut from_self = true;
+        for start in 0..n
+        {
+            if visited[start]
+            {
+                continue;
+            }
+
+            let mut i = start;
+            while !visited[i]
+            {
+                visited[i] = true;
+                order[i] = if from_self { self.order[i] } else { other.order[i] };
+                i = position[other.order[i]];
+            }
+            from_self = !from_self;
+        }
+        self.child(order)
+    }
+
+    /// Edge recombination crossover (ERX).
+    pub fn edge_recombination_crossover(&self, other: &GAPermutationGenome, rng_ctx: &mut GARandomCtx) -> GAPermutationGenome
+    {
+        assert_eq!(self.len(), other.len());
+        let n = self.len();
+        if n == 0
+        {
+            return self.child(vec![]);
+        }
+
+        // Neighbours of each value in either parent (tours are cyclic).
+        let mut edges : Vec<Vec<usize>> = vec![vec![]; n];
+        for parent in &[&self.order, &other.order]
+        {
+            for i in 0..n
+            {
+                let v = parent[i];
+                for &w in &[parent[(i + n - 1) % n], parent[(i + 1) % n]]
+                {
+                    if w != v && !edges[v].contains(&w)
+                    {
+                        edges[v].push(w);
+                    }
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(n);
+        let mut visited = vec![false; n];
+        let mut current = self.order[0];
+        loop
+        {
+            order.push(current);
+            visited[current] = true;
+            if order.len() == n
+            {
+                break;
+            }
+
+            for e in &mut edges
+            {
+                e.retain(|&w| w != current);
+            }
+
+            // Neighbour with the fewest edges left, ties broken at random;
+            // a random unvisited value when there are no neighbours left.
+            let fewest = edges[current].iter().map(|&w| edges[w].len()).min();
+            current = match fewest
+            {
+                Some(fewest) =>
+                {
+                    let candidates : Vec<usize> = edges[current].iter().cloned()
+                                                                .filter(|&w| edges[w].len() == fewest)
+                                                                .collect();
+                    candidates[rng_ctx.gen_range(0, candidates.len())]
+                },
+                None =>
+                {
+                    let candidates : Vec<usize> = (0..n).filter(|&w| !visited[w]).collect();
+                    candidates[rng_ctx.gen_range(0, candidates.len())]
+                }
+            };
+        }
+        self.child(order)
+    }
+
+    // Genome with 'order' and the operators of self.
+    fn child(&self, order: Vec<usize>) -> GAPermutationGenome
+    {
+        debug_assert!(is_permutation(&order));
+        GAPermutationGenome { order, raw: 0.0, fitness: 0.0, .. self.clone() }
+    }
+}
+impl GAIndividual for GAPermutationGenome
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GAPermutationGenome, rng_ctx: &mut GARandomCtx) -> Box<GAPermutationGenome>
+    {
+        let child = match self.crossover
+        {
+            GAPermutationCrossover::PartiallyMapped => self.partially_mapped_crossover(other, rng_ctx),
+            GAPermutationCrossover::Order => self.order_crossover(other, rng_ctx),
+            GAPermutationCrossover::Cycle => self.cycle_crossover(other),
+            GAPermutationCrossover::EdgeRecombination => self.edge_recombination_crossover(other, rng_ctx),
+        };
+        Box::new(child)
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        let n = self.order.len();
+        if n < 2 || !rng_ctx.test_value(p_mutation)
+        {
+            return 0;
+        }
+
+        match self.mutation
+        {
+            GAPermutationMutation::Swap =>
+            {
+                let i = rng_ctx.gen_range(0, n);
+                let j = (i + rng_ctx.gen_range(1, n)) % n;
+                self.order.swap(i, j);
+            },
+            GAPermutationMutation::Insert =>
+            {
+                let v = self.order.remove(rng_ctx.gen_range(0, n));
+                self.order.insert(rng_ctx.gen_range(0, n), v);
+            },
+            GAPermutationMutation::Inversion =>
+            {
+                let (a, b) = segment(n, rng_ctx);
+                self.order[a..b].reverse();
+            },
+            GAPermutationMutation::Scramble =>
+            {
+                let (a, b) = segment(n, rng_ctx);
+                shuffle(&mut self.order[a..b], rng_ctx);
+            },
+        }
+        1
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        self.evaluate_shared(&());
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Fraction of positions holding different elements. Permutations of
+    /// different lengths can't be compared.
+    fn compare(&self, other: &GAPermutationGenome) -> f32
+    {
+        if self.order.len() != other.order.len()
+        {
+            return -1.0;
+        }
+        if self.order.is_empty()
+        {
+            return 0.0;
+        }
+
+        let different = self.order.iter().zip(other.order.iter()).filter(|&(a, b)| a != b).count();
+        different as f32 / self.order.len() as f32
+    }
+}
+impl GAParallelIndividual for GAPermutationGenome
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        let score = (self.objective)(&self.order);
+        self.raw = score;
+        self.fitness = score;
+    }
+}
+impl PartialEq for GAPermutationGenome
+{
+    fn eq(&self, other: &GAPermutationGenome) -> bool
+    {
+        self.order == other.order
+    }
+}
+impl fmt::Debug for GAPermutationGenome
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GAPermutationGenome({:?}) raw: {} fitness: {}", self.order, self.raw, self.fitness)
+    }
+}
+
+/// Permutation Genome Factory
+///
+/// Creates genomes with random orderings of 0..n.
+pub struct GAPermutationFactory
+{
+    n: usize,
+    objective: GAPermutationObjective,
+    crossover: GAPermutationCrossover,
+    mutation: GAPermutationMutation,
+}
+impl GAPermutationFactory
+{
+    pub fn new<F>(n: usize, objective: F) -> GAPermutationFactory
+        where F: Fn(&[usize]) -> f32 + Send + Sync + 'static
+    {
+        GAPermutationFactory
+        {
+            n,
+            objective: Arc::new(objective),
+            crossover: GAPermutationCrossover::default(),
+            mutation: GAPermutationMutation::default(),
+        }
+    }
+
+    /// Crossover operator of the genomes created from now on.
+    pub fn set_crossover_operator(&mut self, crossover: GAPermutationCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    /// Mutation operator of the genomes created from now on.
+    pub fn set_mutation_operator(&mut self, mutation: GAPermutationMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    pub fn random_genome(&mut self, rng_ctx: &mut GARandomCtx) -> GAPermutationGenome
+    {
+        let mut genome = GAPermutationGenome::random(self.n, self.objective.clone(), rng_ctx);
+        genome.set_crossover_operator(self.crossover);
+        genome.set_mutation_operator(self.mutation);
+        genome
+    }
+}
+impl GAFactory<GAPermutationGenome> for GAPermutationFactory
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GAPermutationGenome>
+    {
+        let genomes = (0..n).map(|_| self.random_genome(rng_ctx)).collect();
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+fn is_permutation(order: &[usize]) -> bool
+{
+    let mut seen = vec![false; order.len()];
+    for &v in order
+    {
+        if v >= order.len() || seen[v]
+        {
+            return false;
+        }
+        seen[v] = true;
+    }
+    true
+}
+
+// position[v] is the index of v in 'order'.
+fn positions(order: &[usize]) -> Vec<usize>
+{
+    let mut position = vec![0; order.len()];
+    for (i, &v) in order.iter().enumerate()
+    {
+        position[v] = i;
+    }
+    position
+}
+
+// Random segment [a, b) of a sequence of length n.
+fn segment(n: usize, rng_ctx: &mut GARandomCtx) -> (usize, usize)
+{
+    let a = rng_ctx.gen_range(0, n + 1);
+    let b = rng_ctx.gen_range(0, n + 1);
+    (a.min(b), a.max(b))
+}
+
+// Fisher-Yates shuffle.
+fn shuffle(values: &mut [usize], rng_ctx: &mut GARandomCtx)
+{
+    for i in (1..values.len()).rev()
+    {
+        let j = rng_ctx.gen_range(0, i + 1);
+        values.swap(i, j);
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_random::*;
+    use super::*;
+
+    fn zero() -> GAPermutationObjective
+    {
+        Arc::new(|_: &[usize]| 0.0)
+    }
+
+    // Unordered pairs of consecutive values, tour closed.
+    fn edges(order: &[usize]) -> Vec<(usize, usize)>
+    {
+        let n = order.len();
+        (0..n).map(|i| (order[i].min(order[(i + 1) % n]), order[i].max(order[(i + 1) % n]))).collect()
+    }
+
+    #[test]
+    fn construction()
+    {
+        ga_test_setup("ga_permutation::construction");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        assert_eq!(GAPermutationGenome::identity(4, zero()).order(), &[0, 1, 2, 3]);
+        for n in 0..10
+        {
+            assert!(is_permutation(GAPermutationGenome::random(n, zero(), &mut rng_ctx).order()));
+        }
+        assert!(!is_permutation(&[0, 2]));
+        assert!(!is_permutation(&[1, 1]));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_permutation::compare");
+        let a = GAPermutationGenome::identity(4, zero());
+        assert_eq!(a.compare(&GAPermutationGenome::new(vec![1, 0, 2, 3], zero())), 0.5);
+        assert_eq!(a.compare(&GAPermutationGenome::new(vec![3, 2, 1, 0], zero())), 1.0);
+        assert_eq!(a.compare(&GAPermutationGenome::identity(3, zero())), -1.0);
+        ga_test_teardown();
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_permutation()
+    {
+        ga_test_setup("ga_permutation::not_a_permutation");
+        GAPermutationGenome::new(vec![0, 1, 1], zero());
+        // Not reached
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn crossover_operators()
+    {
+        ga_test_setup("ga_permutation::crossover_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for n in 1..20
+        {
+            let mom = GAPermutationGenome::random(n, zero(), &mut rng_ctx);
+            let dad = GAPermutationGenome::random(n, zero(), &mut rng_ctx);
+
+            let c = mom.partially_mapped_crossover(&dad, &mut rng_ctx);
+            assert!(is_permutation(c.order()));
+
+            let c = mom.order_crossover(&dad, &mut rng_ctx);
+            assert!(is_permutation(c.order()));
+
+            // Every value keeps the position it has in one of the parents.
+            let c = mom.cycle_crossover(&dad);
+            assert!(is_permutation(c.order()));
+            assert!((0..n).all(|i| c.order()[i] == mom.order()[i] || c.order()[i] == dad.order()[i]));
+
+            let c = mom.edge_recombination_crossover(&dad, &mut rng_ctx);
+            assert!(is_permutation(c.order()));
+            assert_eq!(c.order()[0], mom.order()[0]);
+
+            // Same tour from the same parents.
+            let c = mom.edge_recombination_crossover(&mom, &mut rng_ctx);
+            let mut c_edges = edges(c.order());
+            let mut mom_edges = edges(mom.order());
+            c_edges.sort();
+            mom_edges.sort();
+            assert_eq!(c_edges, mom_edges);
+
+            for &crossover in &[GAPermutationCrossover::PartiallyMapped, GAPermutationCrossover::Order,
+                                GAPermutationCrossover::Cycle, GAPermutationCrossover::EdgeRecombination]
+            {
+                let mut m = mom.clone();
+                m.set_crossover_operator(crossover);
+                let c = m.crossover(&dad, &mut rng_ctx);
+                assert!(is_permutation(c.order()));
+                assert_eq!(c.crossover_operator(), crossover);
+                if crossover != GAPermutationCrossover::EdgeRecombination
+                {
+                    assert_eq!(m.crossover(&mom, &mut rng_ctx).order(), mom.order());
+                }
+            }
+        }
+
+        // Cycles of [0 1 2 3 4] / [1 0 3 4 2]: {0, 1} from mom, {2, 3, 4}
+        // from dad.
+        let mom = GAPermutationGenome::identity(5, zero());
+        let dad = GAPermutationGenome::new(vec![1, 0, 3, 4, 2], zero());
+        assert_eq!(mom.cycle_crossover(&dad).order(), &[0, 1, 3, 4, 2]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn mutation_operators()
+    {
+        ga_test_setup("ga_permutation::mutation_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for &mutation in &[GAPermutationMutation::Swap, GAPermutationMutation::Insert,
+                           GAPermutationMutation::Inversion, GAPermutationMutation::Scramble]
+        {
+            let mut g = GAPermutationGenome::identity(10, zero());
+            g.set_mutation_operator(mutation);
+
+            assert_eq!(g.mutate(0.0, &mut rng_ctx), 0);
+            assert_eq!(g.order(), GAPermutationGenome::identity(10, zero()).order());
+
+            let mut changed = false;
+            for _ in 0..20
+            {
+                let before = g.order().to_vec();
+                assert_eq!(g.mutate(1.0, &mut rng_ctx), 1);
+                assert!(is_permutation(g.order()));
+                changed |= before != g.order();
+
+                if mutation == GAPermutationMutation::Swap
+                {
+                    assert_eq!((0..10).filter(|&i| before[i] != g.order()[i]).count(), 2);
+                }
+            }
+            assert!(changed);
+        }
+
+        let mut g = GAPermutationGenome::identity(1, zero());
+        assert_eq!(g.mutate(1.0, &mut rng_ctx), 0);
+        assert_eq!(g.order(), &[0]);
+        ga_test_teardown();
+    }
+}
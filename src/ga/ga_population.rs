@@ -4,26 +4,56 @@
 
 //! Genetic Algorithm Population
 
-use ::ga::ga_core::GAIndividual;
+use ::ga::ga_core::{GAError, GAIndividual, GAParallelIndividual};
 use ::ga::ga_random::GARandomCtx;
+use ::ga::ga_replacement::GAReplacement;
+use ::ga::ga_scaling::GAScaling;
 
 use std::cmp::{Ordering};
 use std::iter::FromIterator;
-use std::any::Any;
 use std::option::Option;
 use std::f32;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+// Shared, not boxed, so that cloned populations (e.g. the all-time best one
+// kept by the statistics) keep scaling the same way.
+type GAPopulationScaling<T> = Arc<dyn GAScaling<T> + Send + Sync>;
 
 // Better name than 'Basis'?
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GAPopulationSortBasis
 {
+    #[default]
     Raw,
     Fitness,
 }
 
+/// Population diversity mode.
+///
+/// How `GAPopulation::compute_diversity` chooses the pairs of individuals it
+/// compares.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GADiversityMode
+{
+    /// Every pair of individuals; n*(n-1)/2 comparisons.
+    #[default]
+    Exact,
+    /// 'pairs' pairs of different individuals, drawn at random. For large
+    /// populations, where comparing every pair is too expensive.
+    Sampled { pairs: usize },
+}
+
 // The 'Copy' trait requires the 'Clone' trait.
 // 'Copy' removes the 'move' semantics from an assignment or a function return of value.
 #[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GAPopulationSortOrder
 {
     LowIsBest,
@@ -36,6 +66,14 @@ impl Default for GAPopulationSortOrder
 }
 
 /// Genetic Algorithm Population
+///
+/// With the `serde` feature, a population of individuals that can be
+/// serialized can be serialized too: its individuals, sort order and
+/// diversity. Sort orders and statistics are derived again after
+/// deserializing. The scaling scheme isn't serialized; set it again.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>")))]
+#[cfg_attr(feature = "serde", serde(from = "GAPopulationData<T>"))]
 pub struct GAPopulation<T: GAIndividual>
 {
     population: Vec<T>,
@@ -43,21 +81,57 @@ pub struct GAPopulation<T: GAIndividual>
     sort_order: GAPopulationSortOrder,
 
     // 'population' ordered by raw score.
+    #[cfg_attr(feature = "serde", serde(skip))]
     population_order_raw: Vec<usize>,
     // Is 'population_order_raw' sorted?
+    #[cfg_attr(feature = "serde", serde(skip))]
     is_raw_sorted: bool,
 
     // 'population' ordered by fitness score.
+    #[cfg_attr(feature = "serde", serde(skip))]
     population_order_fitness: Vec<usize>,
     // Is 'population_order_fitness' sorted?
+    #[cfg_attr(feature = "serde", serde(skip))]
     is_fitness_sorted: bool,
 
     // We keep 2 lists of indexes to the population vector.
     // One sorted by raw score and one by fitness score.
 
     // `None` if statistics haven't been computed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     statistics: Option<GAPopulationStats>,
+
+    // `None` if diversity hasn't been measured.
+    diversity: Option<f32>,
+
+    // Turns raw scores into fitness scores after every evaluation.
+    // `None` leaves fitness as set by the individuals themselves.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scaling: Option<GAPopulationScaling<T>>,
+}
+// What a serialized GAPopulation holds. Anything derived from the
+// individuals, like the sort orders, is computed again instead of trusted.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GAPopulationData<T>
+{
+    population: Vec<T>,
+    sort_order: GAPopulationSortOrder,
+    diversity: Option<f32>,
 }
+
+#[cfg(feature = "serde")]
+impl<T: GAIndividual> From<GAPopulationData<T>> for GAPopulation<T>
+{
+    fn from(data: GAPopulationData<T>) -> GAPopulation<T>
+    {
+        let mut pop = GAPopulation::new(data.population, data.sort_order);
+        pop.diversity = data.diversity;
+        pop.sort();
+        pop
+    }
+}
+
 impl<T: GAIndividual> GAPopulation<T>
 {
     // TODO: New should use some parameters, maybe a Config
@@ -71,7 +145,9 @@ impl<T: GAIndividual> GAPopulation<T>
             is_raw_sorted: false,
             population_order_fitness: vec![],
             is_fitness_sorted: false,
-            statistics: None
+            statistics: None,
+            diversity: None,
+            scaling: None
         }
     }
 
@@ -80,12 +156,99 @@ impl<T: GAIndividual> GAPopulation<T>
         return &mut self.population
     }
 
-    pub fn evaluate(&mut self, evaluation_ctx: &mut Any)
+    // Individuals in insertion (unsorted) order.
+    pub fn individuals(&self) -> &[T]
+    {
+        &self.population
+    }
+
+    // A population with the same sort order and scaling scheme as this one,
+    // made of 'individuals'.
+    pub fn next_generation(&self, individuals: Vec<T>) -> GAPopulation<T>
+    {
+        let mut pop = GAPopulation::new(individuals, self.sort_order);
+        pop.scaling = self.scaling.clone();
+        pop
+    }
+
+    // Scaling scheme used by scale(). It is applied on the next evaluation,
+    // or right away by calling scale().
+    pub fn set_scaling(&mut self, scaling: Box<dyn GAScaling<T> + Send + Sync>)
+    {
+        self.scaling = Some(Arc::from(scaling));
+    }
+
+    pub fn has_scaling(&self) -> bool
+    {
+        self.scaling.is_some()
+    }
+
+    // Recompute fitness scores from raw scores with the population's scaling
+    // scheme. Without one, fitness is left as the individuals set it.
+    pub fn scale(&mut self)
+    {
+        if let Some(scaling) = self.scaling.clone()
+        {
+            scaling.evaluate(self);
+        }
+    }
+
+    // Raw scores have changed: every order and cached measure is stale, and
+    // fitness has to be derived again.
+    fn raw_changed(&mut self)
+    {
+        self.is_raw_sorted = false;
+        self.is_fitness_sorted = false;
+        self.statistics = None;
+        self.scale();
+    }
+
+    pub fn evaluate(&mut self, evaluation_ctx: &mut T::EvaluationCtx)
     {
         for ref mut ind in &mut self.population
         {
             ind.evaluate(evaluation_ctx);
         }
+
+        self.raw_changed();
+    }
+
+    // Evaluate the population with 'workers' threads, each one evaluating a
+    // contiguous chunk of individuals against the shared 'evaluation_ctx'.
+    //
+    // Individuals are evaluated independently of each other, so results don't
+    // depend on the number of workers. 0 or 1 workers evaluate in the calling
+    // thread.
+    pub fn evaluate_parallel(&mut self, evaluation_ctx: &T::EvaluationCtx, workers: usize)
+        where T: GAParallelIndividual, T::EvaluationCtx: Sync
+    {
+        let size = self.size();
+        if workers <= 1 || size <= 1
+        {
+            for ind in &mut self.population
+            {
+                ind.evaluate_shared(evaluation_ctx);
+            }
+            self.raw_changed();
+            return;
+        }
+
+        let chunk_size = size.div_ceil(workers);
+        thread::scope(|scope|
+        {
+            for chunk in self.population.chunks_mut(chunk_size)
+            {
+                scope.spawn(move ||
+                {
+                    for ind in chunk
+                    {
+                        ind.evaluate_shared(evaluation_ctx);
+                    }
+                });
+            }
+        });
+
+        self.raw_changed();
     }
 
     pub fn size(&self) -> usize
@@ -120,43 +283,51 @@ impl<T: GAIndividual> GAPopulation<T>
     }
 
     //TODO: this is a temporary implementation
-    pub fn select(&self) -> &T
+    pub fn select(&self) -> Result<&T, GAError>
     {
         self.individual(0, GAPopulationSortBasis::Fitness)
     }
 
     //TODO: This is a temporary implementation 
-    pub fn best(&self, i: usize, sort_basis: GAPopulationSortBasis) -> &T
+    pub fn best(&self, i: usize, sort_basis: GAPopulationSortBasis) -> Result<&T, GAError>
     {
-        // TODO: Call GAPopulation.scale().
-
+        // Fitness is rescaled whenever the population is evaluated (see
+        // scale()), so it is up to date here.
         self.individual(i, sort_basis)
     }
 
     //TODO: This is a temporary implementation 
-    pub fn worst(&self) -> &T
+    pub fn worst(&self) -> Result<&T, GAError>
     {
+        if self.size() == 0
+        {
+            return Err(GAError::EmptyPopulation);
+        }
         self.individual(self.size()-1, GAPopulationSortBasis::Fitness)
     }
 
+    // The best_by/worst_by shortcuts below are for populations known to be
+    // sorted and not empty: they panic otherwise. Use individual() to handle
+    // those cases.
+
     pub fn best_by_raw_score(&self) -> &T
     {
-        self.individual(0, GAPopulationSortBasis::Raw)
+        self.kth_best_by_raw_score(0)
     }
 
     pub fn best_by_raw_score_mut(&mut self) -> &mut T
     {
-        self.individual_mut(0, GAPopulationSortBasis::Raw)
+        self.ranked_mut_or_panic(0, GAPopulationSortBasis::Raw)
     }
 
     pub fn kth_best_by_raw_score(&self, k: usize) -> &T
     {
-        self.individual(k, GAPopulationSortBasis::Raw)
+        self.ranked_or_panic(k, GAPopulationSortBasis::Raw)
     }
 
     pub fn worst_by_raw_score(&self) -> &T
     {
-        self.individual(self.size()-1, GAPopulationSortBasis::Raw)
+        self.ranked_or_panic(self.size().wrapping_sub(1), GAPopulationSortBasis::Raw)
     }
 
     pub fn worst_by_raw_score_mut(&mut self) -> &mut T
@@ -166,17 +337,35 @@ impl<T: GAIndividual> GAPopulation<T>
         // (from size()) and mutable (from individual_mut()) borrows
         // of self.
         let size = self.size();
-        self.individual_mut(size-1, GAPopulationSortBasis::Raw)
+        self.ranked_mut_or_panic(size.wrapping_sub(1), GAPopulationSortBasis::Raw)
     }
 
     pub fn best_by_fitness_score(&self) -> &T
     {
-        self.individual(0, GAPopulationSortBasis::Fitness)
+        self.ranked_or_panic(0, GAPopulationSortBasis::Fitness)
     }
 
     pub fn worst_by_fitness_score(&self) -> &T
     {
-        self.individual(self.size()-1, GAPopulationSortBasis::Fitness)
+        self.ranked_or_panic(self.size().wrapping_sub(1), GAPopulationSortBasis::Fitness)
+    }
+
+    fn ranked_or_panic(&self, i: usize, sort_basis: GAPopulationSortBasis) -> &T
+    {
+        match self.individual(i, sort_basis)
+        {
+            Ok(ind) => ind,
+            Err(e) => panic!("GAPopulation - {}", e)
+        }
+    }
+
+    fn ranked_mut_or_panic(&mut self, i: usize, sort_basis: GAPopulationSortBasis) -> &mut T
+    {
+        match self.individual_mut(i, sort_basis)
+        {
+            Ok(ind) => ind,
+            Err(e) => panic!("GAPopulation - {}", e)
+        }
     }
 
     // NOTE:
@@ -228,31 +417,97 @@ impl<T: GAIndividual> GAPopulation<T>
         self.is_fitness_sorted = false;
         self.population_order_raw.clear();
         self.population_order_fitness.clear();
+        self.statistics = None;
+        self.diversity = None;
         drained
     }
 
-    pub fn individual(&self, i : usize, sort_basis : GAPopulationSortBasis) -> &T
+    // Add individuals to the population.
+    //
+    // Sort orders and statistics are invalidated; sort() needs to be called
+    // before ranked access.
+    pub fn add_individuals(&mut self, mut new_individuals: Vec<T>)
     {
-        // TODO: Check that i makes sense
-        match sort_basis
+        self.population.append(&mut new_individuals);
+        self.is_raw_sorted = false;
+        self.is_fitness_sorted = false;
+        self.statistics = None;
+        self.diversity = None;
+    }
+
+    // The ith individual according to 'sort_basis'.
+    //
+    // Fails if 'i' is past the last individual, or if individuals or their
+    // scores changed since the population was last sorted on 'sort_basis'.
+    pub fn individual(&self, i : usize, sort_basis : GAPopulationSortBasis) -> Result<&T, GAError>
+    {
+        let inx = self.individual_index(i, sort_basis)?;
+        Ok(&self.population[inx])
+    }
+
+    pub fn individual_mut(&mut self, i : usize, sort_basis : GAPopulationSortBasis) -> Result<&mut T, GAError>
+    {
+        let inx = self.individual_index(i, sort_basis)?;
+        Ok(&mut self.population[inx])
+    }
+
+    // Position, in the (unsorted) individuals vector, of the ith individual
+    // according to 'sort_basis'.
+    pub fn individual_index(&self, i : usize, sort_basis : GAPopulationSortBasis) -> Result<usize, GAError>
+    {
+        if i >= self.size()
         {
-            GAPopulationSortBasis::Raw
-            => { &self.population[self.population_order_raw[i]] },
-            GAPopulationSortBasis::Fitness
-            => { &self.population[self.population_order_fitness[i]] },
+            Err(GAError::IndexOutOfBounds { index: i, size: self.size() })
+        }
+        else
+        {
+            self.sorted_order(sort_basis).map(|order| order[i])
         }
     }
 
-    pub fn individual_mut(&mut self, i : usize, sort_basis : GAPopulationSortBasis) -> &mut T
+    // Positions of the individuals, from best to worst according to
+    // 'sort_basis'. Fails if the population wasn't sorted since individuals
+    // were last added or removed.
+    fn sorted_order(&self, sort_basis : GAPopulationSortBasis) -> Result<&[usize], GAError>
     {
-        match sort_basis
+        let (order, is_sorted) = match sort_basis
         {
-            GAPopulationSortBasis::Raw
-            => { &mut self.population[self.population_order_raw[i]] },
-            GAPopulationSortBasis::Fitness
-            => { &mut self.population[self.population_order_fitness[i]] },
+            GAPopulationSortBasis::Raw => (&self.population_order_raw, self.is_raw_sorted),
+            GAPopulationSortBasis::Fitness => (&self.population_order_fitness, self.is_fitness_sorted),
+        };
+
+        if !is_sorted || order.len() != self.size()
+        {
+            Err(GAError::NotSorted)
         }
+        else
+        {
+            Ok(order)
+        }
+    }
 
+    // Position, in the (unsorted) individuals vector, of an individual
+    // borrowed from this population (e.g. one returned by a selector).
+    //
+    // Identity, not equality, is used: `None` if 'ind' doesn't live in
+    // this population.
+    pub fn index_of(&self, ind: &T) -> Option<usize>
+    {
+        self.population.iter().position(|p| ptr::eq(p, ind))
+    }
+
+    // Replace the individual at position 'inx' of the (unsorted) individuals
+    // vector and return the one that was replaced.
+    //
+    // Sort orders and statistics are invalidated; sort() needs to be called
+    // before ranked access.
+    pub fn replace_individual(&mut self, inx: usize, new_individual: T) -> T
+    {
+        self.is_raw_sorted = false;
+        self.is_fitness_sorted = false;
+        self.statistics = None;
+        self.diversity = None;
+        mem::replace(&mut self.population[inx], new_individual)
     }
 
     pub fn sort(&mut self)
@@ -322,22 +577,29 @@ impl<T: GAIndividual> GAPopulation<T>
         };
     }
 
-    pub fn raw_score_iterator<'a>(&'a self) -> GAPopulationRawIterator<'a, T>
+    // Iterate over the individuals from best to worst raw score. Fails with
+    // NotSorted if the population wasn't sorted since individuals were last
+    // added or removed.
+    pub fn raw_score_iterator<'a>(&'a self) -> Result<GAPopulationRawIterator<'a, T>, GAError>
     {
-        GAPopulationRawIterator { population: &self, next: 0 }
+        self.sorted_order(GAPopulationSortBasis::Raw)?;
+        Ok(GAPopulationRawIterator { population: &self, next: 0 })
     }
 
-    pub fn fitness_score_iterator<'a>(&'a self) -> GAPopulationFitnessIterator<'a, T>
+    // Iterate over the individuals from best to worst fitness score. Fails
+    // like raw_score_iterator().
+    pub fn fitness_score_iterator<'a>(&'a self) -> Result<GAPopulationFitnessIterator<'a, T>, GAError>
     {
-        GAPopulationFitnessIterator { population: &self, next: 0 }
+        self.sorted_order(GAPopulationSortBasis::Fitness)?;
+        Ok(GAPopulationFitnessIterator { population: &self, next: 0 })
     }
 
-    pub fn swap_individual(&mut self, new_individual: T)
+    pub fn swap_individual(&mut self, new_individual: T) -> Result<(), GAError>
     {
-        let mut should_swap = false;
+        let should_swap;
 
         {
-            let worst = self.worst();
+            let worst = self.worst()?;
             match self.sort_order
             {
                 GAPopulationSortOrder::LowIsBest =>
@@ -356,7 +618,27 @@ impl<T: GAIndividual> GAPopulation<T>
             self.population[self.population_order_fitness[l-1]] = new_individual;
             self.is_raw_sorted = false;
             self.is_fitness_sorted = false;
+            self.statistics = None;
+            self.diversity = None;
         }
+        Ok(())
+    }
+
+    // Insert 'new_individual', displacing the individual chosen by 'replacement'.
+    //
+    // swap_individual() is the special case of replacing the worst individual
+    // only when the new one is better. 'parents' are the positions of the
+    // individuals 'new_individual' was bred from (see index_of()).
+    //
+    // Returns the displaced individual, or None if the replacement scheme
+    // discarded 'new_individual'.
+    pub fn swap_individual_with<R>(&mut self, new_individual: T, parents: &[usize],
+                                   replacement: &mut R, rng_ctx: &mut GARandomCtx) -> Option<T>
+        where R: GAReplacement<T> + ?Sized
+    {
+        self.sort();
+        replacement.replacement_index(self, &new_individual, parents, rng_ctx)
+                   .map(|inx| self.replace_individual(inx, new_individual))
     }
 
     // Compute statistics of a population.
@@ -419,6 +701,8 @@ impl<T: GAIndividual> GAPopulation<T>
                     stats.raw_std_dev = stats.raw_var.sqrt();
                     stats.fitness_std_dev = stats.fitness_var.sqrt();
 
+                    stats.diversity = self.diversity.unwrap_or(-1.0);
+
                     // A clone will be owned by the population, to reuse in future calls.
                     self.statistics = Some(stats.clone());
 
@@ -437,11 +721,119 @@ impl<T: GAIndividual> GAPopulation<T>
         self.statistics = None;
     }
 
+    // Marks the fitness scores as stale. Scaling schemes call this after
+    // rewriting fitness so the fitness order and the cached statistics are
+    // recomputed on next access.
+    pub fn invalidate_fitness(&mut self)
+    {
+        self.is_fitness_sorted = false;
+        self.statistics = None;
+    }
+
+    // Last measured diversity: the mean distance between pairs of individuals.
+    //
+    // -1.0 is the recorded diversity value when diversity is not recorded.
+    // Diversity is measured by compute_diversity(), measure_diversity() or by
+    // a scaling scheme that already computes pairwise distances (see
+    // GASharing), and is forgotten when individuals are added, removed or
+    // replaced.
     pub fn diversity(&mut self) -> f32
     {
-        // Dummy implementation.
-        // -1.0 is the recorded diversity value when diversity is not recorded.
-        -1.0
+        self.diversity.unwrap_or(-1.0)
+    }
+
+    pub fn set_diversity(&mut self, diversity: f32)
+    {
+        self.record_diversity(Some(diversity));
+    }
+
+    // Keep the cached statistics in sync with the recorded diversity.
+    fn record_diversity(&mut self, diversity: Option<f32>)
+    {
+        self.diversity = diversity;
+        if let Some(ref mut stats) = self.statistics
+        {
+            stats.diversity = diversity.unwrap_or(-1.0);
+        }
+    }
+
+    // Measure and record diversity as the mean of GAIndividual::compare()
+    // over the pairs of individuals chosen by 'mode'. 'rng_ctx' is only
+    // used to draw pairs in GADiversityMode::Sampled.
+    //
+    // A population with fewer than 2 individuals has no diversity (0.0). If
+    // any 2 individuals can't be compared, diversity is not recorded and
+    // -1.0 is returned.
+    pub fn compute_diversity(&mut self, mode: GADiversityMode, rng_ctx: &mut GARandomCtx) -> f32
+    {
+        let size = self.size();
+        if size < 2
+        {
+            self.record_diversity(Some(0.0));
+            return 0.0;
+        }
+
+        let all_pairs = size*(size-1)/2;
+        let mut sum = 0.0;
+        let mut count = 0;
+        let mut add = |d: f32| -> bool
+        {
+            sum += d;
+            count += 1;
+            d >= 0.0
+        };
+
+        let comparable = match mode
+        {
+            GADiversityMode::Sampled { pairs } if pairs < all_pairs =>
+            {
+                (0 .. pairs.max(1)).all(|_|
+                {
+                    let i = rng_ctx.gen_range(0, size);
+                    // Any other individual, with the same probability.
+                    let j = (i + rng_ctx.gen_range(1, size)) % size;
+                    add(self.population[i].compare(&self.population[j]))
+                })
+            },
+
+            _ =>
+            {
+                (0 .. size).all(|i| (i+1 .. size).all(|j| add(self.population[i].compare(&self.population[j]))))
+            }
+        };
+
+        if comparable
+        {
+            let diversity = sum / count as f32;
+            self.record_diversity(Some(diversity));
+            diversity
+        }
+        else
+        {
+            self.record_diversity(None);
+            -1.0
+        }
+    }
+
+    // Measure and record diversity as the mean of 'distance' over every pair
+    // of individuals. A population with fewer than 2 individuals has no
+    // diversity (0.0).
+    pub fn measure_diversity<F>(&mut self, distance: F) -> f32
+        where F: Fn(&T, &T) -> f32
+    {
+        let size = self.size();
+        let mut sum = 0.0;
+        for i in 0 .. size
+        {
+            for j in i+1 .. size
+            {
+                sum += distance(&self.population[i], &self.population[j]);
+            }
+        }
+
+        let diversity = if size > 1 { sum / ((size*(size-1)/2) as f32) } else { 0.0 };
+        self.record_diversity(Some(diversity));
+        diversity
     }
 
     pub fn print_statistics(&self)
@@ -482,7 +874,9 @@ impl<T: GAIndividual + Clone> Clone for GAPopulation<T>
             is_raw_sorted: self.is_raw_sorted,
             population_order_fitness: self.population_order_fitness.clone(),
             is_fitness_sorted: self.is_fitness_sorted,
-            statistics: self.statistics.clone()
+            statistics: self.statistics.clone(),
+            diversity: self.diversity,
+            scaling: self.scaling.clone()
         }
     }
 }
@@ -499,9 +893,18 @@ impl<T: GAIndividual + PartialEq> PartialEq for GAPopulation<T>
         // If statistics contain INFs or NANs, this check will
         // fail. This happens when raw=0 and fitness=1/raw.
         && self.statistics == other.statistics
-        // FIXME: sort() must have been called to avoid panic.
-        && self.raw_score_iterator().eq(other.raw_score_iterator())
-        && self.fitness_score_iterator().eq(other.fitness_score_iterator())
+        // Sorted populations are equal if they rank the same individuals the
+        // same way; unsorted ones, if they hold the same individuals.
+        && match (self.raw_score_iterator(), other.raw_score_iterator())
+           {
+               (Ok(l), Ok(r)) => l.eq(r),
+               _ => self.population == other.population,
+           }
+        && match (self.fitness_score_iterator(), other.fitness_score_iterator())
+           {
+               (Ok(l), Ok(r)) => l.eq(r),
+               _ => self.population == other.population,
+           }
     }
 }
 
@@ -524,7 +927,7 @@ impl<'a, T: GAIndividual> Iterator for GAPopulationRawIterator<'a, T>
         else
         {
             self.next = self.next + 1;
-            Some(self.population.individual(self.next - 1, GAPopulationSortBasis::Raw)) 
+            Some(&self.population.population[self.population.population_order_raw[self.next - 1]])
         }
     }
 }
@@ -548,7 +951,7 @@ impl<'a, T: GAIndividual> Iterator for GAPopulationFitnessIterator<'a, T>
         else
         {
             self.next = self.next + 1;
-            Some(self.population.individual(self.next - 1, GAPopulationSortBasis::Fitness)) 
+            Some(&self.population.population[self.population.population_order_fitness[self.next - 1]])
         }
     }
 }
@@ -563,7 +966,11 @@ impl<'a, T: GAIndividual> Iterator for GAPopulationFitnessIterator<'a, T>
 /// Minimum
 /// Variance
 /// Standard deviation
+///
+/// And the diversity of the population, if it was measured (see
+/// `GAPopulation::diversity`).
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GAPopulationStats
 {
     pub raw_sum: f32,
@@ -579,6 +986,9 @@ pub struct GAPopulationStats
     pub fitness_min: f32,
     pub fitness_var: f32,
     pub fitness_std_dev: f32,
+
+    // -1.0 when not measured.
+    pub diversity: f32,
 }
 
 impl GAPopulationStats
@@ -600,6 +1010,8 @@ impl GAPopulationStats
             fitness_min: f32::INFINITY,
             fitness_var: 0.0,
             fitness_std_dev: 0.0,
+
+            diversity: -1.0,
         }
     }
 }
@@ -621,6 +1033,7 @@ impl PartialEq for GAPopulationStats
         && (self.fitness_min-other.fitness_min).abs() < error
         && (self.fitness_var-other.fitness_var).abs() < error
         && (self.fitness_std_dev-other.fitness_std_dev).abs() < error
+        && (self.diversity-other.diversity).abs() < error
     }
 }
 
@@ -633,6 +1046,7 @@ mod test
     use ::ga::ga_test::*;
     use ::ga::ga_core::*;
     use ::ga::ga_random::*;
+    use ::ga::ga_scaling::GANoScaling;
 
     use std::f32;
 
@@ -649,10 +1063,10 @@ mod test
         population.sort();
 
         //GATestIndividual's Fitness is the inverse of the Score (F = 1/S)
-        assert_eq!(population.individual(0, GAPopulationSortBasis::Raw).raw(), f);
-        assert_eq!(population.individual(1, GAPopulationSortBasis::Raw).raw(), f_m);
-        assert_eq!(population.individual(0, GAPopulationSortBasis::Fitness).fitness(), i_f_m);
-        assert_eq!(population.individual(1, GAPopulationSortBasis::Fitness).fitness(), i_f);
+        assert_eq!(population.individual(0, GAPopulationSortBasis::Raw).unwrap().raw(), f);
+        assert_eq!(population.individual(1, GAPopulationSortBasis::Raw).unwrap().raw(), f_m);
+        assert_eq!(population.individual(0, GAPopulationSortBasis::Fitness).unwrap().fitness(), i_f_m);
+        assert_eq!(population.individual(1, GAPopulationSortBasis::Fitness).unwrap().fitness(), i_f);
         ga_test_teardown();
     }
 
@@ -669,8 +1083,7 @@ mod test
             let mut pop = fact.random_population(10, GAPopulationSortOrder::HighIsBest, &mut GARandomCtx::new_unseeded("ga_population::test_clone_population".to_string()));
 
             // Upon creation.
-            // FIXME: Panics because eq() iterates over non-init'ed sorted arrays.
-            //assert_eq!(pop == pop.clone(), true);
+            assert_eq!(pop == pop.clone(), true);
 
             pop.sort();
             pop.statistics();
@@ -683,8 +1096,7 @@ mod test
             let mut pop = fact.random_population(10, GAPopulationSortOrder::LowIsBest, &mut GARandomCtx::new_unseeded("ga_population::test_clone_population".to_string()));
 
             // Upon creation.
-            // FIXME: Panics because eq() iterates over non-init'ed sorted arrays.
-            //assert_eq!(pop == pop.clone(), true);
+            assert_eq!(pop == pop.clone(), true);
 
             pop.sort();
             pop.statistics();
@@ -696,6 +1108,30 @@ mod test
         ga_test_teardown();
     }
 
+    #[test]
+    fn test_unsorted_population()
+    {
+        ga_test_setup("ga_population::test_unsorted_population");
+        let new_pop = |raw_scores: &[f32]|
+        {
+            GAPopulation::new(raw_scores.iter().map(|rs| GATestIndividual::new(*rs)).collect(), GAPopulationSortOrder::HighIsBest)
+        };
+
+        // Iterators can't rank an unsorted population.
+        let mut pop = new_pop(&[1.0, 2.0]);
+        assert!(pop.raw_score_iterator().err() == Some(GAError::NotSorted));
+        assert!(pop.fitness_score_iterator().err() == Some(GAError::NotSorted));
+        pop.sort();
+        assert_eq!(pop.raw_score_iterator().unwrap().count(), 2);
+        pop.add_individuals(vec![GATestIndividual::new(3.0)]);
+        assert!(pop.raw_score_iterator().err() == Some(GAError::NotSorted));
+
+        // Unsorted populations of the same size are compared by their individuals.
+        assert!(new_pop(&[1.0, 2.0]) != new_pop(&[3.0, 4.0]));
+        assert!(new_pop(&[1.0, 2.0]) == new_pop(&[1.0, 2.0]));
+        ga_test_teardown();
+    }
+
     #[test]
     fn test_population_raw_iterator()
     {
@@ -713,7 +1149,7 @@ mod test
             let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::LowIsBest);
             pop.sort();
 
-            let it = pop.raw_score_iterator();
+            let it = pop.raw_score_iterator().unwrap();
             let actual_seq: Vec<f32> = it.map(|ind| { ind.raw() }).collect();
             assert_eq!(expected_seq, actual_seq);
         }
@@ -730,7 +1166,7 @@ mod test
             let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
             pop.sort();
 
-            let it = pop.raw_score_iterator();
+            let it = pop.raw_score_iterator().unwrap();
             let actual_seq: Vec<f32> = it.map(|ind| { ind.raw() }).collect();
             assert_eq!(expected_seq, actual_seq);
         }
@@ -754,7 +1190,7 @@ mod test
             let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
             pop.sort();
 
-            let it = pop.fitness_score_iterator();
+            let it = pop.fitness_score_iterator().unwrap();
             let actual_seq: Vec<f32> = it.map(|ind| { ind.fitness() }).collect();
             assert_eq!(expected_seq, actual_seq);
         }
@@ -771,13 +1207,64 @@ mod test
             let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::LowIsBest);
             pop.sort();
 
-            let it = pop.fitness_score_iterator();
+            let it = pop.fitness_score_iterator().unwrap();
             let actual_seq: Vec<f32> = it.map(|ind| { ind.fitness() }).collect();
             assert_eq!(expected_seq, actual_seq);
         }
         ga_test_teardown();
     }
 
+    // Its raw score is a function of its gene and of the (shared) context.
+    #[derive(Clone)]
+    struct GAParallelTestIndividual
+    {
+        gene: f32,
+        raw: f32,
+        fitness: f32
+    }
+    impl GAIndividual for GAParallelTestIndividual
+    {
+        // Offset added to the raw score.
+        type EvaluationCtx = f32;
+
+        fn crossover(&self, _: &GAParallelTestIndividual, _: &mut GARandomCtx) -> Box<GAParallelTestIndividual> { Box::new(self.clone()) }
+        fn mutate(&mut self, _: f32, _: &mut GARandomCtx) -> usize { 0 }
+        fn evaluate(&mut self, offset: &mut f32) { self.evaluate_shared(offset); }
+        fn fitness(&self) -> f32 { self.fitness }
+        fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+        fn raw(&self) -> f32 { self.raw }
+        fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+    }
+    impl GAParallelIndividual for GAParallelTestIndividual
+    {
+        fn evaluate_shared(&mut self, offset: &f32)
+        {
+            self.raw = self.gene * self.gene + offset;
+            self.fitness = self.raw;
+        }
+    }
+
+    #[test]
+    fn test_evaluate_parallel()
+    {
+        ga_test_setup("ga_population::test_evaluate_parallel");
+        let offset: f32 = 0.5;
+        let evaluated = |workers: usize|
+        {
+            let inds = (0..25).map(|g| GAParallelTestIndividual { gene: g as f32, raw: 0.0, fitness: 0.0 }).collect();
+            let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::LowIsBest);
+            pop.evaluate_parallel(&offset, workers);
+            pop.individuals().iter().map(|ind| ind.raw()).collect::<Vec<f32>>()
+        };
+
+        let expected: Vec<f32> = (0..25).map(|g| (g*g) as f32 + offset).collect();
+        for workers in vec![0, 1, 2, 3, 8, 25, 64]
+        {
+            assert_eq!(evaluated(workers), expected);
+        }
+        ga_test_teardown();
+    }
+
     #[test]
     fn test_population_drain()
     {
@@ -800,13 +1287,142 @@ mod test
             actual_seq.sort_by(|a, b| b.partial_cmp(a).unwrap());
             assert_eq!(expected_seq, actual_seq);
             pop.sort();
-            let it = pop.fitness_score_iterator();
+            let it = pop.fitness_score_iterator().unwrap();
             let rest: Vec<f32> = it.map(|ind| { ind.fitness() }).collect();
             assert_eq!(vec![1.0/8.0, 1.0/9.0], rest);
         }
         ga_test_teardown();
     }
 
+    #[test]
+    fn test_population_access_errors()
+    {
+        ga_test_setup("ga_population::test_population_access_errors");
+
+        let inds: Vec<GATestIndividual> = [1.0, 2.0].iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+        pop.sort();
+
+        assert_eq!(pop.individual(1, GAPopulationSortBasis::Raw).unwrap().raw(), 1.0);
+        assert_eq!(pop.individual(2, GAPopulationSortBasis::Raw).err(),
+                   Some(GAError::IndexOutOfBounds { index: 2, size: 2 }));
+
+        // New individuals are not ranked until the next sort.
+        pop.add_individuals(vec![GATestIndividual::new(3.0)]);
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Raw).err(), Some(GAError::NotSorted));
+        pop.sort();
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Raw).unwrap().raw(), 3.0);
+
+        // Nor are replaced ones, although the population size didn't change.
+        let inx = pop.individual_index(0, GAPopulationSortBasis::Raw).unwrap();
+        pop.replace_individual(inx, GATestIndividual::new(0.5));
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Raw).err(), Some(GAError::NotSorted));
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Fitness).err(), Some(GAError::NotSorted));
+        pop.sort();
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Raw).unwrap().raw(), 2.0);
+
+        // Stale fitness order only affects ranked access on fitness.
+        pop.invalidate_fitness();
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Fitness).err(), Some(GAError::NotSorted));
+        assert_eq!(pop.individual(0, GAPopulationSortBasis::Raw).unwrap().raw(), 2.0);
+
+        let empty: GAPopulation<GATestIndividual> = GAPopulation::new(vec![], GAPopulationSortOrder::HighIsBest);
+        assert_eq!(empty.worst().err(), Some(GAError::EmptyPopulation));
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_population_diversity()
+    {
+        ga_test_setup("ga_population::test_population_diversity");
+
+        let inds: Vec<GATestIndividual> = [1.0, 2.0, 4.0].iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+
+        // Not recorded yet.
+        assert_eq!(pop.diversity(), -1.0);
+
+        // Pairwise distances: 1, 3, 2.
+        let d = pop.measure_diversity(|a, b| (a.raw() - b.raw()).abs());
+        assert_eq!(d, 2.0);
+        assert_eq!(pop.diversity(), 2.0);
+
+        // Replacing an individual forgets it.
+        pop.replace_individual(0, GATestIndividual::new(3.0));
+        assert_eq!(pop.diversity(), -1.0);
+
+        // A single individual has no diversity.
+        let mut single = GAPopulation::new(vec![GATestIndividual::new(1.0)], GAPopulationSortOrder::HighIsBest);
+        assert_eq!(single.measure_diversity(|a, b| (a.raw() - b.raw()).abs()), 0.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_population_compute_diversity()
+    {
+        ga_test_setup("ga_population::test_population_compute_diversity");
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_population_compute_diversity_rng"));
+
+        let inds: Vec<GATestIndividual> = [1.0, 1.25, 1.5].iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+        pop.statistics();
+
+        // Pairwise distances: 0.25, 0.5, 0.25.
+        let d = pop.compute_diversity(GADiversityMode::Exact, &mut rng_ctx);
+        assert!((d - 1.0/3.0).abs() < 1e-6);
+        assert_eq!(pop.statistics().unwrap().diversity, d);
+
+        // As many pairs as there are is exact.
+        assert_eq!(pop.compute_diversity(GADiversityMode::Sampled { pairs: 3 }, &mut rng_ctx), d);
+
+        let sampled = pop.compute_diversity(GADiversityMode::Sampled { pairs: 2 }, &mut rng_ctx);
+        assert!(sampled >= 0.25 && sampled <= 0.5);
+
+        // Individuals that can't be compared.
+        let inds = (0..3).map(|g| GAParallelTestIndividual { gene: g as f32, raw: 0.0, fitness: 0.0 }).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+        assert_eq!(pop.compute_diversity(GADiversityMode::Exact, &mut rng_ctx), -1.0);
+        assert_eq!(pop.diversity(), -1.0);
+        assert_eq!(pop.statistics().unwrap().diversity, -1.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_population_scaling()
+    {
+        ga_test_setup("ga_population::test_population_scaling");
+
+        // GATestIndividual starts with fitness = 1/raw.
+        let inds: Vec<GATestIndividual> = [1.0, 2.0, 4.0].iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+        pop.sort();
+        assert_eq!(pop.best_by_fitness_score().raw(), 1.0);
+        assert_eq!(pop.statistics().unwrap().fitness_max, 1.0);
+
+        // Without a scheme, evaluation leaves fitness alone.
+        pop.evaluate(&mut ());
+        assert_eq!(pop.individuals()[2].fitness(), 0.25);
+
+        // With one, fitness is rescaled and stale orders and statistics are dropped.
+        pop.set_scaling(Box::new(GANoScaling));
+        assert!(pop.has_scaling());
+        pop.evaluate(&mut ());
+        pop.sort();
+        assert_eq!(pop.best_by_fitness_score().raw(), 4.0);
+        assert_eq!(pop.statistics().unwrap().fitness_max, 4.0);
+
+        // The next generation scales the same way.
+        let mut next = pop.next_generation(vec![GATestIndividual::new(8.0)]);
+        assert!(next.has_scaling());
+        next.evaluate(&mut ());
+        assert_eq!(next.individuals()[0].fitness(), 8.0);
+
+        ga_test_teardown();
+    }
+
     #[test]
     fn test_population_raw_statistics()
     {
@@ -915,4 +1531,43 @@ mod test
         }
 
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_population_serde()
+    {
+        ga_test_setup("ga_population::test_population_serde");
+
+        let inds = [3.0, 1.0, 2.0].iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::LowIsBest);
+        pop.set_scaling(Box::new(GANoScaling));
+        pop.sort();
+        let stats = pop.statistics().unwrap();
+
+        let json = ::serde_json::to_string(&pop).unwrap();
+        let mut pop_2 : GAPopulation<GATestIndividual> = ::serde_json::from_str(&json).unwrap();
+
+        assert!(pop_2.individuals() == pop.individuals());
+        assert!(pop_2.order() == GAPopulationSortOrder::LowIsBest);
+        assert_eq!(pop_2.best(0, GAPopulationSortBasis::Raw).unwrap().raw(), 1.0);
+        assert!(pop_2.statistics().unwrap() == stats);
+        // The scaling scheme isn't serialized.
+        assert!(!pop_2.has_scaling());
+
+        // Sort orders aren't taken from the payload.
+        let json = r#"{"population": [{"raw": 1.0, "fitness": 1.0}, {"raw": 2.0, "fitness": 0.5}],
+                       "sort_order": "HighIsBest",
+                       "population_order_raw": [7, 9],
+                       "is_raw_sorted": true,
+                       "diversity": null}"#;
+        let pop_3 : GAPopulation<GATestIndividual> = ::serde_json::from_str(json).unwrap();
+        assert_eq!(pop_3.best(0, GAPopulationSortBasis::Raw).unwrap().raw(), 2.0);
+        assert_eq!(pop_3.best(1, GAPopulationSortBasis::Fitness).unwrap().raw(), 2.0);
+
+        let stats_json = ::serde_json::to_string(&stats).unwrap();
+        let stats_2 : GAPopulationStats = ::serde_json::from_str(&stats_json).unwrap();
+        assert!(stats_2 == stats);
+
+        ga_test_teardown();
+    }
 }
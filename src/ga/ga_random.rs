@@ -7,7 +7,9 @@
 //! Wrapper around the rand crate that provides a Seeded
 //! and Stateful Random Number Generator.
 //!
-//! Internally uses rand::XorShiftRng for speed purposes.
+//! Internally uses rand::XorShiftRng's algorithm for speed purposes. Its
+//! state is kept by GARandomCtx itself, so that a context can be serialized
+//! (with the `serde` feature) and resumed where it left off.
 //!
 //! # Examples
 //!
@@ -30,27 +32,138 @@
 //! }
 //! ```
 //!
-use rand::{Rng, Rand, SeedableRng, XorShiftRng};
+use rand::{Rng, Rand};
 use rand::distributions::range::SampleRange;
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
 use std::fmt;
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
 
 pub type GASeed = [u32; 4];
+
+// rand::XorShiftRng, whose state is private.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GAXorShiftRngData"))]
+struct GAXorShiftRng
+{
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32
+}
+
+// What a serialized GAXorShiftRng holds. An all zero state would only
+// ever generate zeros, so it is rejected.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GAXorShiftRngData
+{
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GAXorShiftRngData> for GAXorShiftRng
+{
+    type Error = &'static str;
+
+    fn try_from(data: GAXorShiftRngData) -> Result<GAXorShiftRng, &'static str>
+    {
+        if data.x == 0 && data.y == 0 && data.z == 0 && data.w == 0
+        {
+            return Err("GAXorShiftRng with an all zero state");
+        }
+        Ok(GAXorShiftRng { x: data.x, y: data.y, z: data.z, w: data.w })
+    }
+}
+
+impl GAXorShiftRng
+{
+    fn new_unseeded() -> GAXorShiftRng
+    {
+        GAXorShiftRng { x: 0x193a6754, y: 0xa8a7d469, z: 0x97830e05, w: 0x113ba7bb }
+    }
+
+    fn from_seed(seed: GASeed) -> GAXorShiftRng
+    {
+        assert!(!seed.iter().all(|&x| x == 0), "GAXorShiftRng::from_seed called with an all zero seed.");
+        GAXorShiftRng { x: seed[0], y: seed[1], z: seed[2], w: seed[3] }
+    }
+}
+
+impl Rng for GAXorShiftRng
+{
+    fn next_u32(&mut self) -> u32
+    {
+        let x = self.x;
+        let t = x ^ (x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        let w = self.w;
+        self.w = w ^ (w >> 19) ^ (t ^ (t >> 8));
+        self.w
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GARandomCtxData"))]
 pub struct GARandomCtx
 {
     seed: GASeed,
-    rng:  XorShiftRng,
+    rng:  GAXorShiftRng,
+    name: String,
+    seeded: bool,
+    values_generated: u32
+}
+
+// What a serialized GARandomCtx holds. A seeded context with an all zero
+// seed couldn't be reset, so it is rejected.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GARandomCtxData
+{
+    seed: GASeed,
+    rng:  GAXorShiftRng,
     name: String,
     seeded: bool,
     values_generated: u32
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<GARandomCtxData> for GARandomCtx
+{
+    type Error = &'static str;
+
+    fn try_from(data: GARandomCtxData) -> Result<GARandomCtx, &'static str>
+    {
+        if data.seeded && data.seed.iter().all(|&x| x == 0)
+        {
+            return Err("seeded GARandomCtx with an all zero seed");
+        }
+        Ok(GARandomCtx
+        {
+            seed: data.seed,
+            rng: data.rng,
+            name: data.name,
+            seeded: data.seeded,
+            values_generated: data.values_generated
+        })
+    }
+}
+
 impl GARandomCtx
 {
 // Constructors 
     pub fn new_unseeded(name: String) -> GARandomCtx
     {
-        let std_rng = XorShiftRng::new_unseeded();
+        let std_rng = GAXorShiftRng::new_unseeded();
         GARandomCtx
         {
             seed: [0; 4],
@@ -63,7 +176,7 @@ impl GARandomCtx
 
     pub fn from_seed(seed: GASeed, name: String) -> GARandomCtx
     {
-        let std_rng = SeedableRng::from_seed(seed); 
+        let std_rng = GAXorShiftRng::from_seed(seed); 
         GARandomCtx
         {
             seed: seed,
@@ -123,11 +236,11 @@ impl GARandomCtx
         self.values_generated = 0;
         if self.seeded
         {
-            self.rng.reseed(self.seed);
+            self.rng = GAXorShiftRng::from_seed(self.seed);
         }
         else
         {
-            self.rng = XorShiftRng::new_unseeded(); 
+            self.rng = GAXorShiftRng::new_unseeded(); 
         }
     }
 }
@@ -161,6 +274,8 @@ mod test
     use super::{GASeed, GARandomCtx};
     use ::ga::ga_test::{ga_test_setup, ga_test_teardown};
 
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
     #[test]
     fn same_seed()
     {
@@ -226,4 +341,77 @@ mod test
         assert_eq!(ga_ctx.gen::<f32>(), ga_ctx_2.gen::<f32>());
         ga_test_teardown();
     }
+
+    #[test]
+    fn same_values_as_xorshift()
+    {
+        ga_test_setup("ga_random::same_values_as_xorshift");
+        let seed : GASeed = [1,2,3,4];
+        let mut ga_ctx = GARandomCtx::from_seed(seed, String::from("TestRandomCtx"));
+        let mut xorshift : XorShiftRng = SeedableRng::from_seed(seed);
+        for _ in 0..100
+        {
+            assert_eq!(ga_ctx.gen::<f64>(), xorshift.gen::<f64>());
+            assert_eq!(ga_ctx.gen_range(0, 10), xorshift.gen_range(0, 10));
+        }
+
+        let mut ga_ctx = GARandomCtx::new_unseeded(String::from("TestRandomCtx"));
+        let mut xorshift = XorShiftRng::new_unseeded();
+        for _ in 0..100
+        {
+            assert_eq!(ga_ctx.gen::<u32>(), xorshift.gen::<u32>());
+        }
+        ga_test_teardown();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_resumes_sequence()
+    {
+        ga_test_setup("ga_random::serde_resumes_sequence");
+        let seed : GASeed = [1,2,3,4];
+        let mut ga_ctx = GARandomCtx::from_seed(seed, String::from("TestRandomCtx"));
+        for _ in 0..10
+        {
+            ga_ctx.gen::<f32>();
+        }
+
+        let json = ::serde_json::to_string(&ga_ctx).unwrap();
+        let mut ga_ctx_2 : GARandomCtx = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", ga_ctx), format!("{:?}", ga_ctx_2));
+        for _ in 0..100
+        {
+            assert_eq!(ga_ctx.gen::<u64>(), ga_ctx_2.gen::<u64>());
+        }
+
+        // Still resets to the seed.
+        ga_ctx.reset();
+        ga_ctx_2.reset();
+        assert_eq!(ga_ctx.gen::<u32>(), ga_ctx_2.gen::<u32>());
+        ga_test_teardown();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_zero_state()
+    {
+        ga_test_setup("ga_random::serde_rejects_zero_state");
+        let ga_ctx = GARandomCtx::from_seed([1,2,3,4], String::from("TestRandomCtx"));
+        let json = ::serde_json::to_string(&ga_ctx).unwrap();
+
+        let zero_state = json.replace("\"rng\":{\"x\":1,\"y\":2,\"z\":3,\"w\":4}",
+                                      "\"rng\":{\"x\":0,\"y\":0,\"z\":0,\"w\":0}");
+        assert!(zero_state != json);
+        assert!(::serde_json::from_str::<GARandomCtx>(&zero_state).is_err());
+
+        let zero_seed = json.replace("\"seed\":[1,2,3,4]", "\"seed\":[0,0,0,0]");
+        assert!(zero_seed != json);
+        assert!(::serde_json::from_str::<GARandomCtx>(&zero_seed).is_err());
+
+        // An unseeded context doesn't use its seed.
+        let unseeded = zero_seed.replace("\"seeded\":true", "\"seeded\":false");
+        assert!(unseeded != zero_seed);
+        assert!(::serde_json::from_str::<GARandomCtx>(&unseeded).is_ok());
+        ga_test_teardown();
+    }
 }
@@ -0,0 +1,632 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Real Genome
+//!
+//! GALib's GARealGenome: an array of real numbers, each of them restricted
+//! to an allele set. Allele sets are shared by all the genomes of a run and
+//! every operator keeps the genes within them.
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use rand::distributions::normal::StandardNormal;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Objective function of a Real Genome. Takes the genes and returns the
+/// raw score (and the fitness) of the genome.
+pub type GARealObjective = Arc<dyn Fn(&[f64]) -> f32 + Send + Sync>;
+
+/// Real Allele Set
+///
+/// Values a gene can take.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GARealAlleleSet
+{
+    /// Any value in [min, max].
+    Continuous { min: f64, max: f64 },
+    /// min, min + step, min + 2 * step... up to max.
+    Discretized { min: f64, max: f64, step: f64 },
+    /// One of the values given.
+    Enumerated(Vec<f64>),
+}
+impl GARealAlleleSet
+{
+    /// Smallest value of the set.
+    pub fn lower(&self) -> f64
+    {
+        match *self
+        {
+            GARealAlleleSet::Continuous { min, .. } |
+            GARealAlleleSet::Discretized { min, .. } => min,
+            GARealAlleleSet::Enumerated(ref values) => values.iter().cloned().fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    /// Largest value of the set.
+    pub fn upper(&self) -> f64
+    {
+        match *self
+        {
+            GARealAlleleSet::Continuous { max, .. } => max,
+            GARealAlleleSet::Discretized { min, max, step } => min + ((max - min) / step).floor() * step,
+            GARealAlleleSet::Enumerated(ref values) => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    /// Value of the set closest to `value`.
+    pub fn bound(&self, value: f64) -> f64
+    {
+        match *self
+        {
+            GARealAlleleSet::Continuous { min, max } => value.max(min).min(max),
+            GARealAlleleSet::Discretized { min, step, .. } =>
+            {
+                let steps = ((value - min) / step).round().max(0.0);
+                let max_steps = ((self.upper() - min) / step).round();
+                min + steps.min(max_steps) * step
+            },
+            GARealAlleleSet::Enumerated(ref values) =>
+            {
+                let mut closest = values[0];
+                for &v in values
+                {
+                    if (v - value).abs() < (closest - value).abs()
+                    {
+                        closest = v;
+                    }
+                }
+                closest
+            },
+        }
+    }
+
+    /// Random value of the set.
+    pub fn random(&self, rng_ctx: &mut GARandomCtx) -> f64
+    {
+        match *self
+        {
+            GARealAlleleSet::Continuous { min, max } =>
+            {
+                min + (max - min) * rng_ctx.next_f64()
+            },
+            GARealAlleleSet::Discretized { min, step, .. } =>
+            {
+                let max_steps = ((self.upper() - min) / step).round() as u64;
+                min + rng_ctx.gen_range(0, max_steps + 1) as f64 * step
+            },
+            GARealAlleleSet::Enumerated(ref values) =>
+            {
+                values[rng_ctx.gen_range(0, values.len())]
+            },
+        }
+    }
+
+    fn validate(&self)
+    {
+        match *self
+        {
+            GARealAlleleSet::Continuous { min, max } => assert!(min <= max),
+            GARealAlleleSet::Discretized { min, max, step } => assert!(min <= max && step > 0.0),
+            GARealAlleleSet::Enumerated(ref values) => assert!(!values.is_empty()),
+        }
+    }
+}
+
+/// Real Genome Crossover
+///
+/// Crossover operator used by `GAIndividual::crossover`. Each gene of the
+/// child is bound to its allele set.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GARealCrossover
+{
+    /// BLX-α: each gene is drawn uniformly from the parents' interval,
+    /// extended by `alpha` times its length on each side.
+    Blend { alpha: f64 },
+    /// The child is a random weighted average of the parents.
+    Arithmetic,
+    /// Simulated binary crossover (SBX), with distribution index `eta`.
+    /// The larger `eta`, the closer the child is to the parents.
+    SimulatedBinary { eta: f64 },
+}
+impl Default for GARealCrossover
+{
+    fn default() -> GARealCrossover
+    {
+        GARealCrossover::Blend { alpha: 0.5 }
+    }
+}
+
+/// Real Genome Mutation
+///
+/// Mutation operator used by `GAIndividual::mutate`. Each gene mutates with
+/// probability `p_mutation`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GARealMutation
+{
+    /// Gaussian noise is added to the gene, with standard deviation
+    /// `scale` times the width of its allele set. The result is bound to
+    /// the allele set.
+    Gaussian { scale: f64 },
+    /// The gene is replaced by a random value of its allele set.
+    Uniform,
+}
+impl Default for GARealMutation
+{
+    fn default() -> GARealMutation
+    {
+        GARealMutation::Gaussian { scale: 0.1 }
+    }
+}
+
+/// Real Genome
+#[derive(Clone)]
+pub struct GARealGenome
+{
+    genes: Vec<f64>,
+    alleles: Arc<Vec<GARealAlleleSet>>,
+    crossover: GARealCrossover,
+    mutation: GARealMutation,
+    objective: GARealObjective,
+    raw: f32,
+    fitness: f32,
+}
+impl GARealGenome
+{
+    /// Genome with `values` (bound to their allele sets) as genes. There
+    /// must be one allele set per value.
+    pub fn new(values: &[f64], alleles: Arc<Vec<GARealAlleleSet>>, objective: GARealObjective) -> GARealGenome
+    {
+        assert_eq!(values.len(), alleles.len());
+        for a in alleles.iter()
+        {
+            a.validate();
+        }
+
+        GARealGenome
+        {
+            genes: values.iter().zip(alleles.iter()).map(|(&v, a)| a.bound(v)).collect(),
+            alleles,
+            crossover: GARealCrossover::default(),
+            mutation: GARealMutation::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+
+    /// Genome with random genes.
+    pub fn random(alleles: Arc<Vec<GARealAlleleSet>>, objective: GARealObjective, rng_ctx: &mut GARandomCtx) -> GARealGenome
+    {
+        let values : Vec<f64> = alleles.iter().map(|a| a.random(rng_ctx)).collect();
+        GARealGenome::new(&values, alleles, objective)
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.genes.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.genes.is_empty()
+    }
+
+    pub fn genes(&self) -> &[f64]
+    {
+        &self.genes
+    }
+
+    pub fn gene(&self, i: usize) -> f64
+    {
+        self.genes[i]
+    }
+
+    /// Set gene `i` to the value of its allele set closest to `value`.
+    pub fn set_gene(&mut self, i: usize, value: f64)
+    {
+        self.genes[i] = self.alleles[i].bound(value);
+    }
+
+    pub fn alleles(&self) -> &[GARealAlleleSet]
+    {
+        &self.alleles
+    }
+
+    pub fn crossover_operator(&self) -> GARealCrossover
+    {
+        self.crossover
+    }
+
+    pub fn set_crossover_operator(&mut self, crossover: GARealCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    pub fn mutation_operator(&self) -> GARealMutation
+    {
+        self.mutation
+    }
+
+    pub fn set_mutation_operator(&mut self, mutation: GARealMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    /// BLX-α crossover.
+    pub fn blend_crossover(&self, other: &GARealGenome, alpha: f64, rng_ctx: &mut GARandomCtx) -> GARealGenome
+    {
+        self.child_with(other, |a, b| {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let d = hi - lo;
+            (lo - alpha * d) + (d + 2.0 * alpha * d) * rng_ctx.next_f64()
+        })
+    }
+
+    /// Arithmetic crossover: `l * self + (1 - l) * other`, for a random `l`
+    /// in [0, 1].
+    pub fn arithmetic_crossover(&self, other: &GARealGenome, rng_ctx: &mut GARandomCtx) -> GARealGenome
+    {
+        let l = rng_ctx.next_f64();
+        self.child_with(other, |a, b| l * a + (1.0 - l) * b)
+    }
+
+    /// Simulated binary crossover (SBX).
+    pub fn simulated_binary_crossover(&self, other: &GARealGenome, eta: f64, rng_ctx: &mut GARandomCtx) -> GARealGenome
+    {
+        self.child_with(other, |a, b| {
+            let u = rng_ctx.next_f64();
+            let beta = if u <= 0.5
+            {
+                (2.0 * u).powf(1.0 / (eta + 1.0))
+            }
+            else
+            {
+                (1.0 / (2.0 * (1.0 - u))).powf(1.0 / (eta + 1.0))
+            };
+            0.5 * ((1.0 + beta) * a + (1.0 - beta) * b)
+        })
+    }
+
+    // Child whose genes are 'combine(self gene, other gene)', bound to the
+    // allele sets.
+    fn child_with<F: FnMut(f64, f64) -> f64>(&self, other: &GARealGenome, mut combine: F) -> GARealGenome
+    {
+        assert_eq!(self.len(), other.len());
+
+        let mut child = GARealGenome { raw: 0.0, fitness: 0.0, .. self.clone() };
+        for i in 0..child.genes.len()
+        {
+            let v = combine(self.genes[i], other.genes[i]);
+            child.set_gene(i, v);
+        }
+        child
+    }
+}
+impl GAIndividual for GARealGenome
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GARealGenome, rng_ctx: &mut GARandomCtx) -> Box<GARealGenome>
+    {
+        let child = match self.crossover
+        {
+            GARealCrossover::Blend { alpha } => self.blend_crossover(other, alpha, rng_ctx),
+            GARealCrossover::Arithmetic => self.arithmetic_crossover(other, rng_ctx),
+            GARealCrossover::SimulatedBinary { eta } => self.simulated_binary_crossover(other, eta, rng_ctx),
+        };
+        Box::new(child)
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        if p_mutation <= 0.0
+        {
+            return 0;
+        }
+
+        let mut mutations = 0;
+        for i in 0..self.genes.len()
+        {
+            if !rng_ctx.test_value(p_mutation)
+            {
+                continue;
+            }
+
+            let allele = &self.alleles[i];
+            self.genes[i] = match self.mutation
+            {
+                GARealMutation::Gaussian { scale } =>
+                {
+                    let StandardNormal(z) = rng_ctx.gen::<StandardNormal>();
+                    let std_dev = scale * (allele.upper() - allele.lower());
+                    allele.bound(self.genes[i] + z * std_dev)
+                },
+                GARealMutation::Uniform => allele.random(rng_ctx),
+            };
+            mutations += 1;
+        }
+        mutations
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        self.evaluate_shared(&());
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Mean distance between genes, each relative to the range of its
+    /// allele set. Genomes of different lengths can't be compared.
+    fn compare(&self, other: &GARealGenome) -> f32
+    {
+        if self.genes.len() != other.genes.len()
+        {
+            return -1.0;
+        }
+        if self.genes.is_empty()
+        {
+            return 0.0;
+        }
+
+        let sum = self.genes.iter().zip(other.genes.iter()).zip(self.alleles.iter())
+                            .map(|((a, b), allele)|
+                            {
+                                let range = allele.upper() - allele.lower();
+                                if range > 0.0 { ((a - b).abs() / range).min(1.0) } else { 0.0 }
+                            })
+                            .sum::<f64>();
+        (sum / self.genes.len() as f64) as f32
+    }
+}
+impl GAParallelIndividual for GARealGenome
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        let score = (self.objective)(&self.genes);
+        self.raw = score;
+        self.fitness = score;
+    }
+}
+impl PartialEq for GARealGenome
+{
+    fn eq(&self, other: &GARealGenome) -> bool
+    {
+        self.genes == other.genes && self.alleles == other.alleles
+    }
+}
+impl fmt::Debug for GARealGenome
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GARealGenome({:?}) raw: {} fitness: {}", self.genes, self.raw, self.fitness)
+    }
+}
+
+/// Real Genome Factory
+///
+/// Creates genomes with random genes.
+pub struct GARealFactory
+{
+    alleles: Arc<Vec<GARealAlleleSet>>,
+    objective: GARealObjective,
+    crossover: GARealCrossover,
+    mutation: GARealMutation,
+}
+impl GARealFactory
+{
+    pub fn new<F>(alleles: Vec<GARealAlleleSet>, objective: F) -> GARealFactory
+        where F: Fn(&[f64]) -> f32 + Send + Sync + 'static
+    {
+        GARealFactory
+        {
+            alleles: Arc::new(alleles),
+            objective: Arc::new(objective),
+            crossover: GARealCrossover::default(),
+            mutation: GARealMutation::default(),
+        }
+    }
+
+    /// Crossover operator of the genomes created from now on.
+    pub fn set_crossover_operator(&mut self, crossover: GARealCrossover)
+    {
+        self.crossover = crossover;
+    }
+
+    /// Mutation operator of the genomes created from now on.
+    pub fn set_mutation_operator(&mut self, mutation: GARealMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    pub fn random_genome(&mut self, rng_ctx: &mut GARandomCtx) -> GARealGenome
+    {
+        let mut genome = GARealGenome::random(self.alleles.clone(), self.objective.clone(), rng_ctx);
+        genome.set_crossover_operator(self.crossover);
+        genome.set_mutation_operator(self.mutation);
+        genome
+    }
+}
+impl GAFactory<GARealGenome> for GARealFactory
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GARealGenome>
+    {
+        let genomes = (0..n).map(|_| self.random_genome(rng_ctx)).collect();
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    fn alleles() -> Arc<Vec<GARealAlleleSet>>
+    {
+        Arc::new(vec![GARealAlleleSet::Continuous { min: -1.0, max: 1.0 },
+                      GARealAlleleSet::Discretized { min: 0.0, max: 10.0, step: 2.5 },
+                      GARealAlleleSet::Enumerated(vec![3.0, -7.0, 11.0])])
+    }
+
+    fn in_allele_sets(g: &GARealGenome) -> bool
+    {
+        let (c, d, e) = (g.gene(0), g.gene(1), g.gene(2));
+        c >= -1.0 && c <= 1.0 &&
+        d >= 0.0 && d <= 10.0 && (d / 2.5).fract() == 0.0 &&
+        [3.0, -7.0, 11.0].contains(&e)
+    }
+
+    fn zero() -> GARealObjective
+    {
+        Arc::new(|_: &[f64]| 0.0)
+    }
+
+    #[test]
+    fn allele_sets()
+    {
+        ga_test_setup("ga_real::allele_sets");
+        let a = alleles();
+        assert_eq!(a[0].bound(3.0), 1.0);
+        assert_eq!(a[0].bound(0.25), 0.25);
+        assert_eq!(a[1].bound(3.5), 2.5);
+        assert_eq!(a[1].bound(12.0), 10.0);
+        assert_eq!(a[1].bound(-3.0), 0.0);
+        assert_eq!(a[2].bound(6.0), 3.0);
+        assert_eq!(a[2].bound(100.0), 11.0);
+        assert_eq!((a[2].lower(), a[2].upper()), (-7.0, 11.0));
+
+        let g = GARealGenome::new(&[5.0, 6.0, 0.0], a.clone(), zero());
+        assert_eq!(g.genes(), &[1.0, 5.0, 3.0]);
+
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for _ in 0..50
+        {
+            assert!(in_allele_sets(&GARealGenome::random(a.clone(), zero(), &mut rng_ctx)));
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_real::compare");
+        let g = GARealGenome::new(&[-1.0, 0.0, 11.0], alleles(), zero());
+        assert_eq!(g.compare(&g.clone()), 0.0);
+        // Relative distances: 1, 0.5, 0.
+        let h = GARealGenome::new(&[1.0, 5.0, 11.0], alleles(), zero());
+        assert_eq!(g.compare(&h), 0.5);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn mutation_operators()
+    {
+        ga_test_setup("ga_real::mutation_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mut g = GARealGenome::new(&[0.0, 5.0, 3.0], alleles(), zero());
+
+        g.mutate(0.0, &mut rng_ctx);
+        assert_eq!(g.genes(), &[0.0, 5.0, 3.0]);
+
+        for &mutation in &[GARealMutation::Gaussian { scale: 1.0 }, GARealMutation::Uniform]
+        {
+            g.set_mutation_operator(mutation);
+            let mut changed = false;
+            for _ in 0..50
+            {
+                let before = g.genes().to_vec();
+                g.mutate(1.0, &mut rng_ctx);
+                assert!(in_allele_sets(&g), "{:?}", g);
+                changed |= before != g.genes();
+            }
+            assert!(changed);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn crossover_operators()
+    {
+        ga_test_setup("ga_real::crossover_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let mom = GARealGenome::new(&[-0.5, 0.0, -7.0], alleles(), zero());
+        let dad = GARealGenome::new(&[0.5, 10.0, 11.0], alleles(), zero());
+
+        for _ in 0..50
+        {
+            let c = mom.blend_crossover(&dad, 0.5, &mut rng_ctx);
+            assert!(in_allele_sets(&c));
+
+            let c = mom.arithmetic_crossover(&dad, &mut rng_ctx);
+            assert!(in_allele_sets(&c));
+            assert!(c.gene(0) >= -0.5 && c.gene(0) <= 0.5);
+            // Same weight for all the genes.
+            let l = 0.5 - c.gene(0);
+            assert!((c.alleles()[1].bound((1.0 - l) * 10.0) - c.gene(1)).abs() < 1e-9);
+
+            let c = mom.simulated_binary_crossover(&dad, 2.0, &mut rng_ctx);
+            assert!(in_allele_sets(&c));
+        }
+
+        // Identical parents produce identical children (but for BLX, with
+        // alpha > 0, on parents that differ).
+        let mut m = mom.clone();
+        for &crossover in &[GARealCrossover::Blend { alpha: 0.3 }, GARealCrossover::Arithmetic,
+                            GARealCrossover::SimulatedBinary { eta: 10.0 }]
+        {
+            m.set_crossover_operator(crossover);
+            assert_eq!(m.crossover(&mom, &mut rng_ctx).genes(), mom.genes());
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn simple_ga_with_factory()
+    {
+        ga_test_setup("ga_real::simple_ga_with_factory");
+        // Peak at (0.5, 5.0, 11.0).
+        let mut factory = GARealFactory::new((*alleles()).clone(), |v: &[f64]|
+        {
+            (1.0 / (1.0 + (v[0] - 0.5).powi(2) + (v[1] - 5.0).powi(2) + (v[2] - 11.0).powi(2))) as f32
+        });
+        factory.set_crossover_operator(GARealCrossover::SimulatedBinary { eta: 2.0 });
+        factory.set_mutation_operator(GARealMutation::Gaussian { scale: 0.05 });
+
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 50,
+                                                   population_size: 30,
+                                                   probability_crossover: 0.9,
+                                                   probability_mutation: 0.1,
+                                                   elitism: true,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GARealGenome>),
+                                                 None);
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        let best = ga.population().best_by_raw_score();
+        assert!(in_allele_sets(best));
+        assert!(best.raw() >= initial_best);
+        assert!(best.raw() > 0.8, "{:?}", best);
+        ga_test_teardown();
+    }
+}
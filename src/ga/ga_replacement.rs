@@ -0,0 +1,324 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under an MIT License.
+
+//! GA Replacement Schemes
+//!
+//! A replacement scheme decides which individual of a population is
+//! displaced when a new individual (a child) is inserted in it. Genetic
+//! algorithms with overlapping populations (steady-state, incremental)
+//! use replacement schemes instead of replacing the whole population at
+//! once.
+//!
+//! Available replacement schemes:
+//!
+//! `GAReplaceWorst`
+//! `GAReplaceBest`
+//! `GAReplaceParent`
+//! `GAReplaceRandom`
+//! `GACrowdingReplacement`
+//! `GACustomReplacement`
+use ::ga::ga_core::GAIndividual;
+use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+/// Replacement scheme trait.
+pub trait GAReplacement<T: GAIndividual>
+{
+    /// Choose the individual of the population that `child` will replace.
+    ///
+    /// `pop` is sorted. `parents` are the positions, in the individuals
+    /// vector of `pop` (see `GAPopulation::index_of`), of the individuals
+    /// `child` was bred from.
+    ///
+    /// Returns the position of the individual to replace, or `None` if
+    /// `child` should be discarded.
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, child: &T,
+                         parents: &[usize], rng_ctx: &mut GARandomCtx) -> Option<usize>;
+}
+
+// Is raw score 'l' better than raw score 'r' according to 'order'?
+fn is_better(order: GAPopulationSortOrder, l: f32, r: f32) -> bool
+{
+    match order
+    {
+        GAPopulationSortOrder::HighIsBest => l > r,
+        GAPopulationSortOrder::LowIsBest  => l < r
+    }
+}
+
+/// Replace the worst individual of the population.
+///
+/// The child is discarded if it isn't better than the worst individual.
+pub struct GAReplaceWorst;
+
+impl<T: GAIndividual> GAReplacement<T> for GAReplaceWorst
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, child: &T,
+                         _: &[usize], _: &mut GARandomCtx) -> Option<usize>
+    {
+        if pop.size() == 0
+        {
+            return None;
+        }
+
+        if is_better(pop.order(), child.raw(), pop.worst_by_raw_score().raw())
+        {
+            pop.individual_index(pop.size()-1, GAPopulationSortBasis::Raw).ok()
+        }
+        else
+        {
+            None
+        }
+    }
+}
+
+/// Replace the best individual of the population.
+///
+/// The child always replaces it, regardless of its score.
+pub struct GAReplaceBest;
+
+impl<T: GAIndividual> GAReplacement<T> for GAReplaceBest
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, _: &T,
+                         _: &[usize], _: &mut GARandomCtx) -> Option<usize>
+    {
+        if pop.size() == 0
+        {
+            None
+        }
+        else
+        {
+            pop.individual_index(0, GAPopulationSortBasis::Raw).ok()
+        }
+    }
+}
+
+/// Replace the worst of the child's parents.
+///
+/// Falls back to `GAReplaceWorst` when the parents are unknown.
+pub struct GAReplaceParent;
+
+impl<T: GAIndividual> GAReplacement<T> for GAReplaceParent
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, child: &T,
+                         parents: &[usize], rng_ctx: &mut GARandomCtx) -> Option<usize>
+    {
+        if parents.is_empty()
+        {
+            return GAReplaceWorst.replacement_index(pop, child, parents, rng_ctx);
+        }
+
+        let order = pop.order();
+        let population = pop.individuals();
+        let mut worst_parent = parents[0];
+        for &p in &parents[1..]
+        {
+            if is_better(order, population[worst_parent].raw(), population[p].raw())
+            {
+                worst_parent = p;
+            }
+        }
+
+        Some(worst_parent)
+    }
+}
+
+/// Replace an individual chosen at random, with equal probability.
+pub struct GAReplaceRandom;
+
+impl<T: GAIndividual> GAReplacement<T> for GAReplaceRandom
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, _: &T,
+                         _: &[usize], rng_ctx: &mut GARandomCtx) -> Option<usize>
+    {
+        if pop.size() == 0
+        {
+            None
+        }
+        else
+        {
+            Some(rng_ctx.gen_range(0, pop.size()))
+        }
+    }
+}
+
+/// Crowding replacement.
+///
+/// Replace the individual that is most similar to the child, according to
+/// a user supplied distance function, if the child is better. Keeps niches
+/// of similar individuals from being taken over by a single one.
+pub struct GACrowdingReplacement<F>
+{
+    distance: F
+}
+
+impl<F> GACrowdingReplacement<F>
+{
+    pub fn new(distance: F) -> GACrowdingReplacement<F>
+    {
+        GACrowdingReplacement { distance }
+    }
+}
+
+impl<T: GAIndividual, F: Fn(&T, &T) -> f32> GAReplacement<T> for GACrowdingReplacement<F>
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, child: &T,
+                         _: &[usize], _: &mut GARandomCtx) -> Option<usize>
+    {
+        let mut closest: Option<(usize, f32)> = None;
+
+        for (i, ind) in pop.individuals().iter().enumerate()
+        {
+            let d = (self.distance)(child, ind);
+            match closest
+            {
+                Some((_, closest_d)) if closest_d <= d => {},
+                _ => closest = Some((i, d))
+            }
+        }
+
+        match closest
+        {
+            Some((i, _)) if is_better(pop.order(), child.raw(), pop.individuals()[i].raw()) => Some(i),
+            _ => None
+        }
+    }
+}
+
+/// Custom replacement.
+///
+/// Wraps a user supplied replacement function with the same signature as
+/// `GAReplacement::replacement_index`.
+pub struct GACustomReplacement<F>
+{
+    replacement_fn: F
+}
+
+impl<F> GACustomReplacement<F>
+{
+    pub fn new(replacement_fn: F) -> GACustomReplacement<F>
+    {
+        GACustomReplacement { replacement_fn }
+    }
+}
+
+impl<T, F> GAReplacement<T> for GACustomReplacement<F>
+    where T: GAIndividual,
+          F: FnMut(&GAPopulation<T>, &T, &[usize], &mut GARandomCtx) -> Option<usize>
+{
+    fn replacement_index(&mut self, pop: &GAPopulation<T>, child: &T,
+                         parents: &[usize], rng_ctx: &mut GARandomCtx) -> Option<usize>
+    {
+        (self.replacement_fn)(pop, child, parents, rng_ctx)
+    }
+}
+
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_test::*;
+
+    fn test_population(order: GAPopulationSortOrder) -> GAPopulation<GATestIndividual>
+    {
+        let inds = vec![GATestIndividual::new(3.0),
+                        GATestIndividual::new(1.0),
+                        GATestIndividual::new(2.0)];
+        let mut pop = GAPopulation::new(inds, order);
+        pop.sort();
+        pop
+    }
+
+    #[test]
+    fn test_replace_worst()
+    {
+        ga_test_setup("ga_replacement::test_replace_worst");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_replace_worst_rng"));
+
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+        assert_eq!(GAReplaceWorst.replacement_index(&pop, &GATestIndividual::new(1.5), &[], &mut rng_ctx), Some(1));
+        assert_eq!(GAReplaceWorst.replacement_index(&pop, &GATestIndividual::new(0.5), &[], &mut rng_ctx), None);
+
+        let pop = test_population(GAPopulationSortOrder::LowIsBest);
+        assert_eq!(GAReplaceWorst.replacement_index(&pop, &GATestIndividual::new(2.5), &[], &mut rng_ctx), Some(0));
+        assert_eq!(GAReplaceWorst.replacement_index(&pop, &GATestIndividual::new(3.5), &[], &mut rng_ctx), None);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_replace_best()
+    {
+        ga_test_setup("ga_replacement::test_replace_best");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_replace_best_rng"));
+
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+        assert_eq!(GAReplaceBest.replacement_index(&pop, &GATestIndividual::new(0.5), &[], &mut rng_ctx), Some(0));
+
+        let pop = test_population(GAPopulationSortOrder::LowIsBest);
+        assert_eq!(GAReplaceBest.replacement_index(&pop, &GATestIndividual::new(0.5), &[], &mut rng_ctx), Some(1));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_replace_parent()
+    {
+        ga_test_setup("ga_replacement::test_replace_parent");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_replace_parent_rng"));
+        let child = GATestIndividual::new(0.5);
+
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+        assert_eq!(GAReplaceParent.replacement_index(&pop, &child, &[0, 2], &mut rng_ctx), Some(2));
+
+        let pop = test_population(GAPopulationSortOrder::LowIsBest);
+        assert_eq!(GAReplaceParent.replacement_index(&pop, &child, &[0, 2], &mut rng_ctx), Some(0));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_replace_random()
+    {
+        ga_test_setup("ga_replacement::test_replace_random");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_replace_random_rng"));
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+
+        for _ in 0..10
+        {
+            let i = GAReplaceRandom.replacement_index(&pop, &GATestIndividual::new(0.5), &[], &mut rng_ctx).unwrap();
+            assert!(i < pop.size());
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_crowding_replacement()
+    {
+        ga_test_setup("ga_replacement::test_crowding_replacement");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_crowding_replacement_rng"));
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+        let mut crowding = GACrowdingReplacement::new(|l: &GATestIndividual, r: &GATestIndividual| (l.raw() - r.raw()).abs());
+
+        // Closest to 2.2 is 2.0, which is worse.
+        assert_eq!(crowding.replacement_index(&pop, &GATestIndividual::new(2.2), &[], &mut rng_ctx), Some(2));
+        // Closest to 0.9 is 1.0, which is better.
+        assert_eq!(crowding.replacement_index(&pop, &GATestIndividual::new(0.9), &[], &mut rng_ctx), None);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_custom_replacement()
+    {
+        ga_test_setup("ga_replacement::test_custom_replacement");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from("test_custom_replacement_rng"));
+        let pop = test_population(GAPopulationSortOrder::HighIsBest);
+
+        // Always replace the first individual that was inserted.
+        let mut custom = GACustomReplacement::new(|_: &GAPopulation<GATestIndividual>, _: &GATestIndividual, _: &[usize], _: &mut GARandomCtx| Some(0));
+        assert_eq!(custom.replacement_index(&pop, &GATestIndividual::new(0.5), &[], &mut rng_ctx), Some(0));
+        ga_test_teardown();
+    }
+}
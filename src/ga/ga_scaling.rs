@@ -6,8 +6,11 @@
 //!
 //! Scales the raw score of a population's individuals.
 
-use ::ga::ga_core::GAIndividual;
-use ::ga::ga_population::GAPopulation;
+use ::ga::ga_core::{GAError, GAIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 /// Scaling Scheme Trait
 /// 
@@ -18,6 +21,79 @@ pub trait GAScaling<T: GAIndividual>
     fn evaluate(&self, pop: &mut GAPopulation<T>);
 }
 
+/// Scaling Scheme Choice
+///
+/// Plain-data choice of a scaling scheme, for algorithm configs
+/// (see `GAPopulation::set_scaling`). `GASharing` needs a distance function,
+/// so it has to be set on the population directly.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GAScalingScheme
+{
+    /// Keep whatever scheme the population already has, if any.
+    #[default]
+    Unchanged,
+    NoScaling,
+    Linear { multiplier: f32 },
+    SigmaTruncation { multiplier: f32 },
+    PowerLaw { exponent: f32 },
+    LinearRank { pressure: f32 },
+    ExponentialRank { base: f32 },
+}
+
+impl GAScalingScheme
+{
+    /// The scheme to set on a population. `None` for `Unchanged`.
+    ///
+    /// Fails with `GAError::InvalidParameter` when a parameter is out of the
+    /// range the scheme's constructor asserts.
+    pub fn build<T: GAIndividual>(&self) -> Result<Option<Box<dyn GAScaling<T> + Send + Sync>>, GAError>
+    {
+        let scaling : Box<dyn GAScaling<T> + Send + Sync> = match *self
+        {
+            GAScalingScheme::Unchanged => return Ok(None),
+            GAScalingScheme::NoScaling => Box::new(GANoScaling),
+            GAScalingScheme::Linear { multiplier }
+            => Box::new(GALinearScaling::new(multiplier)),
+            GAScalingScheme::SigmaTruncation { multiplier }
+            => Box::new(GASigmaTruncationScaling::new(multiplier)),
+            GAScalingScheme::PowerLaw { exponent }
+            =>
+            {
+                check_parameter(exponent > 0.0, "power law exponent", exponent)?;
+                Box::new(GAPowerLawScaling::new(exponent))
+            },
+            GAScalingScheme::LinearRank { pressure }
+            =>
+            {
+                check_parameter((1.0..=2.0).contains(&pressure), "linear rank pressure", pressure)?;
+                Box::new(GALinearRankScaling::new(pressure))
+            },
+            GAScalingScheme::ExponentialRank { base }
+            =>
+            {
+                check_parameter(base > 0.0 && base < 1.0, "exponential rank base", base)?;
+                Box::new(GAExponentialRankScaling::new(base))
+            },
+        };
+        Ok(Some(scaling))
+    }
+}
+
+// Configured parameters are checked before reaching the constructors, which
+// assert on them. NaN is never valid.
+fn check_parameter(valid: bool, name: &'static str, value: f32) -> Result<(), GAError>
+{
+    if valid
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(GAError::InvalidParameter { name, value })
+    }
+}
+
 /// No Scaling - raw and fitness are the same
 pub struct GANoScaling;
 
@@ -32,6 +108,8 @@ impl<T: GAIndividual> GAScaling<T> for GANoScaling
             let rs = ind.raw();
             ind.set_fitness(rs); 
         }
+
+        pop.invalidate_fitness();
     }
 }
 
@@ -44,11 +122,10 @@ pub struct GALinearScaling
     multiplier: f32
 }
 
-#[allow(unused_variables)]
-const GA_LINEAR_SCALING_MULTIPLIER : f32 = 2.0;
+pub const GA_LINEAR_SCALING_MULTIPLIER : f32 = 2.0;
 impl GALinearScaling
 {
-    fn new(mult: f32) -> GALinearScaling
+    pub fn new(mult: f32) -> GALinearScaling
     {
         GALinearScaling{ multiplier: mult }
     }
@@ -81,13 +158,13 @@ impl<T: GAIndividual> GAScaling<T> for GALinearScaling
 {
     fn evaluate(&self, pop : &mut GAPopulation<T>)
     {
-        let max = pop.best_by_raw_score().raw();
-        let min = pop.worst_by_raw_score().raw();
-
-        // TODO: avg should be part of GAPopulation
-        let avg = (max - min) / 2.0;
+        let stats = match pop.statistics()
+        {
+            Some(stats) => stats,
+            None        => return
+        };
 
-        let (a, b) = self.prescale(max, min, avg);
+        let (a, b) = self.prescale(stats.raw_max, stats.raw_min, stats.raw_avg);
 
         let pop_vec = pop.population();
         for ind in pop_vec
@@ -95,6 +172,359 @@ impl<T: GAIndividual> GAScaling<T> for GALinearScaling
             let rs = ind.raw();
             ind.set_fitness(a*rs+b); 
         }
+
+        pop.invalidate_fitness();
+    }
+}
+
+/// Sigma Truncation Scaling
+/// Shifts raw scores by ```avg - c*std_dev``` (Goldberg, via GALib) and clamps
+/// at zero, so individuals more than ```c``` standard deviations below the
+/// mean get no selection weight.
+///
+/// For `LowIsBest` populations the cut-off is mirrored to ```avg + c*std_dev```.
+/// Fitness keeps the population's ordering: the best individual scores 0 and
+/// every individual past the cut-off shares the largest (worst) fitness, which
+/// gives them a zero slice in a `LowIsBest` roulette wheel.
+pub struct GASigmaTruncationScaling
+{
+    multiplier: f32
+}
+
+pub const GA_SIGMA_TRUNCATION_MULTIPLIER : f32 = 2.0;
+
+impl GASigmaTruncationScaling
+{
+    pub fn new(mult: f32) -> GASigmaTruncationScaling
+    {
+        GASigmaTruncationScaling{ multiplier: mult }
+    }
+
+    pub fn multiplier(&self) -> f32
+    {
+        self.multiplier
+    }
+}
+
+impl Default for GASigmaTruncationScaling
+{
+    fn default() -> GASigmaTruncationScaling
+    {
+        GASigmaTruncationScaling::new(GA_SIGMA_TRUNCATION_MULTIPLIER)
+    }
+}
+
+impl<T: GAIndividual> GAScaling<T> for GASigmaTruncationScaling
+{
+    fn evaluate(&self, pop: &mut GAPopulation<T>)
+    {
+        let stats = match pop.statistics()
+        {
+            Some(stats) => stats,
+            None        => return
+        };
+
+        let c = self.multiplier;
+        let order = pop.order();
+
+        match order
+        {
+            GAPopulationSortOrder::HighIsBest
+            =>  {
+                    let offset = stats.raw_avg - c*stats.raw_std_dev;
+                    for ind in pop.population()
+                    {
+                        let rs = ind.raw();
+                        ind.set_fitness((rs - offset).max(0.0));
+                    }
+                },
+            GAPopulationSortOrder::LowIsBest
+            =>  {
+                    // Truncate on the mirrored score, then flip it back so
+                    // that lower fitness is still better.
+                    let cutoff = stats.raw_avg + c*stats.raw_std_dev;
+                    let worst = (cutoff - stats.raw_min).max(0.0);
+                    for ind in pop.population()
+                    {
+                        let rs = ind.raw();
+                        ind.set_fitness(worst - (cutoff - rs).max(0.0));
+                    }
+                }
+        }
+
+        pop.invalidate_fitness();
+    }
+}
+
+/// Power Law Scaling
+/// Raises raw scores to a power: ```fitness = raw^k```.
+///
+/// The sign of the raw score is kept (```-|raw|^k``` for negative scores) so
+/// the mapping stays monotonic and the population's ordering is preserved
+/// for both `HighIsBest` and `LowIsBest`.
+pub struct GAPowerLawScaling
+{
+    exponent: f32
+}
+
+pub const GA_POWER_LAW_EXPONENT : f32 = 1.0005;
+
+impl GAPowerLawScaling
+{
+    pub fn new(k: f32) -> GAPowerLawScaling
+    {
+        assert!(k > 0.0, "power law exponent must be positive");
+        GAPowerLawScaling{ exponent: k }
+    }
+
+    pub fn exponent(&self) -> f32
+    {
+        self.exponent
+    }
+}
+
+impl Default for GAPowerLawScaling
+{
+    fn default() -> GAPowerLawScaling
+    {
+        GAPowerLawScaling::new(GA_POWER_LAW_EXPONENT)
+    }
+}
+
+impl<T: GAIndividual> GAScaling<T> for GAPowerLawScaling
+{
+    fn evaluate(&self, pop: &mut GAPopulation<T>)
+    {
+        let k = self.exponent;
+        for ind in pop.population()
+        {
+            let rs = ind.raw();
+            ind.set_fitness(rs.signum()*rs.abs().powf(k));
+        }
+
+        pop.invalidate_fitness();
+    }
+}
+
+// Sets fitness from each individual's position in the raw score order.
+// 'rank_fitness(rank, size)' maps rank 0 (best) to the highest value; for
+// `LowIsBest` populations the mapping is reversed so that lower fitness is
+// still better.
+fn rank_evaluate<T, F>(pop: &mut GAPopulation<T>, rank_fitness: F)
+    where T: GAIndividual, F: Fn(usize, usize) -> f32
+{
+    pop.sort_int(false, GAPopulationSortBasis::Raw);
+
+    let size = pop.size();
+    let order = pop.order();
+    let indices : Vec<usize> = (0..size).map(|i| pop.individual_index(i, GAPopulationSortBasis::Raw)
+                                                    .expect("raw scores were just sorted"))
+                                        .collect();
+
+    {
+        let pop_vec = pop.population();
+        for (rank, inx) in indices.into_iter().enumerate()
+        {
+            let f = match order
+            {
+                GAPopulationSortOrder::HighIsBest => rank_fitness(rank, size),
+                GAPopulationSortOrder::LowIsBest  => rank_fitness(size - 1 - rank, size)
+            };
+            pop_vec[inx].set_fitness(f);
+        }
+    }
+
+    pop.invalidate_fitness();
+}
+
+/// Linear Rank Scaling
+/// Baker's linear ranking. With selection pressure ```s``` in ```[1, 2]```,
+/// the best individual scores ```s```, the worst ```2 - s``` and the rest are
+/// spaced evenly in between, so the average fitness is always 1.
+pub struct GALinearRankScaling
+{
+    pressure: f32
+}
+
+pub const GA_LINEAR_RANK_PRESSURE : f32 = 1.5;
+
+impl GALinearRankScaling
+{
+    pub fn new(s: f32) -> GALinearRankScaling
+    {
+        assert!((1.0..=2.0).contains(&s), "linear rank pressure must be in [1, 2]");
+        GALinearRankScaling{ pressure: s }
+    }
+
+    pub fn pressure(&self) -> f32
+    {
+        self.pressure
+    }
+}
+
+impl Default for GALinearRankScaling
+{
+    fn default() -> GALinearRankScaling
+    {
+        GALinearRankScaling::new(GA_LINEAR_RANK_PRESSURE)
+    }
+}
+
+impl<T: GAIndividual> GAScaling<T> for GALinearRankScaling
+{
+    fn evaluate(&self, pop: &mut GAPopulation<T>)
+    {
+        let s = self.pressure;
+        rank_evaluate(pop, |rank, size|
+        {
+            if size < 2
+            {
+                return 1.0;
+            }
+            let position = (size - 1 - rank) as f32 / (size - 1) as f32;
+            (2.0 - s) + 2.0*(s - 1.0)*position
+        });
+    }
+}
+
+/// Exponential Rank Scaling
+/// The individual at rank ```i``` (0 being the best) scores ```base^i```.
+/// A ```base``` close to 1 gives mild selection pressure, one close to 0 a
+/// very strong one.
+pub struct GAExponentialRankScaling
+{
+    base: f32
+}
+
+pub const GA_EXPONENTIAL_RANK_BASE : f32 = 0.9;
+
+impl GAExponentialRankScaling
+{
+    pub fn new(base: f32) -> GAExponentialRankScaling
+    {
+        assert!(base > 0.0 && base < 1.0, "exponential rank base must be in (0, 1)");
+        GAExponentialRankScaling{ base }
+    }
+
+    pub fn base(&self) -> f32
+    {
+        self.base
+    }
+}
+
+impl Default for GAExponentialRankScaling
+{
+    fn default() -> GAExponentialRankScaling
+    {
+        GAExponentialRankScaling::new(GA_EXPONENTIAL_RANK_BASE)
+    }
+}
+
+impl<T: GAIndividual> GAScaling<T> for GAExponentialRankScaling
+{
+    fn evaluate(&self, pop: &mut GAPopulation<T>)
+    {
+        let base = self.base;
+        rank_evaluate(pop, |rank, _| base.powi(rank as i32));
+    }
+}
+
+/// Distance between two individuals, used by `GASharing` to measure how
+/// crowded a niche is. 0 means identical.
+pub type GASharingDistance<T> = Box<dyn Fn(&T, &T) -> f32 + Send + Sync>;
+
+/// Fitness Sharing (Goldberg and Richardson, via GALib)
+/// Divides each raw score by the individual's niche count,
+/// ```m_i = sum_j sh(d_ij)```, where
+/// ```sh(d) = 1 - (d/sigma_share)^alpha``` for ```d < sigma_share``` and 0
+/// otherwise. Individuals sharing a crowded niche split its payoff, which
+/// keeps several peaks populated on multimodal problems.
+///
+/// For `LowIsBest` populations the raw score is multiplied by the niche count
+/// instead, so crowding still makes an individual look worse. Either way raw
+/// scores are expected to be non-negative.
+///
+/// Sharing distances may have any scale, so they aren't recorded as the
+/// population's diversity; see `GAPopulation::compute_diversity` instead.
+pub struct GASharing<T: GAIndividual>
+{
+    distance: GASharingDistance<T>,
+    sigma: f32,
+    alpha: f32
+}
+
+pub const GA_SHARING_ALPHA : f32 = 1.0;
+
+impl<T: GAIndividual> GASharing<T>
+{
+    pub fn new<F>(distance: F, sigma: f32, alpha: f32) -> GASharing<T>
+        where F: Fn(&T, &T) -> f32 + Send + Sync + 'static
+    {
+        assert!(sigma > 0.0, "sharing cutoff sigma must be positive");
+        assert!(alpha > 0.0, "sharing alpha must be positive");
+        GASharing{ distance: Box::new(distance), sigma, alpha }
+    }
+
+    pub fn sigma(&self) -> f32
+    {
+        self.sigma
+    }
+
+    pub fn alpha(&self) -> f32
+    {
+        self.alpha
+    }
+
+    fn share(&self, d: f32) -> f32
+    {
+        if d < self.sigma
+        {
+            1.0 - (d / self.sigma).powf(self.alpha)
+        }
+        else
+        {
+            0.0
+        }
+    }
+}
+
+impl<T: GAIndividual> GAScaling<T> for GASharing<T>
+{
+    fn evaluate(&self, pop: &mut GAPopulation<T>)
+    {
+        let size = pop.size();
+        if size == 0
+        {
+            return;
+        }
+
+        // Every individual shares with itself: sh(0) = 1.
+        let mut niche_counts = vec![1.0; size];
+        {
+            let inds = pop.individuals();
+            for i in 0 .. size
+            {
+                for j in i+1 .. size
+                {
+                    let sh = self.share((self.distance)(&inds[i], &inds[j]));
+                    niche_counts[i] += sh;
+                    niche_counts[j] += sh;
+                }
+            }
+        }
+
+        let order = pop.order();
+        for (ind, m) in pop.population().iter_mut().zip(niche_counts)
+        {
+            let rs = ind.raw();
+            match order
+            {
+                GAPopulationSortOrder::HighIsBest => ind.set_fitness(rs / m),
+                GAPopulationSortOrder::LowIsBest  => ind.set_fitness(rs * m)
+            }
+        }
+
+        pop.invalidate_fitness();
     }
 }
 
@@ -108,6 +538,8 @@ mod test
     use super::super::ga_core::*;
     use super::super::ga_population::*;
     use super::super::ga_test::*;
+    use super::super::ga_random::GARandomCtx;
+    use super::super::ga_selectors::*;
     
     #[test]
     fn no_scaling()
@@ -121,8 +553,8 @@ mod test
 
         scaler.evaluate(&mut population);
 
-        assert_eq!(population.individual(0, GAPopulationSortBasis::Raw).fitness(),
-                   population.individual(0, GAPopulationSortBasis::Raw).raw());
+        assert_eq!(population.individual(0, GAPopulationSortBasis::Raw).unwrap().fitness(),
+                   population.individual(0, GAPopulationSortBasis::Raw).unwrap().raw());
 
         ga_test_teardown();
     }
@@ -140,10 +572,272 @@ mod test
         scaler.evaluate(&mut population);
 
         // TODO: Real test
-        assert!(population.individual(0, GAPopulationSortBasis::Raw).fitness() !=
-                population.individual(0, GAPopulationSortBasis::Raw).raw());
+        assert!(population.individual(0, GAPopulationSortBasis::Raw).unwrap().fitness() !=
+                population.individual(0, GAPopulationSortBasis::Raw).unwrap().raw());
+
+        ga_test_teardown();
+    }
+
+    fn sigma_population(order: GAPopulationSortOrder) -> GAPopulation<GATestIndividual>
+    {
+        // avg = 5, sample std_dev = sqrt(10) ~ 3.1623
+        let raws = [1.0, 3.0, 5.0, 7.0, 9.0];
+        GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(), order)
+    }
+
+    #[test]
+    fn sigma_truncation_scaling_high_is_best()
+    {
+        ga_test_setup("ga_scaling::sigma_truncation_scaling_high_is_best");
+        let mut population = sigma_population(GAPopulationSortOrder::HighIsBest);
+
+        let scaler = GASigmaTruncationScaling::new(1.0);
+        scaler.evaluate(&mut population);
+
+        let offset = 5.0 - 10.0f32.sqrt();
+        for ind in population.individuals()
+        {
+            let expected = (ind.raw() - offset).max(0.0);
+            assert!((ind.fitness() - expected).abs() < 1e-5);
+        }
+
+        // 1.0 lies more than one std_dev below the mean.
+        population.sort();
+        assert_eq!(population.worst_by_fitness_score().fitness(), 0.0);
+        assert_eq!(population.best_by_fitness_score().raw(), 9.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn sigma_truncation_scaling_low_is_best()
+    {
+        ga_test_setup("ga_scaling::sigma_truncation_scaling_low_is_best");
+        let mut population = sigma_population(GAPopulationSortOrder::LowIsBest);
+
+        let scaler = GASigmaTruncationScaling::new(1.0);
+        scaler.evaluate(&mut population);
+
+        population.sort();
+        assert_eq!(population.best_by_fitness_score().raw(), 1.0);
+        assert_eq!(population.best_by_fitness_score().fitness(), 0.0);
+
+        // 9.0 lies more than one std_dev above the mean: it is truncated to
+        // the worst fitness.
+        let worst = population.worst_by_fitness_score().fitness();
+        assert!((worst - (4.0 + 10.0f32.sqrt())).abs() < 1e-5);
+        for ind in population.individuals()
+        {
+            assert!(ind.fitness() >= 0.0);
+            if ind.raw() == 9.0
+            {
+                assert_eq!(ind.fitness(), worst);
+            }
+        }
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn sigma_truncation_scaling_low_is_best_roulette_wheel()
+    {
+        ga_test_setup("ga_scaling::sigma_truncation_scaling_low_is_best_roulette_wheel");
+        let mut population = sigma_population(GAPopulationSortOrder::LowIsBest);
+        GASigmaTruncationScaling::new(1.0).evaluate(&mut population);
+
+        // The best individual has 0 fitness; the truncated one gets no slice.
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("sigma_truncation_roulette_rng"));
+        let mut selector = GARouletteWheelSelector::new(population.size());
+        selector.update::<GAFitnessScoreSelection>(&mut population).unwrap();
+        let mut best_count = 0;
+        for _ in 0..50
+        {
+            let selected = selector.select::<GAFitnessScoreSelection>(&population, &mut rng_ctx).unwrap();
+            assert!(selected.raw() != 9.0);
+            if selected.raw() == 1.0
+            {
+                best_count += 1;
+            }
+        }
+        assert!(best_count > 0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn sigma_truncation_scaling_clamps_to_zero()
+    {
+        ga_test_setup("ga_scaling::sigma_truncation_scaling_clamps_to_zero");
+        // avg = 8, sample std_dev = sqrt(20) ~ 4.4721
+        let raws = [0.0, 10.0, 10.0, 10.0, 10.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+
+        assert_eq!(GASigmaTruncationScaling::default().multiplier(), GA_SIGMA_TRUNCATION_MULTIPLIER);
+
+        let scaler = GASigmaTruncationScaling::new(1.0);
+        scaler.evaluate(&mut population);
+
+        for ind in population.individuals()
+        {
+            assert!(ind.fitness() >= 0.0);
+        }
+
+        let stats = population.statistics().unwrap();
+        assert_eq!(stats.fitness_min, 0.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn power_law_scaling()
+    {
+        ga_test_setup("ga_scaling::power_law_scaling");
+        let raws = [-2.0, 1.0, 3.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+
+        let scaler = GAPowerLawScaling::new(2.0);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert_eq!(fitness, vec![-4.0, 1.0, 9.0]);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn linear_rank_scaling()
+    {
+        ga_test_setup("ga_scaling::linear_rank_scaling");
+        // Raw scores spanning orders of magnitude.
+        let raws = [1.0e6, 1.0, 1.0e3, 10.0, 1.0e-3];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+
+        let scaler = GALinearRankScaling::new(2.0);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert_eq!(fitness, vec![2.0, 0.5, 1.5, 1.0, 0.0]);
+
+        let stats = population.statistics().unwrap();
+        assert!((stats.fitness_avg - 1.0).abs() < 1e-5);
 
         ga_test_teardown();
     }
 
+    #[test]
+    fn linear_rank_scaling_low_is_best()
+    {
+        ga_test_setup("ga_scaling::linear_rank_scaling_low_is_best");
+        let raws = [5.0, 1.0, 3.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::LowIsBest);
+
+        let scaler = GALinearRankScaling::new(1.5);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert_eq!(fitness, vec![1.5, 0.5, 1.0]);
+
+        population.sort();
+        assert_eq!(population.best_by_fitness_score().raw(), 1.0);
+        assert_eq!(population.worst_by_fitness_score().raw(), 5.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn exponential_rank_scaling()
+    {
+        ga_test_setup("ga_scaling::exponential_rank_scaling");
+        let raws = [2.0, 8.0, 4.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+
+        let scaler = GAExponentialRankScaling::new(0.5);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert_eq!(fitness, vec![0.25, 1.0, 0.5]);
+
+        population.set_order_and_sort(GAPopulationSortOrder::LowIsBest);
+        scaler.evaluate(&mut population);
+        population.sort();
+        assert_eq!(population.best_by_fitness_score().raw(), 2.0);
+        assert_eq!(population.best_by_fitness_score().fitness(), 0.25);
+
+        ga_test_teardown();
+    }
+
+    fn raw_distance(a: &GATestIndividual, b: &GATestIndividual) -> f32
+    {
+        (a.raw() - b.raw()).abs()
+    }
+
+    #[test]
+    fn sharing_scaling()
+    {
+        ga_test_setup("ga_scaling::sharing_scaling");
+        // The first two individuals share a niche (distance 0.5 < sigma).
+        let raws = [10.0, 10.5, 20.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+        assert_eq!(population.diversity(), -1.0);
+
+        let scaler = GASharing::new(raw_distance, 1.0, GA_SHARING_ALPHA);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert!((fitness[0] - 10.0/1.5).abs() < 1e-5);
+        assert!((fitness[1] - 7.0).abs() < 1e-5);
+        assert_eq!(fitness[2], 20.0);
+
+        // Diversity is left to compute_diversity().
+        assert_eq!(population.diversity(), -1.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn sharing_scaling_low_is_best()
+    {
+        ga_test_setup("ga_scaling::sharing_scaling_low_is_best");
+        let raws = [10.0, 10.5, 20.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::LowIsBest);
+
+        // alpha = 2: sh(0.5) = 1 - 0.25
+        let scaler = GASharing::new(raw_distance, 1.0, 2.0);
+        scaler.evaluate(&mut population);
+
+        let fitness : Vec<f32> = population.individuals().iter().map(|i| i.fitness()).collect();
+        assert_eq!(fitness, vec![17.5, 18.375, 20.0]);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn scheme_parameters_are_checked()
+    {
+        ga_test_setup("ga_scaling::scheme_parameters_are_checked");
+        let invalid = [(GAScalingScheme::PowerLaw { exponent: 0.0 }, "power law exponent", 0.0),
+                       (GAScalingScheme::LinearRank { pressure: 0.5 }, "linear rank pressure", 0.5),
+                       (GAScalingScheme::ExponentialRank { base: 1.0 }, "exponential rank base", 1.0)];
+        for &(scheme, name, value) in invalid.iter()
+        {
+            assert!(scheme.build::<GATestIndividual>().err() == Some(GAError::InvalidParameter { name, value }));
+        }
+        assert!(GAScalingScheme::PowerLaw { exponent: f32::NAN }.build::<GATestIndividual>().is_err());
+
+        assert!(GAScalingScheme::Unchanged.build::<GATestIndividual>().unwrap().is_none());
+        for scheme in [GAScalingScheme::PowerLaw { exponent: 2.0 },
+                       GAScalingScheme::LinearRank { pressure: 2.0 },
+                       GAScalingScheme::ExponentialRank { base: 0.5 }].iter()
+        {
+            assert!(scheme.build::<GATestIndividual>().unwrap().is_some());
+        }
+        ga_test_teardown();
+    }
 }
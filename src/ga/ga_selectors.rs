@@ -23,12 +23,17 @@
 //! `GAUniformSelector`
 //! `GARouletteWheelSelector`
 //! `GATournamentSelector`
+//! `GAKTournamentSelector`
+//! `GAStochasticUniformSelector`
+//! `GAStochasticRemainderSelector`
+//! `GADeterministicSamplingSelector`
 //!
 //! # Examples
-use ::ga::ga_core::GAIndividual;
+use ::ga::ga_core::{GAError, GAIndividual};
 use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
 use ::ga::ga_random::{GARandomCtx};
 use std::cmp;
+use std::cmp::Ordering;
 
 /// Selector trait.
 ///
@@ -39,13 +44,52 @@ pub trait GASelector<T: GAIndividual>
     /// Update internal state. 
     ///
     /// NOOP default implementation for selectors that don't keep internal state.
-    fn update<S: GAScoreSelection<T>>(&mut self, _: &mut GAPopulation<T>) {}
+    /// Fails if the selector can't select from the population (e.g. scores
+    /// it can't handle); selections fail until a successful update.
+    fn update<S: GAScoreSelection<T>>(&mut self, _: &mut GAPopulation<T>) -> Result<(), GAError> { Ok(()) }
 
     /// Select an individual from the population. 
     ///
     /// Each selector implements a different method of selection. Randomization 
     /// is a key aspect of all methods.
-    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> &'a T;
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>;
+
+    /// Select `n` individuals from the population in one pass.
+    ///
+    /// Some methods of selection (stochastic universal sampling, remainder
+    /// sampling) are only meaningful when many individuals are selected at
+    /// once. The default implementation makes `n` independent selections.
+    fn select_many<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, n: usize, rng_ctx: &mut GARandomCtx) -> Result<Vec<&'a T>, GAError>
+    {
+        (0..n).map(|_| self.select::<S>(pop, rng_ctx)).collect()
+    }
+}
+
+// Selection needs individuals to select from.
+fn check_not_empty<T: GAIndividual>(pop: &GAPopulation<T>) -> Result<(), GAError>
+{
+    if pop.size() == 0
+    {
+        Err(GAError::EmptyPopulation)
+    }
+    else
+    {
+        Ok(())
+    }
+}
+
+// Per-individual selector state must match the population it selects from.
+fn check_updated<T: GAIndividual>(pop: &GAPopulation<T>, expected: usize) -> Result<(), GAError>
+{
+    check_not_empty(pop)?;
+    if expected != pop.size()
+    {
+        Err(GAError::SelectorNotUpdated { expected, size: pop.size() })
+    }
+    else
+    {
+        Ok(())
+    }
 }
 
 /// Selection score type basis.
@@ -68,7 +112,7 @@ pub trait GAScoreSelection<T: GAIndividual>
 
     fn min_score(pop: &GAPopulation<T>) -> f32;
 
-    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Box<Iterator<Item=&'a T> + 'a>;
+    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>, GAError>;
 }
 
 /// Selection based on raw score.
@@ -96,9 +140,9 @@ impl<T: GAIndividual> GAScoreSelection<T> for GARawScoreSelection
         Self::score(pop.worst_by_raw_score())
     }
 
-    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Box<Iterator<Item=&'a T> + 'a>
+    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>, GAError>
     {
-        Box::new(pop.raw_score_iterator())
+        Ok(Box::new(pop.raw_score_iterator()?))
     }
 }
 
@@ -127,9 +171,9 @@ impl<T: GAIndividual> GAScoreSelection<T> for GAFitnessScoreSelection
         Self::score(pop.worst_by_fitness_score())
     }
 
-    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Box<Iterator<Item=&'a T> + 'a>
+    fn iterator<'a>(pop: &'a GAPopulation<T>) -> Result<Box<dyn Iterator<Item=&'a T> + 'a>, GAError>
     {
-        Box::new(pop.fitness_score_iterator())
+        Ok(Box::new(pop.fitness_score_iterator()?))
     }
 }
 
@@ -137,6 +181,7 @@ impl<T: GAIndividual> GAScoreSelection<T> for GAFitnessScoreSelection
 ///
 /// Select the best individual of the population. If more than 1 share the
 /// best score, choose 1 among them at random.
+#[derive(Clone)]
 pub struct GARankSelector;
 
 impl GARankSelector
@@ -147,29 +192,41 @@ impl GARankSelector
     }
 }
 
+impl Default for GARankSelector
+{
+    fn default() -> GARankSelector
+    {
+        GARankSelector::new()
+    }
+}
+
 impl<T: GAIndividual> GASelector<T> for GARankSelector
 {
-    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>)
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
     {
         pop.sort();
+        Ok(())
     }
 
-    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> &'a T
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
     {
+        check_not_empty(pop)?;
+
         // All individuals that share the best score will be considered for selection.
-        let best_score: f32 = S::max_score(pop);
+        let best_score: f32 = S::score(pop.individual(0, S::population_sort_basis())?);
 
         // Collect all individuals that share the best score.
-        let best_inds: Vec<&T> = S::iterator(pop).take_while(|ind| S::score(ind) == best_score).collect();
+        let best_inds: Vec<&T> = S::iterator(pop)?.take_while(|ind| S::score(ind) == best_score).collect();
 
         // Select 1 from them at random.
-        best_inds[rng_ctx.gen_range(0, best_inds.len())]
+        Ok(best_inds[rng_ctx.gen_range(0, best_inds.len())])
     }
 }
 
 /// Uniform selector.
 ///
 /// Select an individual at random, with equal probability.
+#[derive(Clone)]
 pub struct GAUniformSelector;
 
 impl GAUniformSelector
@@ -180,18 +237,29 @@ impl GAUniformSelector
     }
 }
 
+impl Default for GAUniformSelector
+{
+    fn default() -> GAUniformSelector
+    {
+        GAUniformSelector::new()
+    }
+}
+
 impl<T: GAIndividual> GASelector<T> for GAUniformSelector
 {
-    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>)
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
     {
         // Need to sort first, because GAPopulation.individual() draws individuals
         // from the sorted lists.
         pop.sort();
+        Ok(())
     }
 
     // Select any individual at random.
-    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> &'a T
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
     {
+        check_not_empty(pop)?;
+
         // Since selection is at random, it doesn't matter where the individual
         // is drawn from, the raw-score-sorted or the fitness-score-sorted list.
         pop.individual(
@@ -200,11 +268,145 @@ impl<T: GAIndividual> GASelector<T> for GAUniformSelector
     }
 }
 
+// Weights of proportional selection, for the individuals of the (sorted)
+// population in score order: each individual weighs its score according to
+// ranking (LowIsBest or HighIsBest). All individuals weigh the same if they
+// all share the same score.
+//
+// Fails if scores have mixed signs: all of them must be >= 0 or all <= 0,
+// whatever the ranking.
+fn proportional_weights<T: GAIndividual, S: GAScoreSelection<T>>(pop: &GAPopulation<T>) -> Result<Vec<f32>, GAError>
+{
+    check_not_empty(pop)?;
+
+    let basis = S::population_sort_basis();
+    let best_score = S::score(pop.individual(0, basis)?);
+    let worst_score = S::score(pop.individual(pop.size()-1, basis)?);
+    let max_score = best_score.max(worst_score);
+    let min_score = best_score.min(worst_score);
+
+    if max_score == min_score
+    {
+        Ok(vec![1.0; pop.size()])
+    }
+    else if min_score >= 0.0 || max_score <= 0.0
+    {
+        let scores = (0..pop.size()).map(|i| pop.individual(i, basis).map(S::score))
+                                    .collect::<Result<Vec<f32>, GAError>>()?;
+        match pop.order()
+        {
+            GAPopulationSortOrder::HighIsBest
+            => Ok(scores),
+            GAPopulationSortOrder::LowIsBest
+            => Ok(scores.iter().map(|score| -score + max_score + min_score).collect())
+        }
+    }
+    else
+    {
+        Err(GAError::MixedSignScores { best: best_score, worst: worst_score })
+    }
+}
+
+// Selection probabilities: proportional weights normalized to add up to 1.
+fn proportional_probabilities<T: GAIndividual, S: GAScoreSelection<T>>(pop: &GAPopulation<T>) -> Result<Vec<f32>, GAError>
+{
+    let weights = proportional_weights::<T, S>(pop)?;
+    let sum: f32 = weights.iter().sum();
+    Ok(weights.iter().map(|w| w / sum).collect())
+}
+
+// Positions, in score order, of 'n' individuals chosen by remainder
+// sampling: individual i is chosen floor(n*p_i) times first. The remaining
+// slots are filled by Bernoulli trials on the fractional parts when an
+// 'rng_ctx' is given (stochastic remainder), or by the largest fractional
+// parts otherwise (deterministic sampling).
+fn remainder_sampling(probabilities: &[f32], n: usize, rng_ctx: Option<&mut GARandomCtx>) -> Vec<usize>
+{
+    let slots = probabilities.len();
+    let mut choices = Vec::with_capacity(n);
+    if slots == 0
+    {
+        return choices;
+    }
+
+    let mut fractions = Vec::with_capacity(slots);
+    for (i, p) in probabilities.iter().enumerate()
+    {
+        let expected = p * n as f32;
+        let copies = expected.floor();
+        for _ in 0..(copies as usize)
+        {
+            if choices.len() < n
+            {
+                choices.push(i);
+            }
+        }
+        fractions.push(expected - copies);
+    }
+
+    match rng_ctx
+    {
+        Some(rng_ctx) =>
+        {
+            while choices.len() < n && fractions.iter().any(|f| *f > 0.0)
+            {
+                for (i, fraction) in fractions.iter_mut().enumerate()
+                {
+                    if choices.len() == n
+                    {
+                        break;
+                    }
+                    if *fraction > 0.0 && rng_ctx.test_value(*fraction)
+                    {
+                        choices.push(i);
+                        *fraction = 0.0;
+                    }
+                }
+            }
+        },
+        None =>
+        {
+            // Stable: ties go to the better individual.
+            let mut by_fraction : Vec<usize> = (0..slots).collect();
+            by_fraction.sort_by(|a, b| fractions[*b].partial_cmp(&fractions[*a]).unwrap_or(Ordering::Equal));
+            for i in by_fraction
+            {
+                if choices.len() == n
+                {
+                    break;
+                }
+                choices.push(i);
+            }
+        }
+    }
+
+    // Rounding errors may leave slots unfilled; they go to the best.
+    let mut i = 0;
+    while choices.len() < n
+    {
+        choices.push(i % slots);
+        i += 1;
+    }
+
+    choices
+}
+
+// Fisher-Yates shuffle, so that batches don't come out in score order.
+fn shuffle<E>(v: &mut [E], rng_ctx: &mut GARandomCtx)
+{
+    for i in (1..v.len()).rev()
+    {
+        let j = rng_ctx.gen_range(0, i+1);
+        v.swap(i, j);
+    }
+}
+
 /// Roulette Wheel selector.
 ///
 /// Select an individual at random, each one having a probability of selection
 /// that is proportional to its score according to ranking (LowIsBest or 
 /// HighIsBest). 
+#[derive(Clone)]
 pub struct GARouletteWheelSelector
 {
     wheel_proportions: Vec<f32>,
@@ -216,96 +418,52 @@ impl GARouletteWheelSelector
     {
         let wheel_size = p_size;
 
+        // Empty until updated with a population.
         GARouletteWheelSelector
         {
-            wheel_proportions: vec![0.0; wheel_size],
+            wheel_proportions: Vec::with_capacity(wheel_size),
         }
     }
 }
 
+impl Default for GARouletteWheelSelector
+{
+    fn default() -> GARouletteWheelSelector
+    {
+        GARouletteWheelSelector::new(0)
+    }
+}
+
 impl<T: GAIndividual> GASelector<T> for GARouletteWheelSelector
 {
-    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>)
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
     {
-        if pop.size() != self.wheel_proportions.len()
-        {
-            self.wheel_proportions.resize(pop.size(), 0.0);
-        }
+        // A failed update leaves no wheel to select from.
+        self.wheel_proportions.clear();
 
         pop.sort();
 
-        let wheel_slots = self.wheel_proportions.len();
-        let max_score = S::max_score(pop);
-        let min_score = S::min_score(pop);
+        let weights = proportional_weights::<T, S>(pop)?;
 
-        if max_score == min_score
+        let mut sum = 0.0;
+        for w in &weights
         {
-            // Upper bound is excluded.
-            for i in 0 .. wheel_slots
-            {
-                self.wheel_proportions[i] = ((i+1) as f32)/(wheel_slots as f32);
-            }
+            sum += *w;
+            self.wheel_proportions.push(sum);
         }
-        else if (max_score > 0.0 && min_score >= 0.0) 
-                 || (max_score <= 0.0 && min_score < 0.0)
-        {
-            // This is not a move, but a copy.
-            let population_sort_basis = S::population_sort_basis();
 
-            match pop.order()
-            {
-                GAPopulationSortOrder::HighIsBest 
-                =>  {
-                        self.wheel_proportions[0] 
-                          = S::score(
-                              pop.individual(0, population_sort_basis));
-
-                        for i in 1 .. wheel_slots
-                        {
-                            self.wheel_proportions[i]
-                              = S::score(
-                                  pop.individual(i, population_sort_basis))
-                                + self.wheel_proportions[i-1]; 
-                        }
-
-                        for i in 0 .. wheel_slots
-                        {
-                            self.wheel_proportions[i] 
-                              /= self.wheel_proportions[wheel_slots-1];
-                        }
-                    },
-                GAPopulationSortOrder::LowIsBest
-                =>  {
-                        self.wheel_proportions[0] 
-                          = -S::score(
-                               pop.individual(0, population_sort_basis)) 
-                            + max_score + min_score;
-
-                        for i in 1 .. wheel_slots
-                        {
-                            self.wheel_proportions[i] 
-                              = -S::score(
-                                   pop.individual(i, population_sort_basis))
-                                + max_score + min_score 
-                                + self.wheel_proportions[i-1]; 
-                        }
-
-                        for i in 0 .. wheel_slots
-                        {
-                            self.wheel_proportions[i]
-                              /= self.wheel_proportions[wheel_slots-1];
-                        }
-                    }
-            }
-        }
-        else
+        for proportion in &mut self.wheel_proportions
         {
-            // TODO: Raise error.
+            *proportion /= sum;
         }
+
+        Ok(())
     }
 
-    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> &'a T
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
     {
+        check_updated(pop, self.wheel_proportions.len())?;
+
         let wheel_slots = self.wheel_proportions.len();
         let cutoff = rng_ctx.gen::<f32>();
         let mut lower = 0;
@@ -344,6 +502,9 @@ impl<T: GAIndividual> GASelector<T> for GARouletteWheelSelector
 /// Tournament selector.
 ///
 /// Select 2 individuals using Roulette Wheel selection and select the best of the 2.
+/// It inherits the Roulette Wheel's restrictions on score signs; see
+/// `GAKTournamentSelector` for a tournament that works with any scores.
+#[derive(Clone)]
 pub struct GATournamentSelector
 {
     roulette_wheel_selector: GARouletteWheelSelector,
@@ -360,14 +521,22 @@ impl GATournamentSelector
     }
 }
 
+impl Default for GATournamentSelector
+{
+    fn default() -> GATournamentSelector
+    {
+        GATournamentSelector::new(0)
+    }
+}
+
 impl<T: GAIndividual> GASelector<T> for GATournamentSelector
 {
-    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>)
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
     {
-        self.roulette_wheel_selector.update::<S>(pop);
+        self.roulette_wheel_selector.update::<S>(pop)
     }
 
-    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> &'a T
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
     {
         let low_score_individual;
         let high_score_individual;
@@ -375,8 +544,8 @@ impl<T: GAIndividual> GASelector<T> for GATournamentSelector
         let individual2;
 
         // Select 2 individuals using Roulette Wheel selection.
-        individual1 = self.roulette_wheel_selector.select::<S>(pop, rng_ctx);
-        individual2 = self.roulette_wheel_selector.select::<S>(pop, rng_ctx);
+        individual1 = self.roulette_wheel_selector.select::<S>(pop, rng_ctx)?;
+        individual2 = self.roulette_wheel_selector.select::<S>(pop, rng_ctx)?;
 
         if S::score(individual1) 
            >= S::score(individual2)
@@ -393,12 +562,293 @@ impl<T: GAIndividual> GASelector<T> for GATournamentSelector
         // Return the individual that is best according to population rank.
         match pop.order()
         {
-            GAPopulationSortOrder::HighIsBest => high_score_individual,
-            GAPopulationSortOrder::LowIsBest  => low_score_individual
+            GAPopulationSortOrder::HighIsBest => Ok(high_score_individual),
+            GAPopulationSortOrder::LowIsBest  => Ok(low_score_individual)
         } 
     }
 }
 
+/// k-Tournament selector.
+///
+/// Draw `k` individuals uniformly at random (with replacement) and select
+/// the best of them with probability `p`, the second best with probability
+/// `p*(1-p)`, the third with `p*(1-p)^2` and so on; the worst takes what is
+/// left. Only the ranking of the individuals matters, so scores may have any
+/// sign.
+#[derive(Clone)]
+pub struct GAKTournamentSelector
+{
+    size: usize,
+    probability: f32,
+}
+
+// Default: the better of 2 individuals, always.
+pub const GA_K_TOURNAMENT_SIZE : usize = 2;
+pub const GA_K_TOURNAMENT_PROBABILITY : f32 = 1.0;
+
+impl GAKTournamentSelector
+{
+    pub fn new(k: usize, p: f32) -> GAKTournamentSelector
+    {
+        assert!(k > 0, "tournament size must be at least 1");
+        assert!(p > 0.0 && p <= 1.0, "tournament probability must be in (0, 1]");
+
+        GAKTournamentSelector
+        {
+            size: k,
+            probability: p
+        }
+    }
+
+    pub fn size(&self) -> usize
+    {
+        self.size
+    }
+
+    pub fn probability(&self) -> f32
+    {
+        self.probability
+    }
+}
+
+impl Default for GAKTournamentSelector
+{
+    fn default() -> GAKTournamentSelector
+    {
+        GAKTournamentSelector::new(GA_K_TOURNAMENT_SIZE, GA_K_TOURNAMENT_PROBABILITY)
+    }
+}
+
+impl<T: GAIndividual> GASelector<T> for GAKTournamentSelector
+{
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
+    {
+        // Participants are drawn by rank.
+        pop.sort();
+        Ok(())
+    }
+
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
+    {
+        check_not_empty(pop)?;
+
+        // The lower the rank, the better the individual.
+        let mut ranks : Vec<usize> = (0..self.size).map(|_| rng_ctx.gen_range(0, pop.size())).collect();
+        ranks.sort();
+
+        let last = ranks.len() - 1;
+        let winner = ranks.iter()
+                          .take(last)
+                          .find(|_| rng_ctx.test_value(self.probability))
+                          .unwrap_or(&ranks[last]);
+
+        pop.individual(*winner, S::population_sort_basis())
+    }
+}
+
+/// Stochastic Universal Sampling selector.
+///
+/// Baker's SUS. A batch of `n` selections spins the roulette wheel once,
+/// with `n` equally spaced pointers, so every individual is chosen either
+/// the floor or the ceiling of its expected number of times. A single
+/// selection is a plain Roulette Wheel selection.
+#[derive(Clone)]
+pub struct GAStochasticUniformSelector
+{
+    roulette_wheel_selector: GARouletteWheelSelector,
+}
+
+impl GAStochasticUniformSelector
+{
+    pub fn new(p_size: usize) -> GAStochasticUniformSelector
+    {
+        GAStochasticUniformSelector
+        {
+            roulette_wheel_selector: GARouletteWheelSelector::new(p_size)
+        }
+    }
+}
+
+impl Default for GAStochasticUniformSelector
+{
+    fn default() -> GAStochasticUniformSelector
+    {
+        GAStochasticUniformSelector::new(0)
+    }
+}
+
+impl<T: GAIndividual> GASelector<T> for GAStochasticUniformSelector
+{
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
+    {
+        self.roulette_wheel_selector.update::<S>(pop)
+    }
+
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
+    {
+        self.roulette_wheel_selector.select::<S>(pop, rng_ctx)
+    }
+
+    fn select_many<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, n: usize, rng_ctx: &mut GARandomCtx) -> Result<Vec<&'a T>, GAError>
+    {
+        let wheel = &self.roulette_wheel_selector.wheel_proportions;
+        check_updated(pop, wheel.len())?;
+
+        let mut selected = Vec::with_capacity(n);
+        if n == 0
+        {
+            return Ok(selected);
+        }
+
+        let step = 1.0 / n as f32;
+        let mut pointer = rng_ctx.gen::<f32>() * step;
+        let mut slot = 0;
+        for _ in 0..n
+        {
+            while slot < wheel.len()-1 && wheel[slot] <= pointer
+            {
+                slot += 1;
+            }
+            selected.push(pop.individual(slot, S::population_sort_basis())?);
+            pointer += step;
+        }
+
+        shuffle(&mut selected, rng_ctx);
+        Ok(selected)
+    }
+}
+
+/// Stochastic Remainder Sampling selector.
+///
+/// GALib's GASRSSelector. In a batch of `n` selections, each individual is
+/// chosen as many times as the integer part of its expected number of
+/// selections (`n` times its share of the total score); the remaining
+/// selections are drawn by Bernoulli trials on the fractional parts. A
+/// single selection is a plain proportional selection.
+#[derive(Clone)]
+pub struct GAStochasticRemainderSelector
+{
+    probabilities: Vec<f32>,
+    roulette_wheel_selector: GARouletteWheelSelector,
+}
+
+impl GAStochasticRemainderSelector
+{
+    pub fn new(p_size: usize) -> GAStochasticRemainderSelector
+    {
+        GAStochasticRemainderSelector
+        {
+            probabilities: vec![],
+            roulette_wheel_selector: GARouletteWheelSelector::new(p_size)
+        }
+    }
+}
+
+impl Default for GAStochasticRemainderSelector
+{
+    fn default() -> GAStochasticRemainderSelector
+    {
+        GAStochasticRemainderSelector::new(0)
+    }
+}
+
+impl<T: GAIndividual> GASelector<T> for GAStochasticRemainderSelector
+{
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
+    {
+        self.probabilities.clear();
+        self.roulette_wheel_selector.update::<S>(pop)?;
+        self.probabilities = proportional_probabilities::<T, S>(pop)?;
+        Ok(())
+    }
+
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
+    {
+        self.roulette_wheel_selector.select::<S>(pop, rng_ctx)
+    }
+
+    fn select_many<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, n: usize, rng_ctx: &mut GARandomCtx) -> Result<Vec<&'a T>, GAError>
+    {
+        check_updated(pop, self.probabilities.len())?;
+
+        let mut selected = remainder_sampling(&self.probabilities, n, Some(&mut *rng_ctx))
+                             .into_iter()
+                             .map(|i| pop.individual(i, S::population_sort_basis()))
+                             .collect::<Result<Vec<&T>, GAError>>()?;
+        shuffle(&mut selected, rng_ctx);
+        Ok(selected)
+    }
+}
+
+/// Deterministic Sampling selector.
+///
+/// GALib's GADSSelector. Like `GAStochasticRemainderSelector`, but the
+/// remaining selections go to the individuals with the largest fractional
+/// parts, so the chosen individuals are fixed by their scores.
+///
+/// A single selection draws uniformly from the individuals chosen for a
+/// batch the size of the population.
+#[derive(Clone)]
+pub struct GADeterministicSamplingSelector
+{
+    probabilities: Vec<f32>,
+    choices: Vec<usize>,
+}
+
+impl GADeterministicSamplingSelector
+{
+    pub fn new() -> GADeterministicSamplingSelector
+    {
+        GADeterministicSamplingSelector
+        {
+            probabilities: vec![],
+            choices: vec![]
+        }
+    }
+}
+
+impl Default for GADeterministicSamplingSelector
+{
+    fn default() -> GADeterministicSamplingSelector
+    {
+        GADeterministicSamplingSelector::new()
+    }
+}
+
+impl<T: GAIndividual> GASelector<T> for GADeterministicSamplingSelector
+{
+    fn update<S: GAScoreSelection<T>>(&mut self, pop: &mut GAPopulation<T>) -> Result<(), GAError>
+    {
+        self.probabilities.clear();
+        self.choices.clear();
+
+        pop.sort();
+        self.probabilities = proportional_probabilities::<T, S>(pop)?;
+        self.choices = remainder_sampling(&self.probabilities, pop.size(), None);
+        Ok(())
+    }
+
+    fn select<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, rng_ctx: &mut GARandomCtx) -> Result<&'a T, GAError>
+    {
+        check_updated(pop, self.choices.len())?;
+
+        let i = self.choices[rng_ctx.gen_range(0, self.choices.len())];
+        pop.individual(i, S::population_sort_basis())
+    }
+
+    fn select_many<'a, S: GAScoreSelection<T>>(&self, pop: &'a GAPopulation<T>, n: usize, rng_ctx: &mut GARandomCtx) -> Result<Vec<&'a T>, GAError>
+    {
+        check_updated(pop, self.probabilities.len())?;
+
+        let mut selected = remainder_sampling(&self.probabilities, n, None)
+                             .into_iter()
+                             .map(|i| pop.individual(i, S::population_sort_basis()))
+                             .collect::<Result<Vec<&T>, GAError>>()?;
+        shuffle(&mut selected, rng_ctx);
+        Ok(selected)
+    }
+}
+
 
 ////////////////////////////////////////
 // Tests
@@ -430,18 +880,18 @@ mod test
         {
             let mut raw_rank_selector = GARankSelector::new();
 
-            raw_rank_selector.update::<GARawScoreSelection>(&mut population);
+            raw_rank_selector.update::<GARawScoreSelection>(&mut population).unwrap();
 
             // Best Raw score is that of 1st individual.
-            assert_eq!(raw_rank_selector.select::<GARawScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng"))).raw(), f);
+            assert_eq!(raw_rank_selector.select::<GARawScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng"))).unwrap().raw(), f);
         }
 
         {
             let mut fitness_rank_selector = GARankSelector::new();
 
-            fitness_rank_selector.update::<GAFitnessScoreSelection>(&mut population);
+            fitness_rank_selector.update::<GAFitnessScoreSelection>(&mut population).unwrap();
 
-            assert_eq!(fitness_rank_selector.select::<GAFitnessScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng"))).fitness(), i_f_m);
+            assert_eq!(fitness_rank_selector.select::<GAFitnessScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng"))).unwrap().fitness(), i_f_m);
         }
         ga_test_teardown();
     }
@@ -460,9 +910,9 @@ mod test
 
         let mut uniform_selector = GAUniformSelector::new();
 
-        uniform_selector.update::<GARawScoreSelection>(&mut population);
+        uniform_selector.update::<GARawScoreSelection>(&mut population).unwrap();
 
-        let selected_individual = uniform_selector.select::<GARawScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng")));
+        let selected_individual = uniform_selector.select::<GARawScoreSelection>(&population, &mut GARandomCtx::new_unseeded(String::from("test_rank_selector_rng"))).unwrap();
         assert!(selected_individual.raw() == f || selected_individual.raw() == f_m);  
         ga_test_teardown();
     }
@@ -491,18 +941,18 @@ mod test
             let mut raw_roulette_wheel_selector 
               = GARouletteWheelSelector::new(population.size());
 
-            raw_roulette_wheel_selector.update::<GARawScoreSelection>(&mut population);
+            raw_roulette_wheel_selector.update::<GARawScoreSelection>(&mut population).unwrap();
 
-            raw_roulette_wheel_selector.select::<GARawScoreSelection>(&population, &mut rng_ctx);
+            raw_roulette_wheel_selector.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap();
         }
         
         {
             let mut fitness_roulette_wheel_selector 
               = GARouletteWheelSelector::new(population.size());
 
-            fitness_roulette_wheel_selector.update::<GAFitnessScoreSelection>(&mut population);
+            fitness_roulette_wheel_selector.update::<GAFitnessScoreSelection>(&mut population).unwrap();
 
-            fitness_roulette_wheel_selector.select::<GAFitnessScoreSelection>(&population, &mut rng_ctx);
+            fitness_roulette_wheel_selector.select::<GAFitnessScoreSelection>(&population, &mut rng_ctx).unwrap();
         }
         ga_test_teardown();
     }
@@ -530,19 +980,267 @@ mod test
             let mut raw_tournament_selector 
               = GARouletteWheelSelector::new(population.size());
 
-            raw_tournament_selector.update::<GARawScoreSelection>(&mut population);
+            raw_tournament_selector.update::<GARawScoreSelection>(&mut population).unwrap();
 
-            raw_tournament_selector.select::<GARawScoreSelection>(&population, &mut rng_ctx);
+            raw_tournament_selector.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap();
         }
 
         {
             let mut fitness_tournament_selector 
               = GARouletteWheelSelector::new(population.size());
 
-            fitness_tournament_selector.update::<GAFitnessScoreSelection>(&mut population);
+            fitness_tournament_selector.update::<GAFitnessScoreSelection>(&mut population).unwrap();
+
+            fitness_tournament_selector.select::<GAFitnessScoreSelection>(&population, &mut rng_ctx).unwrap();
+        }
+        ga_test_teardown();
+    }
+
+    // Times each of 'raws' was selected.
+    fn selection_counts(raws: &[f32], selected: &[&GATestIndividual]) -> Vec<usize>
+    {
+        raws.iter().map(|r| selected.iter().filter(|ind| ind.raw() == *r).count()).collect()
+    }
+
+    fn sampling_population() -> GAPopulation<GATestIndividual>
+    {
+        GAPopulation::new(vec![GATestIndividual::new(4.0), GATestIndividual::new(3.0),
+                               GATestIndividual::new(2.0), GATestIndividual::new(1.0)],
+                          GAPopulationSortOrder::HighIsBest)
+    }
+
+    #[test]
+    fn test_stochastic_uniform_selector()
+    {
+        ga_test_setup("ga_selectors::test_stochastic_uniform_selector");
+        let raws = [4.0, 3.0, 2.0, 1.0];
+        let mut population = sampling_population();
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_stochastic_uniform_selector_rng"));
+
+        let mut selector = GAStochasticUniformSelector::new(population.size());
+        selector.update::<GARawScoreSelection>(&mut population).unwrap();
+
+        // Every individual is selected floor or ceil of n*raw/10 times.
+        for n in [4, 10, 7].iter().cloned()
+        {
+            let selected = selector.select_many::<GARawScoreSelection>(&population, n, &mut rng_ctx).unwrap();
+            assert_eq!(selected.len(), n);
+            for (raw, count) in raws.iter().zip(selection_counts(&raws, &selected))
+            {
+                let expected = n as f32 * raw / 10.0;
+                assert!(count as f32 >= expected.floor() - 1e-4 && count as f32 <= expected.ceil() + 1e-4);
+            }
+        }
+
+        selector.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap();
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_stochastic_remainder_selector()
+    {
+        ga_test_setup("ga_selectors::test_stochastic_remainder_selector");
+        let raws = [4.0, 3.0, 2.0, 1.0];
+        let mut population = sampling_population();
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_stochastic_remainder_selector_rng"));
+
+        let mut selector = GAStochasticRemainderSelector::new(population.size());
+        selector.update::<GARawScoreSelection>(&mut population).unwrap();
+
+        // Integer parts are always selected: 2.8, 2.1, 1.4 and 0.7 for n = 7.
+        for _ in 0..20
+        {
+            let selected = selector.select_many::<GARawScoreSelection>(&population, 7, &mut rng_ctx).unwrap();
+            assert_eq!(selected.len(), 7);
+            let counts = selection_counts(&raws, &selected);
+            assert!(counts[0] >= 2 && counts[0] <= 3);
+            assert!(counts[1] >= 2 && counts[1] <= 3);
+            assert!(counts[2] >= 1 && counts[2] <= 2);
+            assert!(counts[3] <= 1);
+        }
+
+        selector.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap();
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_deterministic_sampling_selector()
+    {
+        ga_test_setup("ga_selectors::test_deterministic_sampling_selector");
+        let raws = [4.0, 3.0, 2.0, 1.0];
+        let mut population = sampling_population();
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_deterministic_sampling_selector_rng"));
+
+        let mut selector = GADeterministicSamplingSelector::new();
+        selector.update::<GARawScoreSelection>(&mut population).unwrap();
+
+        // Expected 1.6, 1.2, 0.8 and 0.4: integer parts, then the 2 largest
+        // fractions (0.8 and 0.6).
+        let selected = selector.select_many::<GARawScoreSelection>(&population, 4, &mut rng_ctx).unwrap();
+        assert_eq!(selection_counts(&raws, &selected), vec![2, 1, 1, 0]);
+
+        // Single selections come from the same individuals.
+        for _ in 0..20
+        {
+            assert!(selector.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap().raw() != 1.0);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_sampling_selectors_low_is_best()
+    {
+        ga_test_setup("ga_selectors::test_sampling_selectors_low_is_best");
+        // Weights: -raw + 1 + 3 = 3, 2, 1.
+        let raws = [1.0, 2.0, 3.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::LowIsBest);
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_sampling_selectors_low_is_best_rng"));
+
+        let mut sus = GAStochasticUniformSelector::new(population.size());
+        sus.update::<GARawScoreSelection>(&mut population).unwrap();
+        let selected = sus.select_many::<GARawScoreSelection>(&population, 6, &mut rng_ctx).unwrap();
+        assert_eq!(selection_counts(&raws, &selected), vec![3, 2, 1]);
+
+        let mut ds = GADeterministicSamplingSelector::new();
+        ds.update::<GARawScoreSelection>(&mut population).unwrap();
+        let selected = ds.select_many::<GARawScoreSelection>(&population, 6, &mut rng_ctx).unwrap();
+        assert_eq!(selection_counts(&raws, &selected), vec![3, 2, 1]);
+        ga_test_teardown();
+    }
 
-            fitness_tournament_selector.select::<GAFitnessScoreSelection>(&population, &mut rng_ctx);
+    #[test]
+    fn test_proportional_selectors_low_is_best_zero_score()
+    {
+        ga_test_setup("ga_selectors::test_proportional_selectors_low_is_best_zero_score");
+        // Weights: -raw + 0 + 2 = 2, 1, 0. The worst is never selected.
+        let raws = [0.0, 1.0, 2.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::LowIsBest);
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_proportional_selectors_low_is_best_zero_score_rng"));
+
+        let mut roulette = GARouletteWheelSelector::new(population.size());
+        roulette.update::<GARawScoreSelection>(&mut population).unwrap();
+        let mut selected = vec![];
+        for _ in 0..30
+        {
+            selected.push(roulette.select::<GARawScoreSelection>(&population, &mut rng_ctx).unwrap());
         }
+        let counts = selection_counts(&raws, &selected);
+        assert_eq!(counts[2], 0);
+        assert!(counts[0] > counts[1]);
+
+        let mut sus = GAStochasticUniformSelector::new(population.size());
+        sus.update::<GARawScoreSelection>(&mut population).unwrap();
+        let selected = sus.select_many::<GARawScoreSelection>(&population, 6, &mut rng_ctx).unwrap();
+        assert_eq!(selection_counts(&raws, &selected), vec![4, 2, 0]);
+
+        let mut remainder = GAStochasticRemainderSelector::new(population.size());
+        remainder.update::<GARawScoreSelection>(&mut population).unwrap();
+        let selected = remainder.select_many::<GARawScoreSelection>(&population, 6, &mut rng_ctx).unwrap();
+        assert_eq!(selection_counts(&raws, &selected), vec![4, 2, 0]);
+
+        // Zero worst score: all scores are <= 0.
+        let mut population = GAPopulation::new(vec![GATestIndividual::new(-2.0),
+                                                    GATestIndividual::new(0.0)],
+                                               GAPopulationSortOrder::LowIsBest);
+        assert_eq!(roulette.update::<GARawScoreSelection>(&mut population), Ok(()));
+        assert_eq!(sus.update::<GARawScoreSelection>(&mut population), Ok(()));
+        assert_eq!(remainder.update::<GARawScoreSelection>(&mut population), Ok(()));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_k_tournament_selector()
+    {
+        ga_test_setup("ga_selectors::test_k_tournament_selector");
+        // Mixed-sign scores; fitness (1/raw) is mixed-sign too.
+        let raws = [-5.0, -1.0, 2.0, 3.0];
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_k_tournament_selector_rng"));
+
+        for order in [GAPopulationSortOrder::HighIsBest, GAPopulationSortOrder::LowIsBest].iter().cloned()
+        {
+            let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(), order);
+
+            let mut selector = GAKTournamentSelector::new(3, 1.0);
+            selector.update::<GARawScoreSelection>(&mut population).unwrap();
+
+            let best = population.best_by_raw_score().raw();
+            let worst = population.worst_by_raw_score().raw();
+
+            let selected = selector.select_many::<GARawScoreSelection>(&population, 1000, &mut rng_ctx).unwrap();
+            let best_count = selected.iter().filter(|ind| ind.raw() == best).count();
+            let worst_count = selected.iter().filter(|ind| ind.raw() == worst).count();
+
+            // The worst only wins when it is drawn 3 times: (1/4)^3.
+            assert!(best_count > 500);
+            assert!(worst_count < 50);
+
+            selector.update::<GAFitnessScoreSelection>(&mut population).unwrap();
+            let best_fitness = population.best_by_fitness_score().fitness();
+            let selected = selector.select_many::<GAFitnessScoreSelection>(&population, 1000, &mut rng_ctx).unwrap();
+            assert!(selected.iter().filter(|ind| ind.fitness() == best_fitness).count() > 500);
+        }
+
+        // Binary tournament by default.
+        let selector = GAKTournamentSelector::default();
+        assert_eq!(selector.size(), 2);
+        assert_eq!(selector.probability(), 1.0);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_k_tournament_selector_pressure()
+    {
+        ga_test_setup("ga_selectors::test_k_tournament_selector_pressure");
+        let raws = [1.0, 2.0, 3.0, 4.0];
+        let mut population = GAPopulation::new(raws.iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_k_tournament_selector_pressure_rng"));
+
+        // A single participant: uniform selection.
+        let mut uniform = GAKTournamentSelector::new(1, 1.0);
+        uniform.update::<GARawScoreSelection>(&mut population).unwrap();
+        let selected = uniform.select_many::<GARawScoreSelection>(&population, 1000, &mut rng_ctx).unwrap();
+        assert!(selected.iter().filter(|ind| ind.raw() == 1.0).count() > 150);
+
+        // Lower p, lower pressure.
+        let strong = GAKTournamentSelector::new(4, 1.0);
+        let weak = GAKTournamentSelector::new(4, 0.5);
+        assert_eq!(weak.size(), 4);
+        assert_eq!(weak.probability(), 0.5);
+        let strong_best = strong.select_many::<GARawScoreSelection>(&population, 1000, &mut rng_ctx).unwrap()
+                                .iter().filter(|ind| ind.raw() == 4.0).count();
+        let weak_best = weak.select_many::<GARawScoreSelection>(&population, 1000, &mut rng_ctx).unwrap()
+                            .iter().filter(|ind| ind.raw() == 4.0).count();
+        assert!(strong_best > weak_best);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_selector_errors()
+    {
+        ga_test_setup("ga_selectors::test_selector_errors");
+        let mut rng_ctx = GARandomCtx::from_seed([1, 2, 3, 4], String::from("test_selector_errors_rng"));
+
+        // Proportional selection is undefined for scores of mixed sign.
+        let mut population = GAPopulation::new(vec![GATestIndividual::new(2.0),
+                                                    GATestIndividual::new(-1.0)],
+                                               GAPopulationSortOrder::HighIsBest);
+        let mut roulette = GARouletteWheelSelector::new(2);
+        assert_eq!(roulette.update::<GARawScoreSelection>(&mut population),
+                   Err(GAError::MixedSignScores { best: 2.0, worst: -1.0 }));
+        // No stale wheel is left behind.
+        assert_eq!(roulette.select::<GARawScoreSelection>(&population, &mut rng_ctx).err(),
+                   Some(GAError::SelectorNotUpdated { expected: 0, size: 2 }));
+
+        let mut empty : GAPopulation<GATestIndividual> = GAPopulation::new(vec![], GAPopulationSortOrder::HighIsBest);
+        let mut rank = GARankSelector::new();
+        assert_eq!(rank.update::<GARawScoreSelection>(&mut empty), Ok(()));
+        assert_eq!(rank.select::<GARawScoreSelection>(&empty, &mut rng_ctx).err(),
+                   Some(GAError::EmptyPopulation));
+        assert_eq!(GAUniformSelector::new().select::<GARawScoreSelection>(&empty, &mut rng_ctx).err(),
+                   Some(GAError::EmptyPopulation));
         ga_test_teardown();
     }
 }
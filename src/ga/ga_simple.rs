@@ -1,20 +1,25 @@
 // Copyright 2016 Revolution Solid & Contributors.
 // author(s): sysnett
 // rust-monster is licensed under a MIT License.
-use ::ga::ga_core::{GAFactory, GAFlags, GeneticAlgorithm, GAIndividual};
+use ::ga::ga_core::{GAError, GAFactory, GAFlags, GeneticAlgorithm, GAIndividual, GAParallelIndividual};
 use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
 use ::ga::ga_random::{GARandomCtx, GASeed};
+use ::ga::ga_scaling::GAScalingScheme;
 use ::ga::ga_selectors::*;
+use ::ga::ga_statistics::GAStatistics;
+use ::ga::ga_terminators::{GAGenerationTerminator, GATerminationCtx, GATerminator};
 
-use std::any::Any;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
-/// Simple Evaluation Context
-/// Empty Evaluation Context 
-struct SimpleEvaluationCtx;
+// Evaluates a population, with whatever context and in however many
+// threads it was built for.
+type SimpleEvaluator<'a, T> = Box<dyn FnMut(&mut GAPopulation<T>) + 'a>;
 
 /// Simple Genetic Algorithm Config
 /// Genetic Algorithm Config Trait Implementation for the Simple Genetic Algorithm
 #[derive(Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SimpleGeneticAlgorithmCfg
 {
     pub d_seed : GASeed,
@@ -29,6 +34,20 @@ pub struct SimpleGeneticAlgorithmCfg
 
     pub elitism : bool,
 
+    // Score that selection is based on. With a scaling scheme (see
+    // 'scaling'), selecting on fitness selects on the scaled scores.
+    pub selection_basis : GAPopulationSortBasis,
+
+    // Scaling scheme set on the population when the algorithm is initialized
+    // (initialization fails if its parameters are invalid).
+    // The population rescales fitness every time it is evaluated.
+    pub scaling : GAScalingScheme,
+
+    // Threads that evaluate the population when the algorithm is created
+    // with a shared evaluation context (see 'new_with_shared_eval_ctx').
+    // 0 or 1 evaluate in the calling thread.
+    pub evaluation_workers      : usize,
+
     pub flags                   : GAFlags, 
 }
 
@@ -39,28 +58,52 @@ pub struct SimpleGeneticAlgorithmCfg
 /// This genetic algorithm is the 'simple' genetic algorithm that Goldberg describes 
 /// in his book. It uses non-overlapping populations. When you create a simple genetic 
 /// algorithm, you must specify either an individual or a population of individuals. 
-pub struct SimpleGeneticAlgorithm<'a, T: GAIndividual>
+///
+/// Parents are selected with a `GARouletteWheelSelector` unless another
+/// selector is given with `with_selector`. The algorithm is done after
+/// `max_generations` generations unless another terminator is given with
+/// `set_terminator`.
+pub struct SimpleGeneticAlgorithm<'a, T: GAIndividual, Sel: GASelector<T> = GARouletteWheelSelector>
 {
   current_generation : i32, 
   config : SimpleGeneticAlgorithmCfg,
   population : GAPopulation<T>,
   rng_ctx : GARandomCtx,
-  eval_ctx: Option<&'a mut Any>,
+  // Built by the constructors, which know how the population has to be
+  // evaluated.
+  evaluator: SimpleEvaluator<'a, T>,
+  selector: Sel,
+  terminator: Box<dyn GATerminator<T>>,
+  statistics: GAStatistics<T>,
 }
 impl<'a, T: GAIndividual> SimpleGeneticAlgorithm<'a, T>
 {
     pub fn new(cfg: SimpleGeneticAlgorithmCfg,
-               factory: Option<&mut GAFactory<T>>,
+               factory: Option<&mut dyn GAFactory<T>>,
                population: Option<GAPopulation<T>>) -> SimpleGeneticAlgorithm<'a, T>
+        where T::EvaluationCtx: Default + 'a
     {
-        SimpleGeneticAlgorithm::new_with_eval_ctx(cfg, factory, population, None)
+        let mut eval_ctx = T::EvaluationCtx::default();
+        let evaluator = move |pop: &mut GAPopulation<T>| pop.evaluate(&mut eval_ctx);
+
+        SimpleGeneticAlgorithm::new_with_evaluator(cfg, factory, population, Box::new(evaluator))
     }
 
     pub fn new_with_eval_ctx(cfg: SimpleGeneticAlgorithmCfg,
-                             factory: Option<&mut GAFactory<T>>,
+                             factory: Option<&mut dyn GAFactory<T>>,
                              population: Option<GAPopulation<T>>,
-                             eval_ctx: Option<&'a mut Any>) -> SimpleGeneticAlgorithm<'a, T>
+                             eval_ctx: &'a mut T::EvaluationCtx) -> SimpleGeneticAlgorithm<'a, T>
 
+    {
+        let evaluator = move |pop: &mut GAPopulation<T>| pop.evaluate(eval_ctx);
+
+        SimpleGeneticAlgorithm::new_with_evaluator(cfg, factory, population, Box::new(evaluator))
+    }
+
+    fn new_with_evaluator(cfg: SimpleGeneticAlgorithmCfg,
+                          factory: Option<&mut dyn GAFactory<T>>,
+                          population: Option<GAPopulation<T>>,
+                          evaluator: SimpleEvaluator<'a, T>) -> SimpleGeneticAlgorithm<'a, T>
     {
         //TODO: Some sort of generator for the name of the rng would be good
         let mut rng = GARandomCtx::from_seed(cfg.d_seed, String::from("")) ;
@@ -85,93 +128,195 @@ impl<'a, T: GAIndividual> SimpleGeneticAlgorithm<'a, T>
             }
         }
 
-        SimpleGeneticAlgorithm { current_generation: 0, config: cfg, population: p, rng_ctx: rng, eval_ctx: eval_ctx }
+        let selector = GARouletteWheelSelector::new(cfg.population_size);
+
+        SimpleGeneticAlgorithm { current_generation: 0, config: cfg, population: p, rng_ctx: rng, evaluator, selector,
+                                 terminator: Box::new(GAGenerationTerminator::new(cfg.max_generations)),
+                                 statistics: GAStatistics::new() }
+    }
+}
+impl<'a, T: GAIndividual, Sel: GASelector<T>> SimpleGeneticAlgorithm<'a, T, Sel>
+{
+    /// Select parents with `selector` instead.
+    ///
+    /// The selector is updated with the population before every generation,
+    /// on the score configured in `selection_basis`.
+    pub fn with_selector<U: GASelector<T>>(self, selector: U) -> SimpleGeneticAlgorithm<'a, T, U>
+    {
+        SimpleGeneticAlgorithm
+        {
+            current_generation: self.current_generation,
+            config: self.config,
+            population: self.population,
+            rng_ctx: self.rng_ctx,
+            evaluator: self.evaluator,
+            selector,
+            terminator: self.terminator,
+            statistics: self.statistics
+        }
+    }
+
+    pub fn selector(&self) -> &Sel
+    {
+        &self.selector
+    }
+
+    /// Decide when the algorithm is done with `terminator`, instead of after
+    /// `max_generations` generations.
+    pub fn set_terminator<U: GATerminator<T> + 'static>(&mut self, terminator: U)
+    {
+        self.terminator = Box::new(terminator);
+    }
+
+    /// Statistics of the run, updated on initialization and after every
+    /// generation.
+    pub fn statistics(&self) -> &GAStatistics<T>
+    {
+        &self.statistics
+    }
+
+    pub fn statistics_mut(&mut self) -> &mut GAStatistics<T>
+    {
+        &mut self.statistics
     }
 }
-impl<'a, T: GAIndividual + Clone> GeneticAlgorithm<T> for SimpleGeneticAlgorithm <'a, T>
+impl<'a, T: GAParallelIndividual> SimpleGeneticAlgorithm<'a, T> where T::EvaluationCtx: Sync
+{
+    /// Create a Simple Genetic Algorithm that evaluates its populations with
+    /// `cfg.evaluation_workers` threads, all of them sharing `eval_ctx`.
+    ///
+    /// The run is the same regardless of the number of workers.
+    pub fn new_with_shared_eval_ctx(cfg: SimpleGeneticAlgorithmCfg,
+                                    factory: Option<&mut dyn GAFactory<T>>,
+                                    population: Option<GAPopulation<T>>,
+                                    eval_ctx: &'a T::EvaluationCtx) -> SimpleGeneticAlgorithm<'a, T>
+    {
+        let workers = cfg.evaluation_workers;
+        let evaluator = move |pop: &mut GAPopulation<T>| pop.evaluate_parallel(eval_ctx, workers);
+
+        SimpleGeneticAlgorithm::new_with_evaluator(cfg, factory, population, Box::new(evaluator))
+    }
+}
+impl<'a, T: GAIndividual + Clone, Sel: GASelector<T>> GeneticAlgorithm<T> for SimpleGeneticAlgorithm <'a, T, Sel>
 {
     fn population(&mut self) -> &mut GAPopulation<T>
     {
         &mut self.population
     }
 
-    fn initialize_internal(&mut self)
+    fn initialize_internal(&mut self) -> Result<(), GAError>
     {
-        assert!(self.population().size() > 0);
-        match self.eval_ctx
+        if self.population.size() == 0
         {
-            Some(ref mut eval_ctx) =>
-            {
-                self.population.evaluate(*eval_ctx);
-            },
-            None =>
-            {
-                let mut v = SimpleEvaluationCtx{};
-                self.population.evaluate(&mut v as &mut Any);
-            }
+            return Err(GAError::EmptyPopulation);
         }
+        if let Some(scaling) = self.config.scaling.build()?
+        {
+            self.population.set_scaling(scaling);
+        }
+        (self.evaluator)(&mut self.population);
         self.population.sort();
+
+        self.statistics.reset();
+        self.statistics.count_population_evaluation(self.population.size());
+        self.statistics.set_best(self.population.clone());
+        self.terminator.reset();
+        Ok(())
     }
 
-    fn step_internal(&mut self) -> i32
+    fn step_internal(&mut self) -> Result<i32, GAError>
     {
-        let mut new_individuals : Vec<T> = vec![];
-
-        let mut roulette_selector = GARouletteWheelSelector::new(self.population.size());
-        roulette_selector.update::<GARawScoreSelection>(&mut self.population);
+        simple_generation(&mut self.population, &self.config, &mut self.selector, &mut self.rng_ctx,
+                          &mut self.statistics, &mut *self.evaluator)?;
+        self.statistics.update(&mut self.population);
 
+        self.current_generation += 1;
+        Ok(self.current_generation)
+    }
 
-        // Create new individuals 
-        for _ in 0..self.population.size()
+    fn done_internal(&mut self) -> bool
+    {
+        let evaluations = self.statistics.num_evaluations();
+        let done = self.terminator.done(&GATerminationCtx::new(self.current_generation, evaluations, &mut self.population));
+        if done
         {
-            let ind = roulette_selector.select::<GARawScoreSelection>(&self.population, &mut self.rng_ctx);
-            let mut new_ind = ind.clone();
-            if self.rng_ctx.test_value(self.config.probability_crossover)
-            {
-                let ind_2 = roulette_selector.select::<GARawScoreSelection>(&self.population, &mut self.rng_ctx);
-                new_ind = *ind.crossover(ind_2, &mut self.rng_ctx);
-            }
+            self.statistics.flush_scores_logged();
+        }
+        done
+    }
+}
 
-            new_ind.mutate(self.config.probability_mutation, &mut self.rng_ctx);
+// Replace 'population' with the next (non-overlapping) generation, evaluated
+// and sorted. Shared by all the algorithms that evolve populations the way
+// the Simple Genetic Algorithm does.
+pub(crate) fn simple_generation<T, Sel>(population: &mut GAPopulation<T>,
+                                        config: &SimpleGeneticAlgorithmCfg,
+                                        selector: &mut Sel,
+                                        rng_ctx: &mut GARandomCtx,
+                                        statistics: &mut GAStatistics<T>,
+                                        evaluator: &mut dyn FnMut(&mut GAPopulation<T>)) -> Result<(), GAError>
+    where T: GAIndividual + Clone, Sel: GASelector<T>
+{
+    let new_individuals = match config.selection_basis
+    {
+        GAPopulationSortBasis::Raw
+        => breed::<T, Sel, GARawScoreSelection>(population, config, selector, rng_ctx, statistics)?,
+        GAPopulationSortBasis::Fitness
+        => breed::<T, Sel, GAFitnessScoreSelection>(population, config, selector, rng_ctx, statistics)?,
+    };
+    statistics.count_replacements(new_individuals.len());
 
-            new_individuals.push(new_ind);
-        }
+    let best_old_individual = population.best(0, GAPopulationSortBasis::Fitness)?.clone();
 
-        let best_old_individual = self.population.best(0, GAPopulationSortBasis::Fitness).clone();
+    // Evaluate the new population
+    // TODO: Archive the old population
+    *population = population.next_generation(new_individuals);
 
-        // Evaluate the new population
-        // TODO: Archive the old population
-        let order = self.population.order();
-        self.population = GAPopulation::new(new_individuals, order);
+    evaluator(population);
+    statistics.count_population_evaluation(population.size());
+    population.sort();
 
-        match self.eval_ctx
-        {
-            Some(ref mut eval_ctx) =>
-            {
-                self.population.evaluate(*eval_ctx);
-            },
-            None =>
-            {
-                let mut v = SimpleEvaluationCtx{};
-                self.population.evaluate(&mut v as &mut Any);
-            }
-        }
-        self.population.sort();
+    if config.elitism
+    {
+        population.swap_individual(best_old_individual)?;
+        population.sort(); // I don't love the double sorting :(
+    }
 
-        if self.config.elitism
+    Ok(())
+}
+
+// Offspring of 'population', as many as individuals it has.
+fn breed<T, Sel, S>(population: &mut GAPopulation<T>,
+                    config: &SimpleGeneticAlgorithmCfg,
+                    selector: &mut Sel,
+                    rng_ctx: &mut GARandomCtx,
+                    statistics: &mut GAStatistics<T>) -> Result<Vec<T>, GAError>
+    where T: GAIndividual + Clone, Sel: GASelector<T>, S: GAScoreSelection<T>
+{
+    let mut new_individuals : Vec<T> = vec![];
+
+    selector.update::<S>(population)?;
+
+    // Create new individuals 
+    for _ in 0..population.size()
+    {
+        let ind = selector.select::<S>(population, rng_ctx)?;
+        statistics.count_selections(1);
+        let mut new_ind = ind.clone();
+        if rng_ctx.test_value(config.probability_crossover)
         {
-            self.population.swap_individual(best_old_individual);
-            self.population.sort(); // I don't love the double sorting :(
+            let ind_2 = selector.select::<S>(population, rng_ctx)?;
+            statistics.count_selections(1);
+            new_ind = *ind.crossover(ind_2, rng_ctx);
+            statistics.count_crossovers(1);
         }
 
-        self.current_generation += 1;
-        self.current_generation
-    }
+        statistics.count_mutations(new_ind.mutate(config.probability_mutation, rng_ctx));
 
-    fn done_internal(&mut self) -> bool
-    {
-        self.current_generation >= self.config.max_generations 
+        new_individuals.push(new_ind);
     }
+
+    Ok(new_individuals)
 }
 
 ////////////////////////////////////////
@@ -182,12 +327,19 @@ mod tests
     use ::ga::ga_test::*;
     use ::ga::ga_population::*;
     use ::ga::ga_core::*;
+    use ::ga::ga_random::GARandomCtx;
+    use ::ga::ga_statistics::{GAScoreFormat, GAScoreWriter};
+    use ::ga::ga_terminators::*;
     use super::*;
 
+    use std::io;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
     fn simple_ga_validation(sga:&mut SimpleGeneticAlgorithm<GATestIndividual>)
     {
-        sga.initialize();
-        assert_eq!(sga.step(), 1);
+        sga.initialize().unwrap();
+        assert_eq!(sga.step(), Ok(1));
         assert_eq!(sga.done(), false);
         assert_eq!(sga.population().size(), 1);
     }
@@ -224,13 +376,298 @@ mod tests
                                                    population_size: 1, 
                                                    ..Default::default()
                                                  },
-                                                 Some(&mut factory as &mut GAFactory<GATestIndividual>),
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
                                                  None
                                                  );
         simple_ga_validation(&mut ga);
         ga_test_teardown();
     }
 
+    #[test]
+    fn scaling_test()
+    {
+        ga_test_setup("ga_simple::scaling_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga : SimpleGeneticAlgorithm<GATestIndividual> =
+                     SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 100,
+                                                   population_size: 5,
+                                                   probability_crossover: 0.5,
+                                                   scaling: GAScalingScheme::LinearRank { pressure: 2.0 },
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None
+                                                 );
+
+        // Fitness is the rank of the raw score, before and after a generation.
+        let expected_fitness = vec![2.0, 1.5, 1.0, 0.5, 0.0];
+        ga.initialize().unwrap();
+        for _ in 0..2
+        {
+            let fitness : Vec<f32> = ga.population().fitness_score_iterator().unwrap().map(|ind| ind.fitness()).collect();
+            assert_eq!(fitness, expected_fitness);
+            assert_eq!(ga.population().best_by_fitness_score().raw(),
+                       ga.population().best_by_raw_score().raw());
+            ga.step().unwrap();
+        }
+
+        // Invalid parameters fail initialization instead of panicking.
+        let mut ga : SimpleGeneticAlgorithm<GATestIndividual> =
+                     SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   population_size: 5,
+                                                   scaling: GAScalingScheme::LinearRank { pressure: 3.0 },
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None
+                                                 );
+        assert_eq!(ga.initialize(), Err(GAError::InvalidParameter { name: "linear rank pressure", value: 3.0 }));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn sigma_truncation_low_is_best_test()
+    {
+        ga_test_setup("ga_simple::sigma_truncation_low_is_best_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga : SimpleGeneticAlgorithm<GATestIndividual> =
+                     SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 5,
+                                                   population_size: 10,
+                                                   probability_crossover: 0.5,
+                                                   population_sort_order: GAPopulationSortOrder::LowIsBest,
+                                                   selection_basis: GAPopulationSortBasis::Fitness,
+                                                   scaling: GAScalingScheme::SigmaTruncation { multiplier: 1.0 },
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None
+                                                 );
+
+        // The best individual's fitness is 0; roulette wheel selection on
+        // fitness still works.
+        ga.initialize().unwrap();
+        assert_eq!(ga.population().best_by_fitness_score().fitness(), 0.0);
+        for generation in 1..6
+        {
+            assert_eq!(ga.step(), Ok(generation));
+        }
+        ga_test_teardown();
+    }
+
+    // Always selects the best individual.
+    struct GATestBestSelector;
+
+    impl GASelector<GATestIndividual> for GATestBestSelector
+    {
+        fn update<S: GAScoreSelection<GATestIndividual>>(&mut self, pop: &mut GAPopulation<GATestIndividual>) -> Result<(), GAError>
+        {
+            pop.sort();
+            Ok(())
+        }
+
+        fn select<'b, S: GAScoreSelection<GATestIndividual>>(&self, pop: &'b GAPopulation<GATestIndividual>,
+                                                              _: &mut GARandomCtx) -> Result<&'b GATestIndividual, GAError>
+        {
+            pop.individual(0, S::population_sort_basis())
+        }
+    }
+
+    #[test]
+    fn selector_test()
+    {
+        ga_test_setup("ga_simple::selector_test");
+        // GATestIndividual's fitness is 1/raw: the best raw score is 4, the
+        // best fitness score belongs to raw score 1.
+        for &(basis, expected_raw) in [(GAPopulationSortBasis::Raw, 4.0),
+                                       (GAPopulationSortBasis::Fitness, 1.0)].iter()
+        {
+            let initial_population = GAPopulation::new([1.0, 2.0, 3.0, 4.0].iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                                       GAPopulationSortOrder::HighIsBest);
+            let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                       d_seed : [1; 4],
+                                                       flags : DEBUG_FLAG,
+                                                       max_generations: 100,
+                                                       selection_basis: basis,
+                                                       ..Default::default()
+                                                     },
+                                                     None,
+                                                     Some(initial_population)
+                                                     ).with_selector(GATestBestSelector);
+            ga.initialize().unwrap();
+            ga.step().unwrap();
+            assert!(ga.population().individuals().iter().all(|ind| ind.raw() == expected_raw));
+        }
+        ga_test_teardown();
+    }
+
+    // Score output kept readable after the writer is handed over.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+    impl Write for SharedBuffer
+    {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+        {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()>
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scores_written_when_done()
+    {
+        ga_test_setup("ga_simple::scores_written_when_done");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 5,
+                                                   population_size: 10,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None);
+        let buffer = SharedBuffer::default();
+        let mut writer = GAScoreWriter::new(buffer.clone(), GAScoreFormat::Tsv);
+        writer.set_flush_frequency(0);
+        ga.statistics_mut().set_score_writer(writer);
+
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            assert!(buffer.0.lock().unwrap().is_empty());
+        }
+
+        // Every generation, the initial one included.
+        let scores = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(scores.lines().count(), 6);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn statistics_test()
+    {
+        ga_test_setup("ga_simple::statistics_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 5,
+                                                   population_size: 10,
+                                                   probability_crossover: 0.5,
+                                                   probability_mutation: 0.1,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None);
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+
+        let stats = ga.statistics();
+        // The initial population is generation #1.
+        assert_eq!(stats.cur_generation, 6);
+        assert!(stats.generation_statistics(6).is_some());
+        assert_eq!(stats.num_population_evaluations(), 6);
+        assert_eq!(stats.num_evaluations(), 60);
+        assert_eq!(stats.num_replacements(), 50);
+        // 1 parent per child, 2 for those bred by crossover.
+        assert_eq!(stats.num_selections(), 50 + stats.num_crossovers());
+        assert!(stats.num_crossovers() > 0 && stats.num_crossovers() < 50);
+        // GATestIndividual's mutation does nothing.
+        assert_eq!(stats.num_mutations(), 0);
+
+        let error = 0.00001;
+        assert!(stats.offline_max_performance() <= stats.alltime_max_score + error);
+        assert!(stats.offline_min_performance() >= stats.alltime_min_score - error);
+        assert!(stats.online_performance() <= stats.offline_max_performance() + error);
+        let best = stats.best().unwrap();
+        assert_eq!(best.best_by_raw_score().raw(), stats.alltime_max_score);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn terminator_test()
+    {
+        ga_test_setup("ga_simple::terminator_test");
+        let new_ga = ||
+        {
+            let initial_population = GAPopulation::new([1.0, 2.0, 3.0, 4.0].iter().map(|r| GATestIndividual::new(*r)).collect(),
+                                                       GAPopulationSortOrder::HighIsBest);
+            SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                          d_seed : [1; 4],
+                                          flags : DEBUG_FLAG,
+                                          max_generations: 100,
+                                          ..Default::default()
+                                        },
+                                        None,
+                                        Some(initial_population)
+                                        ).with_selector(GATestBestSelector)
+        };
+        let run = |ga: &mut SimpleGeneticAlgorithm<GATestIndividual, GATestBestSelector>|
+        {
+            ga.initialize().unwrap();
+            let mut gen = 0;
+            while !ga.done()
+            {
+                gen = ga.step().unwrap();
+            }
+            gen
+        };
+
+        // 4 evaluations on initialization, 4 more every generation.
+        let mut ga = new_ga();
+        ga.set_terminator(GAEvaluationTerminator::new(10));
+        assert_eq!(run(&mut ga), 2);
+        assert_eq!(ga.statistics().num_evaluations(), 12);
+
+        // All individuals score 4 from the first generation on.
+        let mut ga = new_ga();
+        ga.set_terminator(GABestConvergenceTerminator::new(3, 0.0));
+        assert_eq!(run(&mut ga), 3);
+
+        let mut ga = new_ga();
+        ga.set_terminator(GAAllTerminator::new(vec![Box::new(GATargetScoreTerminator::new(4.0)),
+                                                    Box::new(GAGenerationTerminator::new(5))]));
+        assert_eq!(run(&mut ga), 5);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn tournament_selector_test()
+    {
+        ga_test_setup("ga_simple::tournament_selector_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 100,
+                                                   population_size: 10,
+                                                   selection_basis: GAPopulationSortBasis::Fitness,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                 None
+                                                 ).with_selector(GAKTournamentSelector::new(3, 0.9));
+        assert_eq!(ga.selector().size(), 3);
+        ga.initialize().unwrap();
+        assert_eq!(ga.step(), Ok(1));
+        assert_eq!(ga.population().size(), 10);
+        ga_test_teardown();
+    }
+
     #[test]
     #[should_panic]
     #[allow(unused_variables)]
@@ -252,7 +689,6 @@ mod tests
     }
 
     #[test]
-    #[should_panic]
     fn init_test_empty_initial_pop()
     {
         ga_test_setup("ga_simple::init_test_empty_initial_pop");
@@ -267,8 +703,41 @@ mod tests
                                                  None,
                                                  Some(empty_initial_population) 
                                                  );
-        ga.initialize();
-        //Not reached 
+        assert_eq!(ga.initialize(), Err(GAError::EmptyPopulation));
+        ga_test_teardown();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_serde()
+    {
+        ga_test_setup("ga_simple::config_serde");
+        let config = SimpleGeneticAlgorithmCfg {
+                       d_seed : [1, 2, 3, 4],
+                       flags : DEBUG_FLAG,
+                       max_generations: 100,
+                       population_size: 10,
+                       probability_crossover: 0.9,
+                       probability_mutation: 0.01,
+                       population_sort_order: GAPopulationSortOrder::LowIsBest,
+                       elitism: true,
+                       selection_basis: GAPopulationSortBasis::Fitness,
+                       scaling: GAScalingScheme::Linear { multiplier: 1.2 },
+                       ..Default::default()
+                     };
+
+        let json = ::serde_json::to_string(&config).unwrap();
+        let config_2 : SimpleGeneticAlgorithmCfg = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(config_2.d_seed, config.d_seed);
+        assert!(config_2.flags == DEBUG_FLAG);
+        assert_eq!(config_2.max_generations, 100);
+        assert_eq!(config_2.population_size, 10);
+        assert_eq!(config_2.probability_crossover, 0.9);
+        assert_eq!(config_2.probability_mutation, 0.01);
+        assert!(config_2.population_sort_order == GAPopulationSortOrder::LowIsBest);
+        assert!(config_2.elitism);
+        assert_eq!(config_2.selection_basis, GAPopulationSortBasis::Fitness);
+        assert_eq!(config_2.scaling, GAScalingScheme::Linear { multiplier: 1.2 });
         ga_test_teardown();
     }
 }
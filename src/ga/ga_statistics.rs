@@ -5,8 +5,117 @@
 use std::cmp::Ordering::*;
 
 use ::ga::ga_core::GAIndividual;
-use ::ga::ga_population::{GAPopulation, GAPopulationStats, GAPopulationSortOrder};
+use ::ga::ga_population::{GADiversityMode, GAPopulation, GAPopulationStats, GAPopulationSortOrder};
+use ::ga::ga_random::{GARandomCtx, GASeed};
 
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Diversity sampling doesn't draw from the algorithm's random context, so
+// that recording diversity doesn't change the course of a run.
+const GA_STATISTICS_DIVERSITY_SEED: GASeed = [1, 2, 3, 4];
+
+/// Score file layout.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GAScoreFormat
+{
+    /// Comma-separated values, with a header line.
+    #[default]
+    Csv,
+    /// Tab-separated values, without a header line. GALib's layout.
+    Tsv,
+}
+
+/// Score file writer.
+///
+/// Writes a line per generation recorded by a `GAStatistics` (see
+/// `GAStatistics::set_score_writer`) with: generation; raw average, maximum,
+/// minimum and standard deviation; fitness average, maximum, minimum and
+/// standard deviation; and diversity (-1 when not measured). GALib's
+/// scoreFilename and flushFrequency.
+pub struct GAScoreWriter
+{
+    writer: Box<dyn Write + Send>,
+    format: GAScoreFormat,
+    flush_frequency: u32,
+    header_written: bool,
+    // Generations already written.
+    written: usize,
+}
+impl GAScoreWriter
+{
+    /// Write scores to `writer`, flushing them every generation.
+    pub fn new<W: Write + Send + 'static>(writer: W, format: GAScoreFormat) -> GAScoreWriter
+    {
+        GAScoreWriter { writer: Box::new(writer), format, flush_frequency: 1, header_written: false, written: 0 }
+    }
+
+    /// Write scores to the file at `path`, which is created or truncated.
+    pub fn create<P: AsRef<Path>>(path: P, format: GAScoreFormat) -> io::Result<GAScoreWriter>
+    {
+        Ok(GAScoreWriter::new(BufWriter::new(File::create(path)?), format))
+    }
+
+    /// Flush scores every `generations` generations instead. 0 only flushes
+    /// when asked to (see `GAStatistics::flush_scores`) and when the genetic
+    /// algorithm is done.
+    pub fn set_flush_frequency(&mut self, generations: u32)
+    {
+        self.flush_frequency = generations;
+    }
+
+    pub fn flush_frequency(&self) -> u32
+    {
+        self.flush_frequency
+    }
+
+    pub fn format(&self) -> GAScoreFormat
+    {
+        self.format
+    }
+
+    // Write the generations of 'hist_stats' not written yet.
+    fn write(&mut self, hist_stats: &[GAPopulationStats]) -> io::Result<()>
+    {
+        let separator = match self.format
+        {
+            GAScoreFormat::Csv => ",",
+            GAScoreFormat::Tsv => "\t",
+        };
+
+        if self.format == GAScoreFormat::Csv && !self.header_written
+        {
+            writeln!(self.writer, "generation,raw_avg,raw_max,raw_min,raw_std_dev,\
+                                   fitness_avg,fitness_max,fitness_min,fitness_std_dev,diversity")?;
+        }
+        self.header_written = true;
+
+        for (i, stats) in hist_stats.iter().enumerate().skip(self.written)
+        {
+            let fields = [stats.raw_avg, stats.raw_max, stats.raw_min, stats.raw_std_dev,
+                          stats.fitness_avg, stats.fitness_max, stats.fitness_min, stats.fitness_std_dev,
+                          stats.diversity];
+            write!(self.writer, "{}", i+1)?;
+            for field in fields.iter()
+            {
+                write!(self.writer, "{}{}", separator, field)?;
+            }
+            writeln!(self.writer)?;
+        }
+        self.written = hist_stats.len();
+
+        self.writer.flush()
+    }
+}
+
+/// Genetic Algorithm Statistics
+///
+/// GALib's GAStatistics. Every genetic algorithm owns one and updates it on
+/// initialization and after every generation: scores of each generation,
+/// online and offline performance, the all-time best individuals and
+/// counters of the genetic operations performed.
 pub struct GAStatistics<T: GAIndividual>
 {
     // All statistics collected after last reset.
@@ -20,6 +129,9 @@ pub struct GAStatistics<T: GAIndividual>
     pub cur_generation: u32,            // aka curgen
     record_frequency: u32,              // aka scoreFreq
     record_diversity: bool,             // aka dodiv
+    diversity_mode: GADiversityMode,
+    diversity_rng_ctx: GARandomCtx,
+    score_writer: Option<GAScoreWriter>,
 
     pub alltime_best_pop: Option<GAPopulation<T>>,      // aka boa
     pub alltime_max_score: f32,                         // aka maxever
@@ -53,9 +165,17 @@ pub struct GAStatistics<T: GAIndividual>
 
 }
 
+impl<T: GAIndividual> Default for GAStatistics<T>
+{
+    fn default() -> GAStatistics<T>
+    {
+        GAStatistics::new()
+    }
+}
+
 impl<T: GAIndividual> GAStatistics<T>
 {
-    fn new() -> GAStatistics<T>
+    pub fn new() -> GAStatistics<T>
     {
         GAStatistics
         {
@@ -69,6 +189,10 @@ impl<T: GAIndividual> GAStatistics<T>
             cur_generation: 0,
             record_frequency: 1,
             record_diversity: false,
+            diversity_mode: GADiversityMode::Exact,
+            diversity_rng_ctx: GARandomCtx::from_seed(GA_STATISTICS_DIVERSITY_SEED,
+                                                      String::from("GAStatistics diversity")),
+            score_writer: None,
 
             alltime_best_pop: None,
             alltime_max_score: 0.0,
@@ -100,8 +224,211 @@ impl<T: GAIndividual> GAStatistics<T>
         }
     }
 
-    fn update(&mut self, pop: &mut GAPopulation<T>) where T: Clone + PartialEq
+    // Forget everything collected, keeping the settings (what and how is
+    // recorded). Genetic algorithms reset their statistics when initialized.
+    pub fn reset(&mut self)
+    {
+        let mut stats = GAStatistics::new();
+        stats.record_frequency = self.record_frequency;
+        stats.record_diversity = self.record_diversity;
+        stats.diversity_mode = self.diversity_mode;
+        // Scores of the new run follow those of the previous one.
+        self.flush_scores_logged();
+        stats.score_writer = self.score_writer.take().map(|mut writer| { writer.written = 0; writer });
+        *self = stats;
+    }
+
+    /// Write the scores of every generation recorded to `writer`.
+    pub fn set_score_writer(&mut self, writer: GAScoreWriter)
+    {
+        self.score_writer = Some(writer);
+    }
+
+    /// Stop writing scores, returning the writer.
+    pub fn take_score_writer(&mut self) -> Option<GAScoreWriter>
+    {
+        self.score_writer.take()
+    }
+
+    /// Write the scores not written yet.
+    pub fn flush_scores(&mut self) -> io::Result<()>
+    {
+        match self.score_writer
+        {
+            Some(ref mut writer) => writer.write(&self.hist_stats),
+            None => Ok(())
+        }
+    }
+
+    // Write the scores not written yet, logging failures. Genetic algorithms
+    // call it when they are done.
+    pub(crate) fn flush_scores_logged(&mut self)
+    {
+        if let Err(e) = self.flush_scores()
+        {
+            error!("GAStatistics - Failed to write scores: {}", e);
+        }
+    }
+
+    // Flush scores if the writer's frequency says so.
+    //
+    // is_multiple_of() needs Rust 1.87; '%' is guarded by the check for 0.
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+    fn scheduled_flush(&mut self)
+    {
+        let due = match self.score_writer
+        {
+            Some(ref writer) => writer.flush_frequency > 0 && self.cur_generation % writer.flush_frequency == 0,
+            None => false
+        };
+
+        if due
+        {
+            self.flush_scores_logged();
+        }
+    }
+
+    /// Parents selected.
+    pub fn num_selections(&self) -> usize
+    {
+        self.num_selections
+    }
+
+    /// Crossovers performed.
+    pub fn num_crossovers(&self) -> usize
+    {
+        self.num_crossovers
+    }
+
+    /// Mutations made (see `GAIndividual::mutate`).
+    pub fn num_mutations(&self) -> usize
+    {
+        self.num_mutations
+    }
+
+    /// Individuals that took the place of another one in a population.
+    pub fn num_replacements(&self) -> usize
+    {
+        self.num_replacements
+    }
+
+    /// Individuals evaluated.
+    pub fn num_evaluations(&self) -> usize
+    {
+        self.num_ind_evaluations
+    }
+
+    /// Whole populations evaluated.
+    pub fn num_population_evaluations(&self) -> usize
+    {
+        self.num_pop_evaluations
+    }
+
+    pub(crate) fn count_selections(&mut self, n: usize)
+    {
+        self.num_selections += n;
+    }
+
+    pub(crate) fn count_crossovers(&mut self, n: usize)
+    {
+        self.num_crossovers += n;
+    }
+
+    pub(crate) fn count_mutations(&mut self, n: usize)
+    {
+        self.num_mutations += n;
+    }
+
+    pub(crate) fn count_replacements(&mut self, n: usize)
+    {
+        self.num_replacements += n;
+    }
+
+    // 'n' individuals evaluated one by one.
+    pub(crate) fn count_evaluations(&mut self, n: usize)
+    {
+        self.num_ind_evaluations += n;
+    }
+
+    // A population of 'n' individuals evaluated at once.
+    pub(crate) fn count_population_evaluation(&mut self, n: usize)
+    {
+        self.num_pop_evaluations += 1;
+        self.num_ind_evaluations += n;
+    }
+
+    // Set the counters to the totals of 'stats' (e.g. those of all demes).
+    pub(crate) fn total_counts(&mut self, stats: &[&GAStatistics<T>])
+    {
+        self.num_selections = stats.iter().map(|s| s.num_selections).sum();
+        self.num_crossovers = stats.iter().map(|s| s.num_crossovers).sum();
+        self.num_mutations = stats.iter().map(|s| s.num_mutations).sum();
+        self.num_replacements = stats.iter().map(|s| s.num_replacements).sum();
+        self.num_ind_evaluations = stats.iter().map(|s| s.num_ind_evaluations).sum();
+        self.num_pop_evaluations = stats.iter().map(|s| s.num_pop_evaluations).sum();
+    }
+
+    /// Average of the average raw scores of every generation.
+    pub fn online_performance(&self) -> f32
+    {
+        self.on_performance
+    }
+
+    /// Average of the maximum raw scores of every generation.
+    pub fn offline_max_performance(&self) -> f32
+    {
+        self.off_max_performance
+    }
+
+    /// Average of the minimum raw scores of every generation.
+    pub fn offline_min_performance(&self) -> f32
+    {
+        self.off_min_performance
+    }
+
+    // Measure the diversity of every recorded generation (see
+    // GAPopulation::compute_diversity). Off by default: comparing
+    // individuals may be expensive.
+    pub fn set_record_diversity(&mut self, record: bool)
+    {
+        self.record_diversity = record;
+    }
+
+    pub fn record_diversity(&self) -> bool
+    {
+        self.record_diversity
+    }
+
+    pub fn set_diversity_mode(&mut self, mode: GADiversityMode)
+    {
+        self.diversity_mode = mode;
+    }
+
+    pub fn diversity_mode(&self) -> GADiversityMode
+    {
+        self.diversity_mode
+    }
+
+    // Diversity of each recorded generation, first one first. -1.0 for
+    // generations whose diversity wasn't measured.
+    pub fn diversity_history(&self) -> Vec<f32>
+    {
+        self.hist_stats.iter().map(|stats| stats.diversity).collect()
+    }
+
+    fn measure_diversity(&mut self, pop: &mut GAPopulation<T>)
+    {
+        if self.record_diversity
+        {
+            pop.compute_diversity(self.diversity_mode, &mut self.diversity_rng_ctx);
+        }
+    }
+
+    // Record the generation that follows the last one recorded.
+    pub fn update(&mut self, pop: &mut GAPopulation<T>) where T: Clone
     {
+        self.measure_diversity(pop);
+
         match pop.statistics()
         {
             None => 
@@ -113,34 +440,34 @@ impl<T: GAIndividual> GAStatistics<T>
             {
                 self.cur_generation += 1;
 
-                // TODO: Flush scores.
-
                 self.alltime_max_score = self.alltime_max_score.max(stats.raw_max);
                 self.alltime_min_score = self.alltime_min_score.min(stats.raw_min);
                 self.on_performance = (self.on_performance * (self.cur_generation-1) as f32 + stats.raw_avg) / self.cur_generation as f32;
                 self.off_max_performance = (self.off_max_performance * (self.cur_generation-1) as f32 + stats.raw_max) / self.cur_generation as f32;
                 self.off_min_performance = (self.off_min_performance * (self.cur_generation-1) as f32 + stats.raw_min) / self.cur_generation as f32;
 
-                // Store and compute diversity in GAPopulationStats.
-                // self.cur_diversity = if self.record_diversity { pop.diversity() } else { -1.0 };
-
                 // Update the alltime_best_pop with the input population.
                 self.update_best(pop);
                 
                 // Archive this generation's statistics.
                 self.hist_stats.push(stats);
+                self.scheduled_flush();
             }
         }
     }
 
-    fn best(&self) -> Option<GAPopulation<T>> where T: Clone
+    // The best individuals seen so far, as many as the population set_best()
+    // was given.
+    pub fn best(&self) -> Option<GAPopulation<T>> where T: Clone
     {
         self.alltime_best_pop.clone()
     }
 
     // Set generation #1. Or reset to new generation #1.
-    fn set_best(&mut self, mut pop: GAPopulation<T>)
+    pub fn set_best(&mut self, mut pop: GAPopulation<T>)
     {
+        self.measure_diversity(&mut pop);
+
         match pop.statistics()
         {
             None =>
@@ -150,19 +477,20 @@ impl<T: GAIndividual> GAStatistics<T>
             Some(stats) =>
             {
                 self.cur_generation = 1;
-                self.alltime_max_score = self.alltime_max_score.max(stats.raw_max);
-                self.alltime_min_score = self.alltime_min_score.min(stats.raw_min);
+                self.alltime_max_score = stats.raw_max;
+                self.alltime_min_score = stats.raw_min;
                 self.on_performance = (self.on_performance * (self.cur_generation-1) as f32 + stats.raw_avg) / self.cur_generation as f32;
                 self.off_max_performance = (self.off_max_performance * (self.cur_generation-1) as f32 + stats.raw_max) / self.cur_generation as f32;
                 self.off_min_performance = (self.off_min_performance * (self.cur_generation-1) as f32 + stats.raw_min) / self.cur_generation as f32;
 
                 self.alltime_best_pop = Some(pop);
                 self.hist_stats.push(stats);
+                self.scheduled_flush();
             }
         }
     }
 
-    fn update_best(&mut self, pop: &GAPopulation<T>) where T: Clone + PartialEq
+    fn update_best(&mut self, pop: &GAPopulation<T>) where T: Clone
     {
         match self.alltime_best_pop
         {
@@ -248,7 +576,10 @@ impl<T: GAIndividual> GAStatistics<T>
                                 let best_pop_jth_best = best_pop.kth_best_by_raw_score(j);
                                 best_pop_jth_best_raw = best_pop_jth_best.raw();
 
-                                if pop_ith_best == best_pop_jth_best
+                                // Already among the best. Individuals that can't be
+                                // compared (see GAIndividual::compare) are never
+                                // considered the same.
+                                if pop_ith_best.compare(best_pop_jth_best) == 0.0
                                 {
                                     break;
                                 }
@@ -281,7 +612,7 @@ impl<T: GAIndividual> GAStatistics<T>
     }
 
     // Get the statistics of the nth generation (#1 is the first one).
-    fn generation_statistics(&mut self, nth_generation: usize) -> Option<GAPopulationStats>
+    pub fn generation_statistics(&self, nth_generation: usize) -> Option<GAPopulationStats>
     {
         if nth_generation > 0 && nth_generation <= self.hist_stats.len()
         {
@@ -294,7 +625,7 @@ impl<T: GAIndividual> GAStatistics<T>
     }
 
     // Get the statistics of the alltime-best individuals.
-    fn alltime_best_statistics(&mut self) -> Option<GAPopulationStats>
+    pub fn alltime_best_statistics(&mut self) -> Option<GAPopulationStats>
     {
         match self.alltime_best_pop
         {
@@ -317,6 +648,8 @@ mod test
     use ::ga::ga_population::*;
     use ::ga::ga_random::GARandomCtx;
 
+    use std::sync::{Arc, Mutex};
+
     #[test]
     fn test_update_statistics()
     {
@@ -607,4 +940,174 @@ mod test
 
         ga_test_teardown();
     }
+
+    #[test]
+    fn test_record_diversity()
+    {
+        ga_test_setup("ga_statistics::test_record_diversity");
+
+        let population = |raws: &[f32]|
+        {
+            let mut pop = GAPopulation::new(raws.iter().map(|rs| GATestIndividual::new(*rs)).collect(),
+                                            GAPopulationSortOrder::HighIsBest);
+            pop.sort();
+            pop
+        };
+
+        let mut stats = GAStatistics::<GATestIndividual>::new();
+        assert!(!stats.record_diversity());
+        stats.set_best(population(&[1.0, 1.5]));
+
+        stats.set_record_diversity(true);
+        assert_eq!(stats.diversity_mode(), GADiversityMode::Exact);
+        let mut pop_2 = population(&[1.0, 1.25]);
+        stats.update(&mut pop_2);
+        assert_eq!(pop_2.diversity(), 0.25);
+
+        stats.set_diversity_mode(GADiversityMode::Sampled { pairs: 10 });
+        stats.update(&mut population(&[1.0, 1.1]));
+
+        let history = stats.diversity_history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], -1.0);
+        assert_eq!(history[1], 0.25);
+        assert!((history[2] - 0.1).abs() < 1e-6);
+        assert_eq!(stats.generation_statistics(2).unwrap().diversity, 0.25);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_counters()
+    {
+        ga_test_setup("ga_statistics::test_counters");
+
+        let mut stats = GAStatistics::<GATestIndividual>::new();
+        stats.set_record_diversity(true);
+        stats.count_selections(4);
+        stats.count_crossovers(2);
+        stats.count_replacements(3);
+        stats.count_evaluations(1);
+        stats.count_population_evaluation(10);
+        assert_eq!(stats.num_selections(), 4);
+        assert_eq!(stats.num_crossovers(), 2);
+        assert_eq!(stats.num_replacements(), 3);
+        assert_eq!(stats.num_evaluations(), 11);
+        assert_eq!(stats.num_population_evaluations(), 1);
+
+        stats.count_mutations(5);
+        assert_eq!(stats.num_mutations(), 5);
+
+        let mut total = GAStatistics::<GATestIndividual>::new();
+        total.total_counts(&[&GAStatistics::new(), &stats]);
+        assert_eq!(total.num_evaluations(), 11);
+        assert_eq!(total.num_selections(), 4);
+
+        // Settings survive a reset.
+        total.set_record_diversity(true);
+        total.reset();
+        assert_eq!(total.num_evaluations(), 0);
+        assert!(total.record_diversity());
+
+        ga_test_teardown();
+    }
+
+    // Score output kept readable after the writer is handed over.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+    impl SharedBuffer
+    {
+        fn lines(&self) -> Vec<String>
+        {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap().lines().map(String::from).collect()
+        }
+    }
+    impl Write for SharedBuffer
+    {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+        {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()>
+        {
+            Ok(())
+        }
+    }
+
+    fn score_test_population(raw_scores: &[f32]) -> GAPopulation<GATestIndividual>
+    {
+        let inds = raw_scores.iter().map(|rs| GATestIndividual::new(*rs)).collect();
+        let mut pop = GAPopulation::new(inds, GAPopulationSortOrder::HighIsBest);
+        pop.sort();
+        pop
+    }
+
+    #[test]
+    fn test_score_writer_csv()
+    {
+        ga_test_setup("ga_statistics::test_score_writer_csv");
+
+        let buffer = SharedBuffer::default();
+        let mut stats = GAStatistics::<GATestIndividual>::new();
+        stats.set_score_writer(GAScoreWriter::new(buffer.clone(), GAScoreFormat::Csv));
+
+        stats.set_best(score_test_population(&[1.0, 2.0, 4.0]));
+        stats.update(&mut score_test_population(&[2.0, 4.0, 8.0]));
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "generation,raw_avg,raw_max,raw_min,raw_std_dev,\
+                              fitness_avg,fitness_max,fitness_min,fitness_std_dev,diversity");
+
+        let gen2_stats = stats.generation_statistics(2).unwrap();
+        let fields: Vec<&str> = lines[2].split(',').collect();
+        assert_eq!(fields.len(), 10);
+        assert_eq!(fields[0], "2");
+        assert_eq!(fields[1].parse::<f32>().unwrap(), gen2_stats.raw_avg);
+        assert_eq!(fields[2].parse::<f32>().unwrap(), 8.0);
+        assert_eq!(fields[3].parse::<f32>().unwrap(), 2.0);
+        assert_eq!(fields[4].parse::<f32>().unwrap(), gen2_stats.raw_std_dev);
+        assert_eq!(fields[5].parse::<f32>().unwrap(), gen2_stats.fitness_avg);
+        assert_eq!(fields[8].parse::<f32>().unwrap(), gen2_stats.fitness_std_dev);
+        // Diversity isn't recorded.
+        assert_eq!(fields[9].parse::<f32>().unwrap(), -1.0);
+
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_score_writer_flush_frequency()
+    {
+        ga_test_setup("ga_statistics::test_score_writer_flush_frequency");
+
+        let buffer = SharedBuffer::default();
+        let mut writer = GAScoreWriter::new(buffer.clone(), GAScoreFormat::Tsv);
+        writer.set_flush_frequency(2);
+        let mut stats = GAStatistics::<GATestIndividual>::new();
+        stats.set_score_writer(writer);
+
+        stats.set_best(score_test_population(&[1.0, 2.0]));
+        assert_eq!(buffer.lines().len(), 0);
+        stats.update(&mut score_test_population(&[2.0, 3.0]));
+        assert_eq!(buffer.lines().len(), 2);
+        stats.update(&mut score_test_population(&[3.0, 4.0]));
+        assert_eq!(buffer.lines().len(), 2);
+
+        // No header, tab-separated.
+        let lines = buffer.lines();
+        assert!(lines[0].starts_with("1\t"));
+        assert_eq!(lines[1].split('\t').count(), 10);
+
+        // Pending scores are written before a new run starts over at
+        // generation 1.
+        stats.reset();
+        assert_eq!(buffer.lines().len(), 3);
+        assert!(buffer.lines()[2].starts_with("3\t3.5\t4\t3\t"));
+        stats.set_best(score_test_population(&[1.0, 2.0]));
+        stats.update(&mut score_test_population(&[2.0, 3.0]));
+        assert_eq!(buffer.lines().len(), 5);
+        assert!(buffer.lines()[3].starts_with("1\t"));
+
+        ga_test_teardown();
+    }
 }
@@ -0,0 +1,464 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Steady-State Genetic Algorithm
+//!
+//! Genetic Algorithm with overlapping populations. Each generation, only a
+//! fraction of the population is replaced by offspring; the rest of the
+//! individuals survive to the next generation.
+use ::ga::ga_core::{GAError, GAEvaluationCtx, GAFactory, GAFlags, GeneticAlgorithm, GAIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortBasis, GAPopulationSortOrder};
+use ::ga::ga_random::{GARandomCtx, GASeed};
+use ::ga::ga_replacement::GAReplacement;
+use ::ga::ga_scaling::GAScalingScheme;
+use ::ga::ga_selectors::{GASelector, GAScoreSelection, GARawScoreSelection, GAFitnessScoreSelection};
+use ::ga::ga_statistics::GAStatistics;
+use ::ga::ga_terminators::{GAGenerationTerminator, GATerminationCtx, GATerminator};
+
+/// Steady-State Genetic Algorithm Config
+#[derive(Copy, Clone, Default)]
+pub struct SteadyStateGeneticAlgorithmCfg
+{
+    pub d_seed : GASeed,
+
+    pub max_generations         : i32,
+    pub population_size         : usize,
+
+    pub probability_crossover   : f32,
+    pub probability_mutation    : f32,
+
+    pub population_sort_order : GAPopulationSortOrder,
+
+    // Score that selection is based on. With a scaling scheme (see
+    // 'scaling'), selecting on fitness selects on the scaled scores.
+    pub selection_basis : GAPopulationSortBasis,
+
+    // Scaling scheme set on the population when the algorithm is initialized
+    // (initialization fails if its parameters are invalid).
+    // The population rescales fitness every time it is evaluated.
+    pub scaling : GAScalingScheme,
+
+    // Fraction of the population replaced each generation.
+    pub replacement_percentage  : f32,
+    // Number of individuals replaced each generation. Takes precedence
+    // over 'replacement_percentage' when not 0.
+    pub replacement_number      : usize,
+
+    pub flags                   : GAFlags,
+}
+
+/// Steady-State Genetic Algorithm
+///
+/// GALib's GASteadyStateGA.
+///
+/// Each generation, parents are chosen with the selector `S` and bred into
+/// as many children as individuals are to be replaced. The children are then
+/// inserted in the population one by one; the replacement scheme `R` decides
+/// which individual each of them displaces (if any).
+pub struct SteadyStateGeneticAlgorithm<'a, T: GAIndividual, S: GASelector<T>, R: GAReplacement<T>>
+{
+    current_generation : i32,
+    config : SteadyStateGeneticAlgorithmCfg,
+    population : GAPopulation<T>,
+    selector : S,
+    replacement : R,
+    rng_ctx : GARandomCtx,
+    eval_ctx: GAEvaluationCtx<'a, T::EvaluationCtx>,
+    terminator : Box<dyn GATerminator<T>>,
+    statistics : GAStatistics<T>,
+}
+impl<'a, T: GAIndividual, S: GASelector<T>, R: GAReplacement<T>> SteadyStateGeneticAlgorithm<'a, T, S, R>
+{
+    pub fn new(cfg: SteadyStateGeneticAlgorithmCfg,
+               factory: Option<&mut dyn GAFactory<T>>,
+               population: Option<GAPopulation<T>>,
+               selector: S,
+               replacement: R) -> SteadyStateGeneticAlgorithm<'a, T, S, R>
+        where T::EvaluationCtx: Default
+    {
+        SteadyStateGeneticAlgorithm::new_internal(cfg, factory, population, selector, replacement,
+                                      GAEvaluationCtx::Owned(T::EvaluationCtx::default()))
+    }
+
+    pub fn new_with_eval_ctx(cfg: SteadyStateGeneticAlgorithmCfg,
+                             factory: Option<&mut dyn GAFactory<T>>,
+                             population: Option<GAPopulation<T>>,
+                             selector: S,
+                             replacement: R,
+                             eval_ctx: &'a mut T::EvaluationCtx) -> SteadyStateGeneticAlgorithm<'a, T, S, R>
+    {
+        SteadyStateGeneticAlgorithm::new_internal(cfg, factory, population, selector, replacement,
+                                      GAEvaluationCtx::Borrowed(eval_ctx))
+    }
+
+    fn new_internal(cfg: SteadyStateGeneticAlgorithmCfg,
+                    factory: Option<&mut dyn GAFactory<T>>,
+                    population: Option<GAPopulation<T>>,
+                    selector: S,
+                    replacement: R,
+                    eval_ctx: GAEvaluationCtx<'a, T::EvaluationCtx>) -> SteadyStateGeneticAlgorithm<'a, T, S, R>
+    {
+        let mut rng = GARandomCtx::from_seed(cfg.d_seed, String::from(""));
+        let p = match factory
+        {
+            Some(f) => f.random_population(cfg.population_size, cfg.population_sort_order, &mut rng),
+            None =>
+            {
+                match population
+                {
+                    Some(p_) => p_,
+                    None =>
+                    {
+                        panic!("Steady-State Genetic Algorithm - either factory or population need to be provided");
+                    }
+                }
+            }
+        };
+
+        SteadyStateGeneticAlgorithm { current_generation: 0, config: cfg, population: p,
+                                      selector, replacement, rng_ctx: rng, eval_ctx,
+                                      terminator: Box::new(GAGenerationTerminator::new(cfg.max_generations)),
+                                      statistics: GAStatistics::new() }
+    }
+
+    /// Decide when the algorithm is done with `terminator`, instead of after
+    /// `max_generations` generations.
+    pub fn set_terminator<U: GATerminator<T> + 'static>(&mut self, terminator: U)
+    {
+        self.terminator = Box::new(terminator);
+    }
+
+    /// Statistics of the run, updated on initialization and after every
+    /// generation.
+    pub fn statistics(&self) -> &GAStatistics<T>
+    {
+        &self.statistics
+    }
+
+    pub fn statistics_mut(&mut self) -> &mut GAStatistics<T>
+    {
+        &mut self.statistics
+    }
+
+    /// Number of individuals replaced each generation.
+    ///
+    /// At least 1 and at most the size of the population.
+    pub fn replacement_count(&self) -> usize
+    {
+        let size = self.population.size();
+        let n = if self.config.replacement_number > 0
+        {
+            self.config.replacement_number
+        }
+        else
+        {
+            (self.config.replacement_percentage * size as f32).round() as usize
+        };
+
+        if n < 1 { 1 } else if n > size { size } else { n }
+    }
+
+    fn evaluate_individual(&mut self, ind: &mut T)
+    {
+        ind.evaluate(self.eval_ctx.get_mut());
+        self.statistics.count_evaluations(1);
+    }
+
+    fn evaluate_population(&mut self)
+    {
+        self.population.evaluate(self.eval_ctx.get_mut());
+        self.statistics.count_population_evaluation(self.population.size());
+    }
+
+    // Breed 'n' children from the current population, selecting parents on
+    // the score 'B' picks. Parents are kept as positions in the individuals
+    // vector, for the replacement scheme.
+    fn breed<B: GAScoreSelection<T>>(&mut self, n: usize) -> Result<Vec<(T, Vec<usize>)>, GAError>
+        where T: Clone
+    {
+        self.selector.update::<B>(&mut self.population)?;
+
+        let mut children : Vec<(T, Vec<usize>)> = vec![];
+        for _ in 0..n
+        {
+            let (mut child, parents) =
+            {
+                let mom = self.selector.select::<B>(&self.population, &mut self.rng_ctx)?;
+                let mom_inx = self.population.index_of(mom).unwrap();
+                self.statistics.count_selections(1);
+
+                if self.rng_ctx.test_value(self.config.probability_crossover)
+                {
+                    let dad = self.selector.select::<B>(&self.population, &mut self.rng_ctx)?;
+                    let dad_inx = self.population.index_of(dad).unwrap();
+                    self.statistics.count_selections(1);
+                    self.statistics.count_crossovers(1);
+                    (*mom.crossover(dad, &mut self.rng_ctx), vec![mom_inx, dad_inx])
+                }
+                else
+                {
+                    (mom.clone(), vec![mom_inx])
+                }
+            };
+
+            let mutations = child.mutate(self.config.probability_mutation, &mut self.rng_ctx);
+            self.statistics.count_mutations(mutations);
+            self.evaluate_individual(&mut child);
+
+            children.push((child, parents));
+        }
+
+        Ok(children)
+    }
+}
+impl<'a, T: GAIndividual + Clone, S: GASelector<T>, R: GAReplacement<T>> GeneticAlgorithm<T> for SteadyStateGeneticAlgorithm<'a, T, S, R>
+{
+    fn population(&mut self) -> &mut GAPopulation<T>
+    {
+        &mut self.population
+    }
+
+    fn initialize_internal(&mut self) -> Result<(), GAError>
+    {
+        if self.population.size() == 0
+        {
+            return Err(GAError::EmptyPopulation);
+        }
+        if let Some(scaling) = self.config.scaling.build()?
+        {
+            self.population.set_scaling(scaling);
+        }
+        self.statistics.reset();
+        self.terminator.reset();
+        self.evaluate_population();
+        self.population.sort();
+        self.statistics.set_best(self.population.clone());
+        Ok(())
+    }
+
+    fn step_internal(&mut self) -> Result<i32, GAError>
+    {
+        let n_replace = self.replacement_count();
+
+        let children = match self.config.selection_basis
+        {
+            GAPopulationSortBasis::Raw => self.breed::<GARawScoreSelection>(n_replace)?,
+            GAPopulationSortBasis::Fitness => self.breed::<GAFitnessScoreSelection>(n_replace)?,
+        };
+
+        // Insert the children only once all of them are bred. A parent may
+        // have already been displaced by an earlier child; the replacement
+        // scheme sees whoever occupies its position now.
+        for (child, parents) in children
+        {
+            if self.population.swap_individual_with(child, &parents, &mut self.replacement, &mut self.rng_ctx).is_some()
+            {
+                self.statistics.count_replacements(1);
+            }
+        }
+        // Raw scores changed: derive fitness again with the population's
+        // scaling scheme, if any.
+        self.population.scale();
+        self.population.sort();
+        self.statistics.update(&mut self.population);
+
+        self.current_generation += 1;
+        Ok(self.current_generation)
+    }
+
+    fn done_internal(&mut self) -> bool
+    {
+        let evaluations = self.statistics.num_evaluations();
+        let done = self.terminator.done(&GATerminationCtx::new(self.current_generation, evaluations, &mut self.population));
+        if done
+        {
+            self.statistics.flush_scores_logged();
+        }
+        done
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_replacement::*;
+    use ::ga::ga_selectors::*;
+    use ::ga::ga_scaling::{GALinearRankScaling, GAScalingScheme};
+    use super::*;
+
+    fn test_cfg() -> SteadyStateGeneticAlgorithmCfg
+    {
+        SteadyStateGeneticAlgorithmCfg {
+            d_seed : [1; 4],
+            flags : DEBUG_FLAG,
+            max_generations: 10,
+            population_size: 8,
+            probability_crossover: 0.9,
+            probability_mutation: 0.1,
+            replacement_percentage: 0.25,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn init_test_with_factory()
+    {
+        ga_test_setup("ga_steady_state::init_test_with_factory");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SteadyStateGeneticAlgorithm::new(test_cfg(),
+                                                      Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                      None,
+                                                      GAUniformSelector::new(),
+                                                      GAReplaceWorst);
+        assert_eq!(ga.replacement_count(), 2);
+
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            assert_eq!(ga.population().size(), 8);
+        }
+        // 8 on initialization, 2 every generation.
+        assert_eq!(ga.statistics().num_evaluations(), 28);
+        assert_eq!(ga.step(), Ok(11));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn replacement_number_overrides_percentage()
+    {
+        ga_test_setup("ga_steady_state::replacement_number_overrides_percentage");
+        let initial_population = GAPopulation::new(vec![GATestIndividual::new(1.0),
+                                                        GATestIndividual::new(2.0),
+                                                        GATestIndividual::new(3.0)],
+                                                   GAPopulationSortOrder::HighIsBest);
+        let ga = SteadyStateGeneticAlgorithm::new(SteadyStateGeneticAlgorithmCfg {
+                                                      replacement_number: 5,
+                                                      ..test_cfg()
+                                                  },
+                                                  None,
+                                                  Some(initial_population),
+                                                  GARankSelector::new(),
+                                                  GAReplaceRandom);
+        // Clamped to the size of the population.
+        assert_eq!(ga.replacement_count(), 3);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn same_seed_same_run()
+    {
+        ga_test_setup("ga_steady_state::same_seed_same_run");
+        let run = ||
+        {
+            let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+            let mut ga = SteadyStateGeneticAlgorithm::new(test_cfg(),
+                                                          Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                          None,
+                                                          GARouletteWheelSelector::new(8),
+                                                          GAReplaceParent);
+            ga.initialize().unwrap();
+            while !ga.done()
+            {
+                ga.step().unwrap();
+            }
+            let scores : Vec<f32> = ga.population().raw_score_iterator().unwrap().map(|ind| ind.raw()).collect();
+            scores
+        };
+
+        assert_eq!(run(), run());
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn replace_worst_never_gets_worse()
+    {
+        ga_test_setup("ga_steady_state::replace_worst_never_gets_worse");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SteadyStateGeneticAlgorithm::new(test_cfg(),
+                                                      Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                      None,
+                                                      GARankSelector::new(),
+                                                      GAReplaceWorst);
+        ga.initialize().unwrap();
+        let mut worst = ga.population().worst_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            let new_worst = ga.population().worst_by_raw_score().raw();
+            assert!(new_worst >= worst);
+            worst = new_worst;
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn children_are_scaled()
+    {
+        ga_test_setup("ga_steady_state::children_are_scaled");
+        let mut population = GAPopulation::new((1..6).map(|rs| GATestIndividual::new(rs as f32)).collect(),
+                                               GAPopulationSortOrder::HighIsBest);
+        population.set_scaling(Box::new(GALinearRankScaling::new(2.0)));
+        let mut ga = SteadyStateGeneticAlgorithm::new(test_cfg(), None, Some(population),
+                                                      GARankSelector::new(),
+                                                      GAReplaceWorst);
+
+        // Fitness is the rank of the raw score, children included.
+        ga.initialize().unwrap();
+        for _ in 0..3
+        {
+            ga.step().unwrap();
+            let fitness : Vec<f32> = ga.population().fitness_score_iterator().unwrap().map(|ind| ind.fitness()).collect();
+            assert_eq!(fitness, vec![2.0, 1.5, 1.0, 0.5, 0.0]);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn scaling_and_selection_basis_test()
+    {
+        ga_test_setup("ga_steady_state::scaling_and_selection_basis_test");
+        let mut factory = GATestFactory::new(GA_TEST_FITNESS_VAL);
+        let mut ga = SteadyStateGeneticAlgorithm::new(SteadyStateGeneticAlgorithmCfg {
+                                                          population_size: 5,
+                                                          population_sort_order: GAPopulationSortOrder::LowIsBest,
+                                                          selection_basis: GAPopulationSortBasis::Fitness,
+                                                          scaling: GAScalingScheme::LinearRank { pressure: 2.0 },
+                                                          ..test_cfg()
+                                                      },
+                                                      Some(&mut factory as &mut dyn GAFactory<GATestIndividual>),
+                                                      None,
+                                                      GARouletteWheelSelector::new(5),
+                                                      GAReplaceWorst);
+
+        // LowIsBest fitness: the best individual ranks 0, the worst 2.
+        ga.initialize().unwrap();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+            let fitness : Vec<f32> = ga.population().fitness_score_iterator().unwrap().map(|ind| ind.fitness()).collect();
+            assert_eq!(fitness, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+            assert_eq!(ga.population().best_by_fitness_score().raw(),
+                       ga.population().best_by_raw_score().raw());
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    #[should_panic]
+    #[allow(unused_variables)]
+    fn init_test_missing_args()
+    {
+        ga_test_setup("ga_steady_state::init_test_missing_args");
+        let ga : SteadyStateGeneticAlgorithm<GATestIndividual, GAUniformSelector, GAReplaceWorst> =
+                 SteadyStateGeneticAlgorithm::new(test_cfg(), None, None, GAUniformSelector::new(), GAReplaceWorst);
+        // Not reached
+        ga_test_teardown();
+    }
+}
@@ -0,0 +1,498 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under an MIT License.
+
+//! GA Termination Criteria
+//!
+//! A terminator decides when a genetic algorithm is done. Genetic
+//! algorithms query their terminator on every call to
+//! `GeneticAlgorithm::done`; by default, they are done after
+//! `max_generations` generations (`GAGenerationTerminator`).
+//!
+//! Available terminators:
+//!
+//! `GAGenerationTerminator`
+//! `GABestConvergenceTerminator`
+//! `GAScoreRatioTerminator`
+//! `GAWallClockTerminator`
+//! `GAEvaluationTerminator`
+//! `GATargetScoreTerminator`
+//! `GAAnyTerminator`
+//! `GAAllTerminator`
+//! `GANotTerminator`
+use ::ga::ga_core::GAIndividual;
+use ::ga::ga_population::{GAPopulation, GAPopulationStats, GAPopulationSortOrder};
+
+use std::f32;
+use std::time::{Duration, Instant};
+
+/// State of a genetic algorithm run, as seen by its terminator.
+pub struct GATerminationCtx<'a, T: GAIndividual + 'a>
+{
+    /// Generations completed since initialization.
+    pub generation: i32,
+    /// Individuals evaluated since initialization.
+    pub evaluations: usize,
+    pub population: &'a GAPopulation<T>,
+    /// Statistics of `population`. `None` if it is empty.
+    pub statistics: Option<GAPopulationStats>,
+}
+impl<'a, T: GAIndividual> GATerminationCtx<'a, T>
+{
+    pub fn new(generation: i32, evaluations: usize, population: &'a mut GAPopulation<T>) -> GATerminationCtx<'a, T>
+    {
+        let statistics = population.statistics();
+        GATerminationCtx { generation, evaluations, population, statistics }
+    }
+
+    /// Best raw score of the population, according to its order.
+    pub fn best_raw(&self) -> Option<f32>
+    {
+        let order = self.population.order();
+        self.statistics.as_ref().map(|stats|
+        {
+            match order
+            {
+                GAPopulationSortOrder::HighIsBest => stats.raw_max,
+                GAPopulationSortOrder::LowIsBest  => stats.raw_min,
+            }
+        })
+    }
+
+    /// Average raw score of the population.
+    pub fn avg_raw(&self) -> Option<f32>
+    {
+        self.statistics.as_ref().map(|stats| stats.raw_avg)
+    }
+}
+
+/// Termination criterion trait.
+pub trait GATerminator<T: GAIndividual>
+{
+    /// Is the run done?
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool;
+
+    /// Forget any state kept from a previous run. Called when the genetic
+    /// algorithm is initialized.
+    fn reset(&mut self) {}
+}
+
+/// Done after a number of generations. GALib's TerminateUponGeneration.
+pub struct GAGenerationTerminator
+{
+    max_generations: i32,
+}
+impl GAGenerationTerminator
+{
+    pub fn new(max_generations: i32) -> GAGenerationTerminator
+    {
+        GAGenerationTerminator { max_generations }
+    }
+
+    pub fn max_generations(&self) -> i32
+    {
+        self.max_generations
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAGenerationTerminator
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        ctx.generation >= self.max_generations
+    }
+}
+
+/// Done when the best raw score hasn't changed by more than `tolerance`
+/// over the last `generations` generations.
+pub struct GABestConvergenceTerminator
+{
+    generations: usize,
+    tolerance: f32,
+    // Best raw score of the last generations queried, oldest first.
+    history: Vec<f32>,
+    last_generation: Option<i32>,
+}
+impl GABestConvergenceTerminator
+{
+    pub fn new(generations: usize, tolerance: f32) -> GABestConvergenceTerminator
+    {
+        assert!(generations > 0, "GABestConvergenceTerminator - generations must be greater than 0");
+        assert!(tolerance >= 0.0, "GABestConvergenceTerminator - tolerance can't be negative");
+        GABestConvergenceTerminator { generations, tolerance, history: vec![], last_generation: None }
+    }
+
+    pub fn generations(&self) -> usize
+    {
+        self.generations
+    }
+
+    pub fn tolerance(&self) -> f32
+    {
+        self.tolerance
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GABestConvergenceTerminator
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        // A generation is recorded once, however many times it is queried.
+        if self.last_generation != Some(ctx.generation)
+        {
+            if let Some(best) = ctx.best_raw()
+            {
+                self.history.push(best);
+                if self.history.len() > self.generations + 1
+                {
+                    self.history.remove(0);
+                }
+                self.last_generation = Some(ctx.generation);
+            }
+        }
+
+        if self.history.len() <= self.generations
+        {
+            return false;
+        }
+
+        let max = self.history.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let min = self.history.iter().cloned().fold(f32::INFINITY, f32::min);
+        max - min <= self.tolerance
+    }
+
+    fn reset(&mut self)
+    {
+        self.history.clear();
+        self.last_generation = None;
+    }
+}
+
+/// Done when the average raw score is within `ratio` of the best one.
+/// GALib's TerminateUponPopConvergence.
+///
+/// The convergence ratio is the smallest of the average and best raw
+/// scores over the largest (in absolute value), in [0, 1]; 1.0 when all the
+/// individuals score the same.
+pub struct GAScoreRatioTerminator
+{
+    ratio: f32,
+}
+impl GAScoreRatioTerminator
+{
+    pub fn new(ratio: f32) -> GAScoreRatioTerminator
+    {
+        GAScoreRatioTerminator { ratio }
+    }
+
+    pub fn ratio(&self) -> f32
+    {
+        self.ratio
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAScoreRatioTerminator
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        match (ctx.best_raw(), ctx.avg_raw())
+        {
+            (Some(best), Some(avg)) =>
+            {
+                let (best, avg) = (best.abs(), avg.abs());
+                let ratio = if best == avg { 1.0 } else { best.min(avg) / best.max(avg) };
+                ratio >= self.ratio
+            },
+            _ => false
+        }
+    }
+}
+
+/// Done after running for a while.
+///
+/// The clock starts when the genetic algorithm is initialized, or on the
+/// first query if it wasn't.
+pub struct GAWallClockTerminator
+{
+    budget: Duration,
+    started: Option<Instant>,
+}
+impl GAWallClockTerminator
+{
+    pub fn new(budget: Duration) -> GAWallClockTerminator
+    {
+        GAWallClockTerminator { budget, started: None }
+    }
+
+    pub fn budget(&self) -> Duration
+    {
+        self.budget
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAWallClockTerminator
+{
+    fn done(&mut self, _: &GATerminationCtx<T>) -> bool
+    {
+        self.started.get_or_insert_with(Instant::now).elapsed() >= self.budget
+    }
+
+    fn reset(&mut self)
+    {
+        self.started = Some(Instant::now());
+    }
+}
+
+/// Done after evaluating a number of individuals.
+pub struct GAEvaluationTerminator
+{
+    max_evaluations: usize,
+}
+impl GAEvaluationTerminator
+{
+    pub fn new(max_evaluations: usize) -> GAEvaluationTerminator
+    {
+        GAEvaluationTerminator { max_evaluations }
+    }
+
+    pub fn max_evaluations(&self) -> usize
+    {
+        self.max_evaluations
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAEvaluationTerminator
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        ctx.evaluations >= self.max_evaluations
+    }
+}
+
+/// Done when the best raw score reaches `target`: at least `target` when
+/// high is best, at most `target` when low is best.
+pub struct GATargetScoreTerminator
+{
+    target: f32,
+}
+impl GATargetScoreTerminator
+{
+    pub fn new(target: f32) -> GATargetScoreTerminator
+    {
+        GATargetScoreTerminator { target }
+    }
+
+    pub fn target(&self) -> f32
+    {
+        self.target
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GATargetScoreTerminator
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        match (ctx.best_raw(), ctx.population.order())
+        {
+            (Some(best), GAPopulationSortOrder::HighIsBest) => best >= self.target,
+            (Some(best), GAPopulationSortOrder::LowIsBest)  => best <= self.target,
+            (None, _) => false
+        }
+    }
+}
+
+/// Done when any of its terminators is.
+///
+/// All of them are queried every time, so that the ones that track the run
+/// (e.g. `GABestConvergenceTerminator`) see every generation.
+pub struct GAAnyTerminator<T: GAIndividual>
+{
+    terminators: Vec<Box<dyn GATerminator<T>>>,
+}
+impl<T: GAIndividual> GAAnyTerminator<T>
+{
+    pub fn new(terminators: Vec<Box<dyn GATerminator<T>>>) -> GAAnyTerminator<T>
+    {
+        GAAnyTerminator { terminators }
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAAnyTerminator<T>
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        let mut done = false;
+        for t in &mut self.terminators
+        {
+            done = t.done(ctx) || done;
+        }
+        done
+    }
+
+    fn reset(&mut self)
+    {
+        for t in &mut self.terminators
+        {
+            t.reset();
+        }
+    }
+}
+
+/// Done when all of its terminators are.
+///
+/// All of them are queried every time (see `GAAnyTerminator`).
+pub struct GAAllTerminator<T: GAIndividual>
+{
+    terminators: Vec<Box<dyn GATerminator<T>>>,
+}
+impl<T: GAIndividual> GAAllTerminator<T>
+{
+    pub fn new(terminators: Vec<Box<dyn GATerminator<T>>>) -> GAAllTerminator<T>
+    {
+        GAAllTerminator { terminators }
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GAAllTerminator<T>
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        let mut done = true;
+        for t in &mut self.terminators
+        {
+            done = t.done(ctx) && done;
+        }
+        done
+    }
+
+    fn reset(&mut self)
+    {
+        for t in &mut self.terminators
+        {
+            t.reset();
+        }
+    }
+}
+
+/// Done when its terminator isn't.
+pub struct GANotTerminator<T: GAIndividual>
+{
+    terminator: Box<dyn GATerminator<T>>,
+}
+impl<T: GAIndividual> GANotTerminator<T>
+{
+    pub fn new(terminator: Box<dyn GATerminator<T>>) -> GANotTerminator<T>
+    {
+        GANotTerminator { terminator }
+    }
+}
+impl<T: GAIndividual> GATerminator<T> for GANotTerminator<T>
+{
+    fn done(&mut self, ctx: &GATerminationCtx<T>) -> bool
+    {
+        !self.terminator.done(ctx)
+    }
+
+    fn reset(&mut self)
+    {
+        self.terminator.reset();
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_population::*;
+    use super::*;
+
+    use std::thread;
+
+    fn population(raws: &[f32], order: GAPopulationSortOrder) -> GAPopulation<GATestIndividual>
+    {
+        GAPopulation::new(raws.iter().map(|rs| GATestIndividual::new(*rs)).collect(), order)
+    }
+
+    #[test]
+    fn test_budget_terminators()
+    {
+        ga_test_setup("ga_terminators::test_budget_terminators");
+        let mut pop = population(&[1.0, 2.0], GAPopulationSortOrder::HighIsBest);
+
+        let mut generations = GAGenerationTerminator::new(3);
+        assert!(!generations.done(&GATerminationCtx::new(2, 0, &mut pop)));
+        assert!(generations.done(&GATerminationCtx::new(3, 0, &mut pop)));
+
+        let mut evaluations = GAEvaluationTerminator::new(10);
+        assert!(!evaluations.done(&GATerminationCtx::new(0, 9, &mut pop)));
+        assert!(evaluations.done(&GATerminationCtx::new(0, 10, &mut pop)));
+
+        let mut clock = GAWallClockTerminator::new(Duration::from_millis(20));
+        GATerminator::<GATestIndividual>::reset(&mut clock);
+        assert!(!clock.done(&GATerminationCtx::new(0, 0, &mut pop)));
+        thread::sleep(Duration::from_millis(25));
+        assert!(clock.done(&GATerminationCtx::new(0, 0, &mut pop)));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_score_terminators()
+    {
+        ga_test_setup("ga_terminators::test_score_terminators");
+        let mut high = population(&[1.0, 2.0, 3.0], GAPopulationSortOrder::HighIsBest);
+        let mut low = population(&[1.0, 2.0, 3.0], GAPopulationSortOrder::LowIsBest);
+
+        let mut target = GATargetScoreTerminator::new(2.0);
+        assert!(target.done(&GATerminationCtx::new(0, 0, &mut high)));
+        assert!(target.done(&GATerminationCtx::new(0, 0, &mut low)));
+        let mut target = GATargetScoreTerminator::new(0.5);
+        assert!(target.done(&GATerminationCtx::new(0, 0, &mut high)));
+        assert!(!target.done(&GATerminationCtx::new(0, 0, &mut low)));
+
+        // Average 2, best 3 (high is best) or 1 (low is best).
+        let mut ratio = GAScoreRatioTerminator::new(0.6);
+        assert!(ratio.done(&GATerminationCtx::new(0, 0, &mut high)));
+        assert!(!ratio.done(&GATerminationCtx::new(0, 0, &mut low)));
+
+        let mut empty = population(&[], GAPopulationSortOrder::HighIsBest);
+        assert!(!ratio.done(&GATerminationCtx::new(0, 0, &mut empty)));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_best_convergence_terminator()
+    {
+        ga_test_setup("ga_terminators::test_best_convergence_terminator");
+        let mut convergence = GABestConvergenceTerminator::new(2, 0.15);
+        let bests = [1.0, 2.0, 2.05, 2.1, 2.1, 3.0];
+        let done : Vec<bool> = bests.iter().enumerate().map(|(gen, best)|
+        {
+            let mut pop = population(&[0.5, *best], GAPopulationSortOrder::HighIsBest);
+            // Querying a generation twice doesn't record it twice.
+            convergence.done(&GATerminationCtx::new(gen as i32, 0, &mut pop));
+            convergence.done(&GATerminationCtx::new(gen as i32, 0, &mut pop))
+        }).collect();
+        assert_eq!(done, vec![false, false, false, true, true, false]);
+
+        GATerminator::<GATestIndividual>::reset(&mut convergence);
+        let mut pop = population(&[3.0], GAPopulationSortOrder::HighIsBest);
+        assert!(!convergence.done(&GATerminationCtx::new(0, 0, &mut pop)));
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn test_combined_terminators()
+    {
+        ga_test_setup("ga_terminators::test_combined_terminators");
+        let mut pop = population(&[1.0, 2.0], GAPopulationSortOrder::HighIsBest);
+        let terminators = || -> Vec<Box<dyn GATerminator<GATestIndividual>>>
+        {
+            vec![Box::new(GAGenerationTerminator::new(5)), Box::new(GAEvaluationTerminator::new(100))]
+        };
+
+        let mut any = GAAnyTerminator::new(terminators());
+        let mut all = GAAllTerminator::new(terminators());
+        assert!(any.done(&GATerminationCtx::new(5, 0, &mut pop)));
+        assert!(!all.done(&GATerminationCtx::new(5, 0, &mut pop)));
+        assert!(all.done(&GATerminationCtx::new(5, 100, &mut pop)));
+
+        let mut not = GANotTerminator::new(Box::new(any));
+        assert!(!not.done(&GATerminationCtx::new(0, 100, &mut pop)));
+        assert!(not.done(&GATerminationCtx::new(0, 0, &mut pop)));
+        ga_test_teardown();
+    }
+}
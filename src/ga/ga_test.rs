@@ -9,7 +9,8 @@ use ::ga::ga_core::*;
 use ::ga::ga_population::*;
 use ::ga::ga_random::*;
 
-use std::any::Any;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 #[cfg(test)]
 extern crate env_logger;
@@ -48,6 +49,7 @@ pub fn ga_test_teardown(){}
 /// GATestIndividual
 /// Implements the GAIndividual Trait with only no-ops
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GATestIndividual
 {
     raw: f32,
@@ -62,16 +64,23 @@ impl GATestIndividual
 }
 impl GAIndividual for GATestIndividual 
 {
-    fn crossover(&self, _: &GATestIndividual, _: &mut Any) -> Box<GATestIndividual>
+    type EvaluationCtx = ();
+
+    fn crossover(&self, _: &GATestIndividual, _: &mut GARandomCtx) -> Box<GATestIndividual>
     { 
         Box::new(GATestIndividual::new(self.raw))
     }
-    fn mutate(&mut self, _: f32, _: &mut Any) {}
-    fn evaluate(&mut self, _: &mut Any) { /* TODO: Maybe use the context to set the fitness */}
+    fn mutate(&mut self, _: f32, _: &mut GARandomCtx) -> usize { 0 }
+    fn evaluate(&mut self, _: &mut ()) { /* TODO: Maybe use the context to set the fitness */}
     fn fitness(&self) -> f32 { self.fitness }
     fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
     fn raw(&self) -> f32 { self.raw }
     fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+    fn compare(&self, other: &GATestIndividual) -> f32 { (self.raw - other.raw).abs().min(1.0) }
+}
+impl GAParallelIndividual for GATestIndividual
+{
+    fn evaluate_shared(&mut self, _: &()) {}
 }
 
 pub struct GATestFactory
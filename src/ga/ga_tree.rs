@@ -0,0 +1,742 @@
+// Copyright 2016 Revolution Solid & Contributors.
+// author(s): sysnett
+// rust-monster is licensed under a MIT License.
+
+//! Tree Genome
+//!
+//! GALib's GATreeGenome, for genetic programming: a tree of nodes taken
+//! from a user defined set of functions (nodes with children) and
+//! terminals (leaves). See `GATreeNode`.
+//!
+//! Trees are stored in prefix order, so every subtree is a contiguous run
+//! of nodes.
+use ::ga::ga_core::{GAFactory, GAIndividual, GAParallelIndividual};
+use ::ga::ga_population::{GAPopulation, GAPopulationSortOrder};
+use ::ga::ga_random::GARandomCtx;
+
+use std::fmt;
+use std::sync::Arc;
+
+// Attempts at finding a crossover point that honors the depth limit before
+// giving up and copying the first parent.
+const CROSSOVER_TRIES: usize = 10;
+
+/// Tree Node
+///
+/// The function and terminal sets of a tree genome. Nodes are compared with
+/// `==` to measure the distance between genomes (see
+/// `GAIndividual::compare`).
+pub trait GATreeNode: Clone + PartialEq
+{
+    /// Number of children; 0 for terminals.
+    fn arity(&self) -> usize;
+
+    /// Random node of the terminal set.
+    fn random_terminal(rng_ctx: &mut GARandomCtx) -> Self;
+
+    /// Random node of the function set.
+    fn random_function(rng_ctx: &mut GARandomCtx) -> Self;
+
+    /// Random node with `arity` children, used by point mutation. `None`
+    /// when there are no other such nodes.
+    ///
+    /// By default, draws a few random nodes and returns the first one with
+    /// the right arity.
+    fn random_with_arity(arity: usize, rng_ctx: &mut GARandomCtx) -> Option<Self>
+    {
+        if arity == 0
+        {
+            return Some(Self::random_terminal(rng_ctx));
+        }
+
+        (0..16).map(|_| Self::random_function(rng_ctx)).find(|n| n.arity() == arity)
+    }
+}
+
+/// Objective function of a Tree Genome. Its result is the raw score (and
+/// the fitness) of the genome.
+pub type GATreeObjective<N> = Arc<dyn Fn(&GATreeGenome<N>) -> f32 + Send + Sync>;
+
+/// Tree Mutation
+///
+/// Mutation operator used by `GAIndividual::mutate`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum GATreeMutation
+{
+    /// With probability `p_mutation`, a random subtree is replaced by a
+    /// newly grown one.
+    #[default]
+    Subtree,
+    /// Each node is replaced, with probability `p_mutation`, by a random
+    /// node with the same arity.
+    Point,
+    /// With probability `p_mutation`, a random subtree becomes the whole
+    /// tree.
+    Hoist,
+}
+
+/// Tree Node Reference
+///
+/// A node of a tree genome, to walk the tree (when evaluating it, for
+/// instance).
+pub struct GATreeRef<'a, N: 'a + GATreeNode>
+{
+    nodes: &'a [N],
+    index: usize,
+}
+impl<'a, N: GATreeNode> Clone for GATreeRef<'a, N>
+{
+    fn clone(&self) -> GATreeRef<'a, N>
+    {
+        *self
+    }
+}
+impl<'a, N: GATreeNode> Copy for GATreeRef<'a, N> {}
+impl<'a, N: GATreeNode> GATreeRef<'a, N>
+{
+    pub fn node(&self) -> &'a N
+    {
+        &self.nodes[self.index]
+    }
+
+    /// Index of the node, in prefix order.
+    pub fn index(&self) -> usize
+    {
+        self.index
+    }
+
+    pub fn children(&self) -> Vec<GATreeRef<'a, N>>
+    {
+        let mut children = vec![];
+        let mut i = self.index + 1;
+        for _ in 0..self.node().arity()
+        {
+            children.push(GATreeRef { nodes: self.nodes, index: i });
+            i = subtree_end(self.nodes, i);
+        }
+        children
+    }
+
+    pub fn child(&self, i: usize) -> GATreeRef<'a, N>
+    {
+        self.children()[i]
+    }
+}
+
+/// Tree Genome
+///
+/// Trees never get deeper than their depth limit. The depth of a tree is
+/// that of its deepest node; the root is at depth 0.
+#[derive(Clone)]
+pub struct GATreeGenome<N: GATreeNode>
+{
+    nodes: Vec<N>,
+    max_depth: usize,
+    mutation: GATreeMutation,
+    objective: GATreeObjective<N>,
+    raw: f32,
+    fitness: f32,
+}
+impl<N: GATreeNode> GATreeGenome<N>
+{
+    /// Genome with the tree given by `nodes`, in prefix order.
+    pub fn new(nodes: Vec<N>, max_depth: usize, objective: GATreeObjective<N>) -> GATreeGenome<N>
+    {
+        assert!(!nodes.is_empty() && subtree_end(&nodes, 0) == nodes.len(),
+                "Tree Genome - nodes don't form a tree");
+        let genome = GATreeGenome
+        {
+            nodes,
+            max_depth,
+            mutation: GATreeMutation::default(),
+            objective,
+            raw: 0.0,
+            fitness: 0.0,
+        };
+        assert!(genome.depth() <= max_depth, "Tree Genome - tree deeper than its depth limit");
+        genome
+    }
+
+    /// Random tree whose terminals are all at depth `depth` ('full' method).
+    pub fn full(depth: usize, max_depth: usize, objective: GATreeObjective<N>, rng_ctx: &mut GARandomCtx) -> GATreeGenome<N>
+    {
+        let mut nodes = vec![];
+        random_subtree(&mut nodes, depth, true, rng_ctx);
+        GATreeGenome::new(nodes, max_depth, objective)
+    }
+
+    /// Random tree of depth `depth` at most ('grow' method).
+    pub fn grow(depth: usize, max_depth: usize, objective: GATreeObjective<N>, rng_ctx: &mut GARandomCtx) -> GATreeGenome<N>
+    {
+        let mut nodes = vec![];
+        random_subtree(&mut nodes, depth, false, rng_ctx);
+        GATreeGenome::new(nodes, max_depth, objective)
+    }
+
+    /// Nodes in prefix order.
+    pub fn nodes(&self) -> &[N]
+    {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> GATreeRef<'_, N>
+    {
+        GATreeRef { nodes: &self.nodes, index: 0 }
+    }
+
+    /// Number of nodes.
+    pub fn size(&self) -> usize
+    {
+        self.nodes.len()
+    }
+
+    pub fn depth(&self) -> usize
+    {
+        node_depths(&self.nodes).into_iter().max().unwrap_or(0)
+    }
+
+    pub fn max_depth(&self) -> usize
+    {
+        self.max_depth
+    }
+
+    pub fn mutation_operator(&self) -> GATreeMutation
+    {
+        self.mutation
+    }
+
+    pub fn set_mutation_operator(&mut self, mutation: GATreeMutation)
+    {
+        self.mutation = mutation;
+    }
+
+    /// Subtree crossover: a random subtree of `self` is replaced by a
+    /// random subtree of `other`. If no replacement within the depth limit
+    /// is found, the child is a copy of `self`.
+    pub fn subtree_crossover(&self, other: &GATreeGenome<N>, rng_ctx: &mut GARandomCtx) -> GATreeGenome<N>
+    {
+        let self_depths = node_depths(&self.nodes);
+        let other_depths = node_depths(&other.nodes);
+
+        for _ in 0..CROSSOVER_TRIES
+        {
+            let i = rng_ctx.gen_range(0, self.nodes.len());
+            let j = rng_ctx.gen_range(0, other.nodes.len());
+            let i_end = subtree_end(&self.nodes, i);
+            let j_end = subtree_end(&other.nodes, j);
+
+            let height = other_depths[j..j_end].iter().max().unwrap() - other_depths[j];
+            if self_depths[i] + height > self.max_depth
+            {
+                continue;
+            }
+
+            let mut nodes = Vec::with_capacity(self.nodes.len() - (i_end - i) + (j_end - j));
+            nodes.extend_from_slice(&self.nodes[..i]);
+            nodes.extend_from_slice(&other.nodes[j..j_end]);
+            nodes.extend_from_slice(&self.nodes[i_end..]);
+            return self.child(nodes);
+        }
+
+        self.child(self.nodes.clone())
+    }
+
+    // Genome with 'nodes' and the settings of self.
+    fn child(&self, nodes: Vec<N>) -> GATreeGenome<N>
+    {
+        GATreeGenome
+        {
+            nodes,
+            max_depth: self.max_depth,
+            mutation: self.mutation,
+            objective: self.objective.clone(),
+            raw: 0.0,
+            fitness: 0.0,
+        }
+    }
+}
+impl<N: GATreeNode> GAIndividual for GATreeGenome<N>
+{
+    type EvaluationCtx = ();
+
+    fn crossover(&self, other: &GATreeGenome<N>, rng_ctx: &mut GARandomCtx) -> Box<GATreeGenome<N>>
+    {
+        Box::new(self.subtree_crossover(other, rng_ctx))
+    }
+
+    fn mutate(&mut self, p_mutation: f32, rng_ctx: &mut GARandomCtx) -> usize
+    {
+        if p_mutation <= 0.0
+        {
+            return 0;
+        }
+
+        let mut mutations = 0;
+        match self.mutation
+        {
+            GATreeMutation::Subtree =>
+            {
+                if rng_ctx.test_value(p_mutation)
+                {
+                    let i = rng_ctx.gen_range(0, self.nodes.len());
+                    let depth = node_depths(&self.nodes)[i];
+                    let mut subtree = vec![];
+                    random_subtree(&mut subtree, self.max_depth - depth, false, rng_ctx);
+                    let end = subtree_end(&self.nodes, i);
+                    self.nodes.splice(i..end, subtree);
+                    mutations += 1;
+                }
+            },
+            GATreeMutation::Point =>
+            {
+                for n in &mut self.nodes
+                {
+                    if rng_ctx.test_value(p_mutation)
+                    {
+                        if let Some(new) = N::random_with_arity(n.arity(), rng_ctx)
+                        {
+                            *n = new;
+                            mutations += 1;
+                        }
+                    }
+                }
+            },
+            GATreeMutation::Hoist =>
+            {
+                if rng_ctx.test_value(p_mutation)
+                {
+                    let i = rng_ctx.gen_range(0, self.nodes.len());
+                    let end = subtree_end(&self.nodes, i);
+                    self.nodes.truncate(end);
+                    self.nodes.drain(..i);
+                    mutations += 1;
+                }
+            },
+        }
+        mutations
+    }
+
+    fn evaluate(&mut self, _: &mut ())
+    {
+        let score = (self.objective)(self);
+        self.raw = score;
+        self.fitness = score;
+    }
+
+    fn fitness(&self) -> f32 { self.fitness }
+    fn set_fitness(&mut self, fitness: f32) { self.fitness = fitness; }
+    fn raw(&self) -> f32 { self.raw }
+    fn set_raw(&mut self, raw: f32) { self.raw = raw; }
+
+    /// Fraction of the nodes of both trees that don't match when the trees
+    /// are overlaid from their roots. A node matches if it is equal to the
+    /// one in the same place of the other tree and so are its ancestors.
+    fn compare(&self, other: &GATreeGenome<N>) -> f32
+    {
+        let unmatched = unmatched_nodes(&self.nodes, 0, &other.nodes, 0);
+        unmatched as f32 / (self.nodes.len() + other.nodes.len()) as f32
+    }
+}
+impl<N: GATreeNode + Send> GAParallelIndividual for GATreeGenome<N>
+{
+    fn evaluate_shared(&mut self, _: &())
+    {
+        self.evaluate(&mut ());
+    }
+}
+impl<N: GATreeNode> PartialEq for GATreeGenome<N>
+{
+    fn eq(&self, other: &GATreeGenome<N>) -> bool
+    {
+        self.nodes == other.nodes
+    }
+}
+impl<N: GATreeNode + fmt::Debug> fmt::Debug for GATreeGenome<N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "GATreeGenome({:?}) raw: {} fitness: {}", self.nodes, self.raw, self.fitness)
+    }
+}
+
+/// Tree Genome Factory
+///
+/// Creates populations with ramped half-and-half: depths are spread evenly
+/// over [`min_depth`, `max_init_depth`], and at each depth half the trees
+/// are built with the 'full' method and half with the 'grow' method.
+pub struct GATreeFactory<N: GATreeNode>
+{
+    min_depth: usize,
+    max_init_depth: usize,
+    max_depth: usize,
+    objective: GATreeObjective<N>,
+    mutation: GATreeMutation,
+}
+impl<N: GATreeNode> GATreeFactory<N>
+{
+    /// Factory of trees between `min_depth` and `max_init_depth` deep,
+    /// limited to `max_depth` afterwards.
+    pub fn new<F>(min_depth: usize, max_init_depth: usize, max_depth: usize, objective: F) -> GATreeFactory<N>
+        where F: Fn(&GATreeGenome<N>) -> f32 + Send + Sync + 'static
+    {
+        assert!(min_depth <= max_init_depth && max_init_depth <= max_depth);
+        GATreeFactory
+        {
+            min_depth,
+            max_init_depth,
+            max_depth,
+            objective: Arc::new(objective),
+            mutation: GATreeMutation::default(),
+        }
+    }
+
+    /// Mutation operator of the genomes created from now on.
+    pub fn set_mutation_operator(&mut self, mutation: GATreeMutation)
+    {
+        self.mutation = mutation;
+    }
+}
+impl<N: GATreeNode> GAFactory<GATreeGenome<N>> for GATreeFactory<N>
+{
+    fn random_population(&mut self, n: usize, sort_order: GAPopulationSortOrder, rng_ctx: &mut GARandomCtx) -> GAPopulation<GATreeGenome<N>>
+    {
+        let depths = self.max_init_depth - self.min_depth + 1;
+        let mut genomes = vec![];
+        for i in 0..n
+        {
+            let depth = self.min_depth + (i / 2) % depths;
+            let mut genome = if i % 2 == 0
+            {
+                GATreeGenome::full(depth, self.max_depth, self.objective.clone(), rng_ctx)
+            }
+            else
+            {
+                GATreeGenome::grow(depth, self.max_depth, self.objective.clone(), rng_ctx)
+            };
+            genome.set_mutation_operator(self.mutation);
+            genomes.push(genome);
+        }
+
+        GAPopulation::new(genomes, sort_order)
+    }
+}
+
+// Nodes of the subtrees at 'i' of 'a' and at 'j' of 'b' that don't match
+// (see GATreeGenome::compare).
+fn unmatched_nodes<N: GATreeNode>(a: &[N], i: usize, b: &[N], j: usize) -> usize
+{
+    if a[i] != b[j] || a[i].arity() != b[j].arity()
+    {
+        return (subtree_end(a, i) - i) + (subtree_end(b, j) - j);
+    }
+
+    let mut unmatched = 0;
+    let (mut child_a, mut child_b) = (i+1, j+1);
+    for _ in 0..a[i].arity()
+    {
+        unmatched += unmatched_nodes(a, child_a, b, child_b);
+        child_a = subtree_end(a, child_a);
+        child_b = subtree_end(b, child_b);
+    }
+    unmatched
+}
+
+// One past the last node of the subtree rooted at 'i'.
+fn subtree_end<N: GATreeNode>(nodes: &[N], i: usize) -> usize
+{
+    let mut pending = 1;
+    let mut j = i;
+    while pending > 0
+    {
+        if j == nodes.len()
+        {
+            // Malformed tree; callers check against the length.
+            return usize::MAX;
+        }
+        pending = pending + nodes[j].arity() - 1;
+        j += 1;
+    }
+    j
+}
+
+// Depth of each node.
+fn node_depths<N: GATreeNode>(nodes: &[N]) -> Vec<usize>
+{
+    let mut depths = Vec::with_capacity(nodes.len());
+    // (depth, children still to visit) of the ancestors of the next node.
+    let mut ancestors : Vec<(usize, usize)> = vec![];
+    for n in nodes
+    {
+        let depth = ancestors.last().map_or(0, |&(d, _)| d + 1);
+        depths.push(depth);
+        if let Some(parent) = ancestors.last_mut()
+        {
+            parent.1 -= 1;
+        }
+
+        if n.arity() > 0
+        {
+            ancestors.push((depth, n.arity()));
+        }
+        else
+        {
+            while ancestors.last().is_some_and(|&(_, left)| left == 0)
+            {
+                ancestors.pop();
+            }
+        }
+    }
+    depths
+}
+
+// Append a random subtree, 'depth' deep at most, in prefix order. With
+// 'full', all terminals are exactly 'depth' deep.
+fn random_subtree<N: GATreeNode>(nodes: &mut Vec<N>, depth: usize, full: bool, rng_ctx: &mut GARandomCtx)
+{
+    let node = if depth == 0 || (!full && rng_ctx.test_value(0.5f32))
+    {
+        N::random_terminal(rng_ctx)
+    }
+    else
+    {
+        N::random_function(rng_ctx)
+    };
+
+    let arity = node.arity();
+    nodes.push(node);
+    for _ in 0..arity
+    {
+        random_subtree(nodes, depth - 1, full, rng_ctx);
+    }
+}
+
+////////////////////////////////////////
+// Tests
+#[cfg(test)]
+mod tests
+{
+    use ::ga::ga_test::*;
+    use ::ga::ga_core::*;
+    use ::ga::ga_population::*;
+    use ::ga::ga_random::*;
+    use ::ga::ga_simple::*;
+    use super::*;
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum Expr
+    {
+        Add,
+        Mul,
+        Neg,
+        X,
+        One,
+    }
+    impl GATreeNode for Expr
+    {
+        fn arity(&self) -> usize
+        {
+            match *self
+            {
+                Expr::Add | Expr::Mul => 2,
+                Expr::Neg => 1,
+                Expr::X | Expr::One => 0,
+            }
+        }
+
+        fn random_terminal(rng_ctx: &mut GARandomCtx) -> Expr
+        {
+            if rng_ctx.test_value(0.5f32) { Expr::X } else { Expr::One }
+        }
+
+        fn random_function(rng_ctx: &mut GARandomCtx) -> Expr
+        {
+            match rng_ctx.gen_range(0, 3)
+            {
+                0 => Expr::Add,
+                1 => Expr::Mul,
+                _ => Expr::Neg,
+            }
+        }
+    }
+
+    fn eval(t: GATreeRef<Expr>, x: f64) -> f64
+    {
+        match *t.node()
+        {
+            Expr::Add => eval(t.child(0), x) + eval(t.child(1), x),
+            Expr::Mul => eval(t.child(0), x) * eval(t.child(1), x),
+            Expr::Neg => -eval(t.child(0), x),
+            Expr::X => x,
+            Expr::One => 1.0,
+        }
+    }
+
+    // Closeness to x^2 + x + 1 over [-1, 1].
+    fn objective() -> GATreeObjective<Expr>
+    {
+        Arc::new(|g: &GATreeGenome<Expr>|
+        {
+            let error : f64 = (0..21).map(|i| -1.0 + 0.1 * i as f64)
+                                     .map(|x| (eval(g.root(), x) - (x * x + x + 1.0)).abs())
+                                     .sum();
+            (1.0 / (1.0 + error)) as f32
+        })
+    }
+
+    fn is_tree(g: &GATreeGenome<Expr>) -> bool
+    {
+        subtree_end(g.nodes(), 0) == g.size() && g.depth() <= g.max_depth()
+    }
+
+    #[test]
+    fn structure()
+    {
+        ga_test_setup("ga_tree::structure");
+        // (x * x) + -(1)
+        let g = GATreeGenome::new(vec![Expr::Add, Expr::Mul, Expr::X, Expr::X, Expr::Neg, Expr::One], 5, objective());
+        assert_eq!(g.size(), 6);
+        assert_eq!(g.depth(), 2);
+        assert_eq!(node_depths(g.nodes()), vec![0, 1, 2, 2, 1, 2]);
+        assert_eq!(g.root().children().iter().map(|c| c.index()).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(eval(g.root(), 3.0), 8.0);
+
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for depth in 0..5
+        {
+            let f = GATreeGenome::full(depth, 5, objective(), &mut rng_ctx);
+            assert_eq!(f.depth(), depth);
+            let leaves = f.nodes().iter().zip(node_depths(f.nodes())).filter(|&(n, _)| n.arity() == 0);
+            assert!(leaves.into_iter().all(|(_, d)| d == depth));
+
+            let g = GATreeGenome::grow(depth, 5, objective(), &mut rng_ctx);
+            assert!(is_tree(&g) && g.depth() <= depth);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    #[should_panic]
+    fn malformed_tree()
+    {
+        ga_test_setup("ga_tree::malformed_tree");
+        GATreeGenome::new(vec![Expr::Add, Expr::X], 5, objective());
+        // Not reached
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn crossover_depth_limit()
+    {
+        ga_test_setup("ga_tree::crossover_depth_limit");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for _ in 0..50
+        {
+            let mom = GATreeGenome::full(4, 4, objective(), &mut rng_ctx);
+            let dad = GATreeGenome::grow(4, 4, objective(), &mut rng_ctx);
+            let child = mom.crossover(&dad, &mut rng_ctx);
+            assert!(is_tree(&child));
+            assert_eq!(child.max_depth(), 4);
+        }
+
+        // A single terminal can only swap with the other parent's root.
+        let mom = GATreeGenome::new(vec![Expr::X], 2, objective());
+        let dad = GATreeGenome::new(vec![Expr::Neg, Expr::One], 2, objective());
+        let child = mom.crossover(&dad, &mut rng_ctx);
+        assert!(child.nodes() == dad.nodes() || child.nodes() == &[Expr::One]);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn mutation_operators()
+    {
+        ga_test_setup("ga_tree::mutation_operators");
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        for &mutation in &[GATreeMutation::Subtree, GATreeMutation::Point, GATreeMutation::Hoist]
+        {
+            let mut changed = false;
+            for _ in 0..20
+            {
+                let mut g = GATreeGenome::full(3, 4, objective(), &mut rng_ctx);
+                g.set_mutation_operator(mutation);
+                let before = g.clone();
+
+                g.mutate(0.0, &mut rng_ctx);
+                assert_eq!(g, before);
+
+                g.mutate(1.0, &mut rng_ctx);
+                assert!(is_tree(&g));
+                changed |= g != before;
+
+                if mutation == GATreeMutation::Point
+                {
+                    let arities = |t: &GATreeGenome<Expr>| t.nodes().iter().map(|n| n.arity()).collect::<Vec<_>>();
+                    assert_eq!(arities(&g), arities(&before));
+                }
+                if mutation == GATreeMutation::Hoist
+                {
+                    assert!(g.size() <= before.size());
+                }
+            }
+            assert!(changed);
+        }
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn symbolic_regression()
+    {
+        ga_test_setup("ga_tree::symbolic_regression");
+        let mut factory = GATreeFactory::new(1, 4, 6, |g: &GATreeGenome<Expr>| objective()(g));
+
+        let mut rng_ctx = GARandomCtx::from_seed([1; 4], String::from(""));
+        let pop = factory.random_population(20, GAPopulationSortOrder::HighIsBest, &mut rng_ctx);
+        assert!(pop.individuals().iter().all(|g| g.depth() <= 4));
+        assert!(pop.individuals().iter().any(|g| g.depth() == 4));
+
+        let mut ga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                   d_seed : [1; 4],
+                                                   flags : DEBUG_FLAG,
+                                                   max_generations: 30,
+                                                   population_size: 60,
+                                                   probability_crossover: 0.9,
+                                                   probability_mutation: 0.1,
+                                                   elitism: true,
+                                                   ..Default::default()
+                                                 },
+                                                 Some(&mut factory as &mut dyn GAFactory<GATreeGenome<Expr>>),
+                                                 None);
+        ga.initialize().unwrap();
+        let initial_best = ga.population().best_by_raw_score().raw();
+        while !ga.done()
+        {
+            ga.step().unwrap();
+        }
+        let best = ga.population().best_by_raw_score();
+        assert!(is_tree(best));
+        assert!(best.raw() >= initial_best);
+        ga_test_teardown();
+    }
+
+    #[test]
+    fn compare()
+    {
+        ga_test_setup("ga_tree::compare");
+        // (x * x) + -(1)
+        let a = GATreeGenome::new(vec![Expr::Add, Expr::Mul, Expr::X, Expr::X, Expr::Neg, Expr::One], 5, objective());
+        assert_eq!(a.compare(&a.clone()), 0.0);
+
+        // (x * 1) + -(1): one node of each tree doesn't match.
+        let b = GATreeGenome::new(vec![Expr::Add, Expr::Mul, Expr::X, Expr::One, Expr::Neg, Expr::One], 5, objective());
+        assert_eq!(a.compare(&b), 2.0 / 12.0);
+
+        // (x * x) + x: the right subtrees (2 nodes and 1 node) don't match.
+        let c = GATreeGenome::new(vec![Expr::Add, Expr::Mul, Expr::X, Expr::X, Expr::X], 5, objective());
+        assert_eq!(a.compare(&c), 3.0 / 11.0);
+        assert_eq!(c.compare(&a), a.compare(&c));
+
+        // Different roots: nothing matches.
+        let d = GATreeGenome::new(vec![Expr::Mul, Expr::X, Expr::X], 5, objective());
+        assert_eq!(a.compare(&d), 1.0);
+        ga_test_teardown();
+    }
+}
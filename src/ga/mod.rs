@@ -1,11 +1,23 @@
 // Copyright 2016 Revolution Solid & Contributors.
 // author(s): sysnett
 // rust-monster is licensed under a MIT License.
+pub mod ga_array;
+pub mod ga_bin2dec;
+pub mod ga_binary_string;
 pub mod ga_core;
+pub mod ga_deme;
+pub mod ga_incremental;
+pub mod ga_list;
+pub mod ga_permutation;
 pub mod ga_population;
 pub mod ga_random;
+pub mod ga_real;
+pub mod ga_replacement;
 pub mod ga_scaling;
 pub mod ga_simple;
 pub mod ga_selectors;
 pub mod ga_statistics;
+pub mod ga_steady_state;
+pub mod ga_terminators;
 pub mod ga_test;
+pub mod ga_tree;
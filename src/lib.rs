@@ -11,5 +11,10 @@ extern crate log;
 
 extern crate rand;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 // Published Modules
 pub mod ga;
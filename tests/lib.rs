@@ -15,10 +15,11 @@ mod tests
     use rust_monster::ga::ga_simple::*;
     use rust_monster::ga::ga_population::*;
     use rust_monster::ga::ga_core::*;
+    use rust_monster::ga::ga_permutation::*;
 
     use std::cmp::min;
-    use std::any::Any;
     use std::f64;
+    use std::sync::Arc;
 
     struct TSPEvaluationCtx
     {
@@ -40,6 +41,20 @@ mod tests
             }
             TSPEvaluationCtx { cities: cities }
         }
+
+        // Length of the closed tour visiting the cities in 'order'.
+        pub fn tour_length(&self, order: &[usize]) -> f32
+        {
+            let n = order.len();
+            let mut cost = 0.0;
+            for i in 0..n
+            {
+                let c1 = self.cities[order[i]];
+                let c2 = self.cities[order[(i + 1) % n]];
+                cost += ((c1.0 - c2.0) * (c1.0 - c2.0) + (c1.1 - c2.1) * (c1.1 - c2.1)).sqrt();
+            }
+            cost as f32
+        }
     }
 
     #[derive(Clone)]
@@ -69,117 +84,86 @@ mod tests
         {
             TSPIndividual{raw: 0.0, fitness: 0.0, inxes: inxes}
         }
+
+        fn tour_cost(&self, tsp_eval_ctx: &TSPEvaluationCtx) -> f32
+        {
+            tsp_eval_ctx.tour_length(&self.inxes)
+        }
     }
     impl GAIndividual for TSPIndividual
     {
+        type EvaluationCtx = TSPEvaluationCtx;
+
         // Crossing over a permutation isn't as simple as one might think
         // algorithm inspired in: http://www.permutationcity.co.uk/projects/mutants/tsp.html
-        fn crossover(&self, other: &TSPIndividual, ctx: &mut Any) -> Box<TSPIndividual>
+        fn crossover(&self, other: &TSPIndividual, rng_ctx: &mut GARandomCtx) -> Box<TSPIndividual>
         {
-            match ctx.downcast_mut::<GARandomCtx>()
+            let to_pick = min(self.inxes.len(), 3);
+            let mut new_inxes = vec![];
+
+            // Copy the first parent
+            for i in 0..self.inxes.len()
+            {
+                new_inxes.push(self.inxes[i]);
+            }
+
+            let mut picked = vec![]; //This are indexes
+            for _ in 0..to_pick
             {
-                Some(rng_ctx) =>
+                picked.push(rng_ctx.gen_range(0, self.inxes.len()));
+            }
+
+            let mut places: [usize; 3] = [0, 0, 0];
+            for pi in 0..picked.len()
+            {
+                for oi in 0..other.inxes.len()
                 {
-                    let to_pick = min(self.inxes.len(), 3);
-                    let mut new_inxes = vec![];
-        
-                    // Copy the first parent
-                    for i in 0..self.inxes.len()
-                    {
-                        new_inxes.push(self.inxes[i]);
-                    }
-        
-                    let mut picked = vec![]; //This are indexes
-                    for _ in 0..to_pick
-                    {
-                        picked.push(rng_ctx.gen_range(0, self.inxes.len()));
-                    }
-        
-                    let mut places: [usize; 3] = [0, 0, 0];
-                    for pi in 0..picked.len()
-                    {
-                        for oi in 0..other.inxes.len()
-                        {
-                            if self.inxes[picked[pi]] == other.inxes[oi]
-                            {
-                                places[pi] = oi;
-                                break;
-                            }
-                        }
-                    }
-        
-                    for pi in 0..picked.len()
+                    if self.inxes[picked[pi]] == other.inxes[oi]
                     {
-                        let temp = new_inxes[picked[pi]];
-                        new_inxes[picked[pi]] = new_inxes[places[pi]];
-                        new_inxes[places[pi]] = temp;
+                        places[pi] = oi;
+                        break;
                     }
-        
-                    Box::new(TSPIndividual::new_from_inxes(new_inxes))
-                },
-                None =>
-                {
-                    panic!("Incorrect type passed for context");
                 }
             }
+
+            for pi in 0..picked.len()
+            {
+                let temp = new_inxes[picked[pi]];
+                new_inxes[picked[pi]] = new_inxes[places[pi]];
+                new_inxes[places[pi]] = temp;
+            }
+
+            Box::new(TSPIndividual::new_from_inxes(new_inxes))
         }
 
-        fn mutate(&mut self, probability: f32, ctx: &mut Any)
+        fn mutate(&mut self, probability: f32, rng: &mut GARandomCtx) -> usize
         {
-            match ctx.downcast_mut::<GARandomCtx>()
+            if rng.test_value(probability)
             {
-                Some(rng) =>
-                {
-                    if rng.test_value(probability)
-                    {
-                        let p1 = rng.gen_range(0, self.inxes.len());
-                        let mut p2 = p1;
-        
-                        while p1 == p2
-                        {
-                            p2 = rng.gen_range(0, self.inxes.len());
-                        }
-        
-                        let tmp = self.inxes[p1];
-                        self.inxes[p1] = self.inxes[p2];
-                        self.inxes[p2] = tmp;
-                    }
-                },
-                None =>
+                let p1 = rng.gen_range(0, self.inxes.len());
+                let mut p2 = p1;
+
+                while p1 == p2
                 {
-                    panic!("Incorrect context");
+                    p2 = rng.gen_range(0, self.inxes.len());
                 }
+
+                let tmp = self.inxes[p1];
+                self.inxes[p1] = self.inxes[p2];
+                self.inxes[p2] = tmp;
+                1
+            }
+            else
+            {
+                0
             }
         }
 
-        fn evaluate(&mut self, evaluation_ctx: &mut Any)
+        fn evaluate(&mut self, evaluation_ctx: &mut TSPEvaluationCtx)
         {
-
-            match evaluation_ctx.downcast_mut::<TSPEvaluationCtx>()
-            {
-               Some(as_tsp_eval_ctx) =>
-               {
-                   let mut cost = 0.0;
-                   for i in 0..self.inxes.len()-1
-                   {
-                      let j = i + 1;
-                      let c1 = as_tsp_eval_ctx.cities[self.inxes[i]];
-                      let c2 = as_tsp_eval_ctx.cities[self.inxes[j]];
-
-                      cost += ((c1.0 - c2.0) * (c1.0 - c2.0) + (c1.1 - c2.1) * (c1.1 - c2.1)).sqrt();
-                   }
-                   let c1 = as_tsp_eval_ctx.cities[self.inxes[0]];
-                   let c2 = as_tsp_eval_ctx.cities[self.inxes[self.inxes.len() - 1]];
-                   cost += ((c1.0 - c2.0) * (c1.0 - c2.0) + (c1.1 - c2.1) * (c1.1 - c2.1)).sqrt();
-                   self.set_raw((cost as f32));
-                   self.set_fitness((cost as f32));
-               }
-               None =>
-               {
-                   // TODO: Better error messaging, print types
-                   panic!("Incorrect type of context passed"); 
-               }
-            }
+            let cost = self.tour_cost(evaluation_ctx);
+            self.set_raw(cost);
+            self.set_fitness(cost);
         }
 
         fn fitness(&self) -> f32 { self.fitness }
@@ -188,6 +172,16 @@ mod tests
         fn set_raw(&mut self, raw: f32) { self.raw = raw; }
     }
 
+    impl GAParallelIndividual for TSPIndividual
+    {
+        fn evaluate_shared(&mut self, evaluation_ctx: &TSPEvaluationCtx)
+        {
+            let cost = self.tour_cost(evaluation_ctx);
+            self.set_raw(cost);
+            self.set_fitness(cost);
+        }
+    }
+
     struct TSPIndividualFactory
     {
         tsp_size: usize,
@@ -239,16 +233,95 @@ mod tests
                                                               },
                                                               Some(&mut ind_factory),
                                                               None,
-                                                              Some(&mut evaluation_ctx as &mut Any),
+                                                              &mut evaluation_ctx
                                                  );
-        sga.initialize();
+        sga.initialize().unwrap();
 
         while !sga.done()
         {
-            let gen = sga.step();
+            let gen = sga.step().unwrap();
             debug!("Generation #{} {:?} {:?}", gen,
-                   sga.population().individual(0, GAPopulationSortBasis::Raw).raw(),
-                   sga.population().individual(0, GAPopulationSortBasis::Raw).inxes);
+                   sga.population().individual(0, GAPopulationSortBasis::Raw).unwrap().raw(),
+                   sga.population().individual(0, GAPopulationSortBasis::Raw).unwrap().inxes);
         }
     }
+
+    #[test]
+    fn tsp_parallel_evaluation_test()
+    {
+        let _ = env_logger::init();
+        let tsp_size = 30;
+        let evaluation_ctx = TSPEvaluationCtx::new(tsp_size);
+
+        // Same run regardless of the number of evaluation threads.
+        let run = |workers: usize|
+        {
+            let mut ind_factory = TSPIndividualFactory::new(tsp_size);
+            let mut sga = SimpleGeneticAlgorithm::new_with_shared_eval_ctx(SimpleGeneticAlgorithmCfg {
+                                                                             d_seed : [1,0,1,0],
+                                                                             flags : DEBUG_FLAG,
+                                                                             max_generations: 50,
+                                                                             population_size: 100,
+                                                                             probability_crossover: 0.9,
+                                                                             probability_mutation: 0.15,
+                                                                             population_sort_order: GAPopulationSortOrder::LowIsBest,
+                                                                             elitism: true,
+                                                                             evaluation_workers: workers,
+                                                                             ..Default::default()
+                                                                           },
+                                                                           Some(&mut ind_factory),
+                                                                           None,
+                                                                           &evaluation_ctx);
+            sga.initialize().unwrap();
+            while !sga.done()
+            {
+                sga.step().unwrap();
+            }
+
+            let best = sga.population().individual(0, GAPopulationSortBasis::Raw).unwrap();
+            debug!("{} workers: {:?} {:?}", workers, best.raw(), best.inxes);
+            (best.raw(), best.inxes.clone())
+        };
+
+        let sequential = run(1);
+        assert_eq!(sequential, run(4));
+        assert_eq!(sequential, run(7));
+    }
+
+    #[test]
+    fn tsp_permutation_genome_test()
+    {
+        let _ = env_logger::init();
+        let tsp_size = 30;
+        let evaluation_ctx = Arc::new(TSPEvaluationCtx::new(tsp_size));
+
+        let mut ind_factory = GAPermutationFactory::new(tsp_size, move |order: &[usize]| evaluation_ctx.tour_length(order));
+        ind_factory.set_crossover_operator(GAPermutationCrossover::EdgeRecombination);
+        ind_factory.set_mutation_operator(GAPermutationMutation::Inversion);
+
+        let mut sga = SimpleGeneticAlgorithm::new(SimpleGeneticAlgorithmCfg {
+                                                    d_seed : [1,0,1,0],
+                                                    flags : DEBUG_FLAG,
+                                                    max_generations: 100,
+                                                    population_size: 100,
+                                                    probability_crossover: 0.9,
+                                                    probability_mutation: 0.15,
+                                                    population_sort_order: GAPopulationSortOrder::LowIsBest,
+                                                    elitism: true,
+                                                    ..Default::default()
+                                                  },
+                                                  Some(&mut ind_factory),
+                                                  None);
+        sga.initialize().unwrap();
+        let initial_best = sga.population().individual(0, GAPopulationSortBasis::Raw).unwrap().raw();
+
+        while !sga.done()
+        {
+            let gen = sga.step().unwrap();
+            debug!("Generation #{} {:?}", gen, sga.population().individual(0, GAPopulationSortBasis::Raw));
+        }
+
+        let best = sga.population().individual(0, GAPopulationSortBasis::Raw).unwrap().raw();
+        assert!(best < initial_best);
+    }
 }